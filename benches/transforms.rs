@@ -0,0 +1,44 @@
+use std::hint::black_box;
+
+use cgmath::{Point3, Vector3};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use render::transforms;
+
+// each benchmark is one call, so criterion's throughput is reported in calls per second
+fn bench_transforms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transforms");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("create_transforms", |b| {
+        b.iter(|| transforms::create_transforms(black_box([1.0, 2.0, 3.0]), black_box([0.3, 0.6, 0.9]), black_box([1.0, 2.0, 1.0])))
+    });
+
+    group.bench_function("create_view_projection", |b| {
+        b.iter(|| {
+            transforms::create_view_projection(
+                black_box(Point3::new(3.0, 1.5, 3.0)),
+                black_box(Point3::new(0.0, 0.0, 0.0)),
+                black_box(Vector3::unit_y()),
+                black_box(1.5),
+                black_box(true),
+            )
+        })
+    });
+
+    let (_, _, view_projection) = transforms::create_view_projection(
+        Point3::new(3.0, 1.5, 3.0), Point3::new(0.0, 0.0, 0.0), Vector3::unit_y(), 1.5, true);
+
+    group.bench_function("frustum_planes", |b| {
+        b.iter(|| transforms::frustum_planes(black_box(view_projection)))
+    });
+
+    let planes = transforms::frustum_planes(view_projection);
+    group.bench_function("frustum_contains_sphere", |b| {
+        b.iter(|| transforms::frustum_contains_sphere(black_box(&planes), black_box(Point3::new(1.0, 2.0, -3.0)), black_box(1.75)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_transforms);
+criterion_main!(benches);
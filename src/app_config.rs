@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use ron::{extensions::Extensions, ser::PrettyConfig};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+// where the settings are kept unless --config names another file
+pub const DEFAULT_CONFIG_FILE: &str = "config.ron";
+
+// names the backends and present modes are written with in the file
+const BACKEND_NAMES: [(&str, wgpu::Backends); 6] = [
+    ("all", wgpu::Backends::all()),
+    ("primary", wgpu::Backends::PRIMARY),
+    ("vulkan", wgpu::Backends::VULKAN),
+    ("metal", wgpu::Backends::METAL),
+    ("dx12", wgpu::Backends::DX12),
+    ("gl", wgpu::Backends::GL),
+];
+
+const PRESENT_MODE_NAMES: [(&str, wgpu::PresentMode); 6] = [
+    ("auto_vsync", wgpu::PresentMode::AutoVsync),
+    ("auto_no_vsync", wgpu::PresentMode::AutoNoVsync),
+    ("fifo", wgpu::PresentMode::Fifo),
+    ("fifo_relaxed", wgpu::PresentMode::FifoRelaxed),
+    ("immediate", wgpu::PresentMode::Immediate),
+    ("mailbox", wgpu::PresentMode::Mailbox),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraConfig {
+    pub position: [f32; 3],
+    pub target: [f32; 3],
+}
+
+// the settings kept between runs, a ron struct like
+// (backend: "vulkan", present_mode: "mailbox", msaa_samples: 4, clear_color: (0.1, 0.2, 0.3, 1.0),
+//  perspective: true, fovy_degrees: 45.0, model: "scene.glb",
+//  camera: (position: (3.0, 1.5, 3.0), target: (0.0, 0.0, 0.0)))
+// every field is optional, a missing one keeps the built in default. the file is read and written
+// with ron's implicit_some, so set fields need no Some(..) around them
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    #[serde(rename = "backend", with = "backend_name", skip_serializing_if = "Option::is_none")]
+    pub backends: Option<wgpu::Backends>,
+    #[serde(with = "present_mode_name", skip_serializing_if = "Option::is_none")]
+    pub present_mode: Option<wgpu::PresentMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msaa_samples: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear_color: Option<[f64; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perspective: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fovy_degrees: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraConfig>,
+}
+
+impl AppConfig {
+    // a broken file never stops the app from starting: a missing, unreadable or unparseable file gives
+    // the defaults, an unknown field or a value of the wrong type being a parse error. fields that parse
+    // but are out of range are reported and dropped while the valid ones are still used
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return AppConfig::default(),
+            Err(e) => {
+                log::warn!("could not read {}, using the default settings: {}", path.display(), e);
+                return AppConfig::default();
+            }
+        };
+
+        match ron_options().from_str::<AppConfig>(&text) {
+            Ok(config) => config.validated(path),
+            Err(e) => {
+                log::warn!("could not parse {}, using the default settings: {}", path.display(), e);
+                AppConfig::default()
+            }
+        }
+    }
+
+    // only the fields that are set are written, so defaults can still change between versions
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let text = ron_options().to_string_pretty(self, PrettyConfig::new().extensions(Extensions::IMPLICIT_SOME))?;
+        std::fs::write(path, text + "\n")?;
+        Ok(())
+    }
+
+    fn validated(mut self, path: &Path) -> Self {
+        self.msaa_samples = self.msaa_samples
+            .filter(|samples| check(path, "msaa_samples", samples, [1, 2, 4, 8, 16].contains(samples), "is not 1, 2, 4, 8 or 16"));
+        self.clear_color = self.clear_color
+            .filter(|color| check(path, "clear_color", color, color.iter().all(|c| (0.0..=1.0).contains(c)), "has a component outside 0 to 1"));
+        self.fovy_degrees = self.fovy_degrees
+            .filter(|fovy| check(path, "fovy_degrees", fovy, *fovy > 0.0 && *fovy < 180.0, "is not between 0 and 180"));
+        self
+    }
+}
+
+fn ron_options() -> ron::Options {
+    ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME)
+}
+
+// reports an invalid field, which is then dropped
+fn check<T: std::fmt::Debug>(path: &Path, name: &str, value: T, valid: bool, problem: &str) -> bool {
+    if !valid {
+        log::warn!("{}: ignoring \"{}\": {:?} {}", path.display(), name, value, problem);
+    }
+    valid
+}
+
+// backends and present modes are written by their names in BACKEND_NAMES and PRESENT_MODE_NAMES
+mod backend_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(backends: &Option<wgpu::Backends>, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_name(backends, &BACKEND_NAMES, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<wgpu::Backends>, D::Error> {
+        deserialize_name(deserializer, &BACKEND_NAMES)
+    }
+}
+
+mod present_mode_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(mode: &Option<wgpu::PresentMode>, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_name(mode, &PRESENT_MODE_NAMES, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<wgpu::PresentMode>, D::Error> {
+        deserialize_name(deserializer, &PRESENT_MODE_NAMES)
+    }
+}
+
+fn serialize_name<T: Copy + PartialEq + std::fmt::Debug, S: Serializer>(item: &Option<T>, names: &[(&str, T)],
+    serializer: S) -> Result<S::Ok, S::Error> {
+    match item {
+        Some(item) => match names.iter().find(|&&(_, known)| known == *item) {
+            Some((name, _)) => serializer.serialize_some(name),
+            None => Err(serde::ser::Error::custom(format!("{:?} has no name in the config file", item))),
+        },
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_name<'de, T: Copy, D: Deserializer<'de>>(deserializer: D, names: &[(&str, T)]) -> Result<Option<T>, D::Error> {
+    let Some(name) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    names
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|&(_, item)| Some(item))
+        .ok_or_else(|| {
+            let known: Vec<&str> = names.iter().map(|(known, _)| *known).collect();
+            serde::de::Error::custom(format!("\"{}\" is not one of {}", name, known.join(", ")))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_config_loads_back_and_drops_invalid_fields() {
+        let path = std::env::temp_dir().join(format!("render_app_config_{}.ron", std::process::id()));
+        let config = AppConfig {
+            backends: Some(wgpu::Backends::VULKAN),
+            present_mode: Some(wgpu::PresentMode::Mailbox),
+            msaa_samples: Some(4),
+            clear_color: Some([0.1, 0.2, 0.3, 1.0]),
+            fovy_degrees: Some(45.0),
+            camera: Some(CameraConfig { position: [3.0, 1.5, 3.0], target: [0.0; 3] }),
+            ..Default::default()
+        };
+        config.save(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("backend: \"vulkan\","), "{}", text);
+        assert!(text.contains("msaa_samples: 4,"), "{}", text);
+        assert!(!text.contains("model"), "{}", text);
+        assert_eq!(AppConfig::load(&path), config);
+
+        // out of range fields are dropped, the rest are kept
+        std::fs::write(&path, "(msaa_samples: 3, fovy_degrees: 60.0, backend: \"gl\")").unwrap();
+        let loaded = AppConfig::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, AppConfig { backends: Some(wgpu::Backends::GL), fovy_degrees: Some(60.0), ..Default::default() });
+    }
+}
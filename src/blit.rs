@@ -0,0 +1,152 @@
+// copies a texture onto a full render target with a fullscreen triangle, scaling it in the process
+pub struct Blit {
+    pub pipeline: wgpu::RenderPipeline,
+    // averages every source texel under each target pixel instead of sampling, for supersampling
+    pub downsample_pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub linear_sampler: wgpu::Sampler,
+    pub nearest_sampler: wgpu::Sampler,
+}
+
+// a sampler is baked into the bind group it was created with, so changing the filter means
+// binding the group built with the other sampler; replacing the sampler alone has no effect
+pub struct BlitBindGroups {
+    pub linear: wgpu::BindGroup,
+    pub nearest: wgpu::BindGroup,
+}
+
+impl BlitBindGroups {
+    pub fn get(&self, filter: wgpu::FilterMode) -> &wgpu::BindGroup {
+        match filter {
+            wgpu::FilterMode::Linear => &self.linear,
+            wgpu::FilterMode::Nearest => &self.nearest,
+        }
+    }
+}
+
+impl Blit {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("Blit Bind Group Layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blit Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, entry_point| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point,
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let linear_sampler = create_sampler(device, wgpu::FilterMode::Linear);
+        let nearest_sampler = create_sampler(device, wgpu::FilterMode::Nearest);
+
+        Blit {
+            pipeline: create_pipeline("Blit Pipeline", "fs_main"),
+            downsample_pipeline: create_pipeline("Downsample Blit Pipeline", "fs_downsample"),
+            bind_group_layout,
+            linear_sampler,
+            nearest_sampler,
+        }
+    }
+
+    pub fn bind_groups(&self, device: &wgpu::Device, view: &wgpu::TextureView) -> BlitBindGroups {
+        BlitBindGroups {
+            linear: self.bind_group(device, view, &self.linear_sampler),
+            nearest: self.bind_group(device, view, &self.nearest_sampler),
+        }
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, view: &wgpu::TextureView, sampler: &wgpu::Sampler) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+            label: Some("Blit Bind Group"),
+        })
+    }
+
+    // downsample box filters a supersampled source, otherwise it is sampled through the bind group's filter
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, bind_group: &wgpu::BindGroup, downsample: bool) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..Default::default()
+        });
+
+        pass.set_pipeline(if downsample { &self.downsample_pipeline } else { &self.pipeline });
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_sampler(device: &wgpu::Device, filter: wgpu::FilterMode) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Blit Sampler"),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        mag_filter: filter,
+        min_filter: filter,
+        ..Default::default()
+    })
+}
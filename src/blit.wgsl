@@ -0,0 +1,46 @@
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) uv : vec2<f32>,
+};
+
+// a single triangle covering the whole screen, uv spans [0, 1] over the visible part
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32) -> Output {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var output: Output;
+    output.Position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    output.uv = uv;
+    return output;
+}
+
+@binding(0) @group(0) var sceneTexture : texture_2d<f32>;
+@binding(1) @group(0) var sceneSampler : sampler;
+
+@fragment
+fn fs_main(@location(0) uv : vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(sceneTexture, sceneSampler, uv);
+}
+
+// the widest box fs_downsample averages, the largest supersampling factor
+const MAX_FOOTPRINT : i32 = 4;
+
+// box filtered downsampling for supersampling: the average of every texel under the output pixel.
+// bilinear sampling only reaches the 2x2 texels around the sample point, which drops most of a 4x
+// target. the footprint comes from how far uv moves per output pixel, which is constant over the
+// fullscreen triangle
+@fragment
+fn fs_downsample(@location(0) uv : vec2<f32>) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(sceneTexture));
+    let footprint = clamp(vec2<i32>(round(fwidth(uv) * size)), vec2<i32>(1), vec2<i32>(MAX_FOOTPRINT));
+    let first = vec2<i32>(floor(uv * size - vec2<f32>(footprint) * 0.5 + 0.5));
+    let last = vec2<i32>(size) - 1;
+
+    var sum = vec4<f32>(0.0);
+    for (var y = 0; y < footprint.y; y++) {
+        for (var x = 0; x < footprint.x; x++) {
+            sum += textureLoad(sceneTexture, clamp(first + vec2<i32>(x, y), vec2<i32>(0), last), 0);
+        }
+    }
+    return sum / f32(footprint.x * footprint.y);
+}
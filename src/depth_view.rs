@@ -0,0 +1,177 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{resource_tracker::ResourceTracker, transforms::DepthMode};
+
+// what the depth view shows in place of the scene
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepthViewMode {
+    Off,
+    Grayscale,
+    // the turbo color ramp, small depth differences are much easier to tell apart than in gray
+    Ramp,
+}
+
+impl DepthViewMode {
+    pub fn next(self) -> Self {
+        match self {
+            DepthViewMode::Off => DepthViewMode::Grayscale,
+            DepthViewMode::Grayscale => DepthViewMode::Ramp,
+            DepthViewMode::Ramp => DepthViewMode::Off,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct DepthViewUniforms {
+    near: f32,
+    far: f32,
+    range: f32,
+    flags: u32,
+}
+
+unsafe impl Pod for DepthViewUniforms {}
+unsafe impl Zeroable for DepthViewUniforms {}
+
+const REVERSED_FLAG: u32 = 1;
+const RAMP_FLAG: u32 = 2;
+
+// draws the scene's depth buffer linearized back to view distance, as gray or through a color ramp.
+// needs the depth pass to store depth and the depth texture to be bindable, see render_target
+pub struct DepthView {
+    pub pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub uniform_buffer: wgpu::Buffer,
+}
+
+impl DepthView {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Depth View Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("depth_view.wgsl").into()),
+        });
+
+        let uniform_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Depth View Uniform Buffer"),
+            size: std::mem::size_of::<DepthViewUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // a multisampled depth buffer is read through a different texture type and entry point
+        let multisampled = sample_count > 1;
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Depth View Bind Group Layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Depth View Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Depth View Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: if multisampled { "fs_multisampled" } else { "fs_main" },
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        DepthView {
+            pipeline,
+            bind_group_layout,
+            uniform_buffer,
+        }
+    }
+
+    // rebuilt with the render target, like the blit's bind groups
+    pub fn bind_group(&self, device: &wgpu::Device, depth_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+            ],
+            label: Some("Depth View Bind Group"),
+        })
+    }
+
+    // near and far of the perspective projection the depth was written with; range is the view distance
+    // drawn at the end of the ramp
+    pub fn write(&self, queue: &wgpu::Queue, near: f32, far: f32, range: f32, depth_mode: DepthMode, mode: DepthViewMode) {
+        let mut flags = 0;
+        if depth_mode == DepthMode::Reversed {
+            flags |= REVERSED_FLAG;
+        }
+        if mode == DepthViewMode::Ramp {
+            flags |= RAMP_FLAG;
+        }
+
+        let uniforms = DepthViewUniforms { near, far, range, flags };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, bind_group: &wgpu::BindGroup) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Depth View Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..Default::default()
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,75 @@
+// matches DepthViewUniforms in depth_view.rs
+struct Settings {
+    near : f32,
+    far : f32,
+    // view distance shown as the far end of the ramp, the full far plane would squeeze the scene into
+    // the first few percent
+    range : f32,
+    // bit 0: reversed z, bit 1: color ramp instead of grayscale
+    flags : u32,
+};
+@binding(0) @group(0) var<uniform> settings : Settings;
+@binding(1) @group(0) var depthTexture : texture_depth_2d;
+@binding(1) @group(0) var depthTextureMultisampled : texture_depth_multisampled_2d;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) uv : vec2<f32>,
+};
+
+// the blit's fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32) -> Output {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var output: Output;
+    output.Position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    output.uv = uv;
+    return output;
+}
+
+// google's polynomial fit of the turbo colormap, close to the original lookup table without one
+fn turbo(x : f32) -> vec3<f32> {
+    let r4 = vec4<f32>(0.13572138, 4.61539260, -42.66032258, 132.13108234);
+    let g4 = vec4<f32>(0.09140261, 2.19418839, 4.84296658, -14.18503333);
+    let b4 = vec4<f32>(0.10667330, 12.64194608, -60.58204836, 110.36276771);
+    let r2 = vec2<f32>(-152.94239396, 59.28637943);
+    let g2 = vec2<f32>(4.27729857, 2.82956604);
+    let b2 = vec2<f32>(-89.90310912, 27.34824973);
+
+    let t = saturate(x);
+    let v4 = vec4<f32>(1.0, t, t * t, t * t * t);
+    let v2 = v4.zw * v4.z;
+    return vec3<f32>(dot(v4, r4) + dot(v2, r2), dot(v4, g4) + dot(v2, g2), dot(v4, b4) + dot(v2, b2));
+}
+
+// depth buffer value back to view distance, then to [0, 1] over the shown range
+fn visualize(stored : f32) -> vec4<f32> {
+    var depth = stored;
+    if ((settings.flags & 1u) != 0u) {
+        depth = 1.0 - depth;
+    }
+
+    let distance = settings.near * settings.far / (settings.far - depth * (settings.far - settings.near));
+    let t = saturate((distance - settings.near) / (settings.range - settings.near));
+
+    if ((settings.flags & 2u) != 0u) {
+        return vec4<f32>(turbo(t), 1.0);
+    }
+    return vec4<f32>(vec3<f32>(t), 1.0);
+}
+
+@fragment
+fn fs_main(in : Output) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(depthTexture));
+    let texel = vec2<i32>(min(in.uv * size, size - 1.0));
+    return visualize(textureLoad(depthTexture, texel, 0));
+}
+
+// with msaa the depth buffer is multisampled, show its first sample
+@fragment
+fn fs_multisampled(in : Output) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(depthTextureMultisampled));
+    let texel = vec2<i32>(min(in.uv * size, size - 1.0));
+    return visualize(textureLoad(depthTextureMultisampled, texel, 0));
+}
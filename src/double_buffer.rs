@@ -0,0 +1,38 @@
+use crate::resource_tracker::ResourceTracker;
+
+// two buffers of the same description used in turn: every write goes to the one the last frame did not
+// read, and draws use the one written last. needed when the cpu writes through a mapping (map_async on a
+// buffer the gpu is still reading waits for that frame) or when frames are recorded ahead and each
+// must keep the data it was recorded with. queue.write_buffer on its own stages the data and orders the
+// copy after earlier submissions, so a single buffer is already correct there and the second one only
+// saves the copy waiting on the previous frame's reads; for small per-frame updates that is overkill
+pub struct DoubleBuffer {
+    pub buffers: [wgpu::Buffer; 2],
+    pub current: usize,
+}
+
+impl DoubleBuffer {
+    // COPY_DST is added to the usage, writes need it
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, descriptor: &wgpu::BufferDescriptor) -> Self {
+        let descriptor = wgpu::BufferDescriptor {
+            usage: descriptor.usage | wgpu::BufferUsages::COPY_DST,
+            ..*descriptor
+        };
+
+        DoubleBuffer {
+            buffers: [tracker.create_buffer(device, &descriptor), tracker.create_buffer(device, &descriptor)],
+            current: 0,
+        }
+    }
+
+    // switches to the other buffer and writes data to it from the start
+    pub fn write(&mut self, queue: &wgpu::Queue, data: &[u8]) {
+        self.current = 1 - self.current;
+        queue.write_buffer(&self.buffers[self.current], 0, data);
+    }
+
+    // the buffer written last, the one to draw from
+    pub fn current(&self) -> &wgpu::Buffer {
+        &self.buffers[self.current]
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+// the error of everything that can fail on the way to a frame: startup, presenting, readback and
+// writing files. model loading still goes through anyhow since gltf failures are only ever logged
+#[derive(Debug)]
+pub enum Error {
+    CreateSurface(wgpu::CreateSurfaceError),
+    // no adapter for the enabled backends can present to the window
+    NoAdapter,
+    // the surface reports no texture format or no alpha mode it can be configured with, seen on some
+    // virtual displays and remote sessions
+    NoSurfaceFormat,
+    NoAlphaMode,
+    RequestDevice(wgpu::RequestDeviceError),
+    Surface(wgpu::SurfaceError),
+    BufferAsync(wgpu::BufferAsyncError),
+    Image(image::ImageError),
+    Io(std::io::Error),
+    Ron(ron::Error),
+    EventLoop(winit::error::EventLoopError),
+    Window(winit::error::OsError),
+    // a request the current setup can't serve, like reading back a target in an unsupported format
+    Unsupported(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CreateSurface(e) => write!(f, "could not create the surface: {}", e),
+            Error::NoAdapter => write!(f, "no appropriate adapter, run with --list-adapters to see the available ones"),
+            Error::NoSurfaceFormat => write!(f, "the surface supports no texture format, the display may be virtual or remote"),
+            Error::NoAlphaMode => write!(f, "the surface supports no alpha mode, the display may be virtual or remote"),
+            Error::RequestDevice(e) => write!(f, "could not create the device: {}", e),
+            Error::Surface(e) => write!(f, "surface error: {}", e),
+            Error::BufferAsync(e) => write!(f, "could not map a buffer: {}", e),
+            Error::Image(e) => write!(f, "image error: {}", e),
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Ron(e) => write!(f, "ron error: {}", e),
+            Error::EventLoop(e) => write!(f, "event loop error: {}", e),
+            Error::Window(e) => write!(f, "could not create the window: {}", e),
+            Error::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::CreateSurface(e) => Some(e),
+            Error::RequestDevice(e) => Some(e),
+            Error::Surface(e) => Some(e),
+            Error::BufferAsync(e) => Some(e),
+            Error::Image(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Ron(e) => Some(e),
+            Error::EventLoop(e) => Some(e),
+            Error::Window(e) => Some(e),
+            Error::NoAdapter | Error::NoSurfaceFormat | Error::NoAlphaMode | Error::Unsupported(_) => None,
+        }
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for Error {
+    fn from(e: wgpu::CreateSurfaceError) -> Self {
+        Error::CreateSurface(e)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        Error::RequestDevice(e)
+    }
+}
+
+impl From<wgpu::SurfaceError> for Error {
+    fn from(e: wgpu::SurfaceError) -> Self {
+        Error::Surface(e)
+    }
+}
+
+impl From<wgpu::BufferAsyncError> for Error {
+    fn from(e: wgpu::BufferAsyncError) -> Self {
+        Error::BufferAsync(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ron::Error> for Error {
+    fn from(e: ron::Error) -> Self {
+        Error::Ron(e)
+    }
+}
+
+impl From<winit::error::EventLoopError> for Error {
+    fn from(e: winit::error::EventLoopError) -> Self {
+        Error::EventLoop(e)
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(e: winit::error::OsError) -> Self {
+        Error::Window(e)
+    }
+}
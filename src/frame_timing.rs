@@ -0,0 +1,194 @@
+use std::{cell::Cell, collections::VecDeque, sync::{atomic::{AtomicU8, Ordering}, Arc}, time::Duration};
+
+use crate::resource_tracker::ResourceTracker;
+
+// frames the cpu and gpu times are averaged over, enough to smooth out single slow frames
+pub const TIMING_WINDOW: usize = 60;
+
+// the readback's map_async result, shared with its callback
+const MAP_PENDING: u8 = 0;
+const MAP_DONE: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+// running average of the last TIMING_WINDOW durations, in milliseconds
+#[derive(Default)]
+pub struct TimingWindow {
+    samples: VecDeque<f32>,
+}
+
+impl TimingWindow {
+    pub fn push(&mut self, milliseconds: f32) {
+        if self.samples.len() == TIMING_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(milliseconds);
+    }
+
+    pub fn average(&self) -> Option<f32> {
+        (!self.samples.is_empty()).then(|| self.samples.iter().sum::<f32>() / self.samples.len() as f32)
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().copied()
+    }
+}
+
+// what the last rendered frame cost, for the hud, benchmarks and anything else reading it back after
+// the frame. draw_calls and triangles count the scene mesh draws, the helper passes (lines, overlays,
+// blits, text) are left out. cpu_record_time runs from the frame's update to its submit, without the
+// wait for the swapchain image. gpu_time is the newest GpuTimer measurement, which lags a few frames
+// behind and is None without timestamp queries
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub draw_calls: u32,
+    pub triangles: u64,
+    pub cpu_record_time: Duration,
+    pub gpu_time: Option<Duration>,
+}
+
+// adds up draw calls and triangles while a frame is recorded, through the &self draw functions
+#[derive(Default)]
+pub struct DrawCounter {
+    draw_calls: Cell<u32>,
+    triangles: Cell<u64>,
+}
+
+impl DrawCounter {
+    pub fn record(&self, triangles: u64) {
+        self.draw_calls.set(self.draw_calls.get() + 1);
+        self.triangles.set(self.triangles.get() + triangles);
+    }
+
+    // the draw calls and triangles recorded since the last take, starting over from zero
+    pub fn take(&self) -> (u32, u64) {
+        (self.draw_calls.replace(0), self.triangles.replace(0))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ReadbackState {
+    Idle,
+    // the resolved timestamps are copied into the readback buffer by the frame being recorded
+    Copied,
+    Mapping,
+}
+
+// how long the gpu spends on a frame's commands, from timestamps written by an empty compute pass
+// before the frame's work and another after it. reading them back goes through a map_async that
+// finishes some frames later; frames recorded while one is in flight go unmeasured rather than stall.
+// needs the TIMESTAMP_QUERY feature, new returns None without it
+pub struct GpuTimer {
+    pub query_set: wgpu::QuerySet,
+    pub resolve_buffer: wgpu::Buffer,
+    pub readback_buffer: wgpu::Buffer,
+    // nanoseconds per timestamp tick
+    pub period: f32,
+    state: ReadbackState,
+    map_result: Arc<AtomicU8>,
+    pub times: TimingWindow,
+}
+
+impl GpuTimer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let size = 2 * std::mem::size_of::<u64>() as wgpu::BufferAddress;
+        let resolve_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Timestamp Resolve Buffer"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Timestamp Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(GpuTimer {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            state: ReadbackState::Idle,
+            map_result: Arc::new(AtomicU8::new(MAP_PENDING)),
+            times: TimingWindow::default(),
+        })
+    }
+
+    // first thing recorded into the frame's encoder
+    pub fn begin(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.write_timestamp(encoder, 0);
+    }
+
+    // last thing recorded into the frame's encoder, before finish
+    pub fn end(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.write_timestamp(encoder, 1);
+        if self.state == ReadbackState::Idle {
+            encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+            encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, self.readback_buffer.size());
+            self.state = ReadbackState::Copied;
+        }
+    }
+
+    fn write_timestamp(&self, encoder: &mut wgpu::CommandEncoder, index: u32) {
+        encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Timestamp Pass"),
+            timestamp_writes: Some(wgpu::ComputePassTimestampWrites {
+                query_set: &self.query_set,
+                beginning_of_pass_write_index: Some(index),
+                end_of_pass_write_index: None,
+            }),
+        });
+    }
+
+    // call once the frame with end in it is submitted, starts reading its timestamps back
+    pub fn after_submit(&mut self) {
+        if self.state != ReadbackState::Copied {
+            return;
+        }
+
+        self.map_result.store(MAP_PENDING, Ordering::Release);
+        let map_result = Arc::clone(&self.map_result);
+        self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            map_result.store(if result.is_ok() { MAP_DONE } else { MAP_FAILED }, Ordering::Release);
+        });
+        self.state = ReadbackState::Mapping;
+    }
+
+    // picks up a finished readback without waiting for one still in flight
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        if self.state != ReadbackState::Mapping {
+            return;
+        }
+        device.poll(wgpu::Maintain::Poll);
+
+        match self.map_result.load(Ordering::Acquire) {
+            MAP_PENDING => {}
+            MAP_DONE => {
+                let ticks: [u64; 2] = {
+                    let data = self.readback_buffer.slice(..).get_mapped_range();
+                    [0, 1].map(|i| u64::from_le_bytes(data[i * 8..i * 8 + 8].try_into().unwrap()))
+                };
+                self.readback_buffer.unmap();
+                // timestamps may wrap or be reordered on some drivers, such a frame is skipped
+                if ticks[1] >= ticks[0] {
+                    self.times.push((ticks[1] - ticks[0]) as f32 * self.period / 1_000_000.0);
+                }
+                self.state = ReadbackState::Idle;
+            }
+            _ => {
+                log::warn!("could not read the gpu timestamps back");
+                self.state = ReadbackState::Idle;
+            }
+        }
+    }
+}
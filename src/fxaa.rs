@@ -0,0 +1,189 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::resource_tracker::ResourceTracker;
+
+// the quality constants of FXAA 3.11's default preset
+pub const EDGE_THRESHOLD: f32 = 0.166;
+pub const EDGE_THRESHOLD_MIN: f32 = 0.0833;
+pub const SUBPIXEL: f32 = 0.75;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct FxaaUniforms {
+    texel_size: [f32; 2],
+    edge_threshold: f32,
+    edge_threshold_min: f32,
+    subpixel: f32,
+    _padding: f32,
+}
+
+unsafe impl Pod for FxaaUniforms {}
+unsafe impl Zeroable for FxaaUniforms {}
+
+// fast approximate anti-aliasing: a fullscreen pass over the resolved scene color that finds edges
+// by their luma contrast and blends across them, see fxaa.wgsl. a single sample per pixel and no
+// extra attachments, so it costs one pass where msaa and ssaa multiply memory or shading, at the
+// price of also softening texture detail
+pub struct Fxaa {
+    pub pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub sampler: wgpu::Sampler,
+    pub uniform_buffer: wgpu::Buffer,
+    // the local contrast an edge needs, relative to the brightest of its neighbors
+    pub edge_threshold: f32,
+    // the contrast below which dark areas are left alone whatever edge_threshold says
+    pub edge_threshold_min: f32,
+    // how much of the subpixel blend is applied, 0 keeps thin features sharp and 1 is softest
+    pub subpixel: f32,
+}
+
+impl Fxaa {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Fxaa Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("fxaa.wgsl").into()),
+        });
+
+        let uniform_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Fxaa Uniform Buffer"),
+            size: std::mem::size_of::<FxaaUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("Fxaa Bind Group Layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Fxaa Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Fxaa Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        // the edge search and the final blend read between texels
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Fxaa Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Fxaa {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            edge_threshold: EDGE_THRESHOLD,
+            edge_threshold_min: EDGE_THRESHOLD_MIN,
+            subpixel: SUBPIXEL,
+        }
+    }
+
+    // rebuilt with the render target, like the blit's bind groups
+    pub fn bind_group(&self, device: &wgpu::Device, color_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(color_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("Fxaa Bind Group"),
+        })
+    }
+
+    // source_size is the size of the texture being anti-aliased, edges are searched in its texels
+    pub fn write(&self, queue: &wgpu::Queue, source_size: (u32, u32)) {
+        let uniforms = FxaaUniforms {
+            texel_size: [1.0 / source_size.0 as f32, 1.0 / source_size.1 as f32],
+            edge_threshold: self.edge_threshold,
+            edge_threshold_min: self.edge_threshold_min,
+            subpixel: self.subpixel,
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, bind_group: &wgpu::BindGroup) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Fxaa Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..Default::default()
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,141 @@
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) uv : vec2<f32>,
+};
+
+// a single triangle covering the whole screen, uv spans [0, 1] over the visible part
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32) -> Output {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var output: Output;
+    output.Position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    output.uv = uv;
+    return output;
+}
+
+struct FxaaUniforms {
+    texelSize : vec2<f32>,
+    edgeThreshold : f32,
+    edgeThresholdMin : f32,
+    subpixel : f32,
+    _padding : f32,
+};
+
+@binding(0) @group(0) var<uniform> fxaa : FxaaUniforms;
+@binding(1) @group(0) var sceneTexture : texture_2d<f32>;
+@binding(2) @group(0) var sceneSampler : sampler;
+
+// how far each step of the edge search moves along the edge, in texels, and how far past the last
+// step an end that was never found is guessed to be
+const EDGE_STEP_COUNT : i32 = 10;
+const EDGE_STEPS = array<f32, 10>(1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 4.0);
+const EDGE_GUESS : f32 = 8.0;
+
+// the sampled colors are linear, the square root brings the luma close to perceived brightness
+fn luma(color : vec4<f32>) -> f32 {
+    return sqrt(dot(color.rgb, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+fn sampleLuma(uv : vec2<f32>) -> f32 {
+    return luma(textureSampleLevel(sceneTexture, sceneSampler, uv, 0.0));
+}
+
+fn neighborLuma(uv : vec2<f32>, x : f32, y : f32) -> f32 {
+    return sampleLuma(uv + vec2<f32>(x, y) * fxaa.texelSize);
+}
+
+@fragment
+fn fs_main(@location(0) uv : vec2<f32>) -> @location(0) vec4<f32> {
+    let center = textureSampleLevel(sceneTexture, sceneSampler, uv, 0.0);
+    let m = luma(center);
+    let n = neighborLuma(uv, 0.0, 1.0);
+    let e = neighborLuma(uv, 1.0, 0.0);
+    let s = neighborLuma(uv, 0.0, -1.0);
+    let w = neighborLuma(uv, -1.0, 0.0);
+
+    // too little contrast to be an edge worth smoothing
+    let highest = max(max(max(n, e), max(s, w)), m);
+    let lowest = min(min(min(n, e), min(s, w)), m);
+    let contrast = highest - lowest;
+    if (contrast < max(fxaa.edgeThresholdMin, fxaa.edgeThreshold * highest)) {
+        return center;
+    }
+
+    let ne = neighborLuma(uv, 1.0, 1.0);
+    let nw = neighborLuma(uv, -1.0, 1.0);
+    let se = neighborLuma(uv, 1.0, -1.0);
+    let sw = neighborLuma(uv, -1.0, -1.0);
+
+    // subpixel blending: how much the pixel differs from the weighted average of its neighborhood,
+    // large for features thinner than a pixel
+    let average = (2.0 * (n + e + s + w) + ne + nw + se + sw) / 12.0;
+    let subpixel = smoothstep(0.0, 1.0, saturate(abs(average - m) / contrast));
+    let subpixelBlend = subpixel * subpixel * fxaa.subpixel;
+
+    // the edge runs along the axis with the smaller luma change
+    let horizontal = 2.0 * abs(n + s - 2.0 * m) + abs(ne + se - 2.0 * e) + abs(nw + sw - 2.0 * w);
+    let vertical = 2.0 * abs(e + w - 2.0 * m) + abs(ne + nw - 2.0 * n) + abs(se + sw - 2.0 * s);
+    let isHorizontal = horizontal >= vertical;
+
+    // step toward the side of the edge with the larger gradient
+    var pixelStep = select(fxaa.texelSize.x, fxaa.texelSize.y, isHorizontal);
+    let positiveLuma = select(e, n, isHorizontal);
+    let negativeLuma = select(w, s, isHorizontal);
+    let positiveGradient = abs(positiveLuma - m);
+    let negativeGradient = abs(negativeLuma - m);
+    var oppositeLuma = positiveLuma;
+    var gradient = positiveGradient;
+    if (positiveGradient < negativeGradient) {
+        pixelStep = -pixelStep;
+        oppositeLuma = negativeLuma;
+        gradient = negativeGradient;
+    }
+
+    // walk both ways along the edge, halfway between the two pixels, until the luma differs from the
+    // edge's by more than a quarter of its gradient
+    let edgeUv = uv + select(vec2<f32>(pixelStep * 0.5, 0.0), vec2<f32>(0.0, pixelStep * 0.5), isHorizontal);
+    let edgeStep = select(vec2<f32>(0.0, fxaa.texelSize.y), vec2<f32>(fxaa.texelSize.x, 0.0), isHorizontal);
+    let edgeLuma = (m + oppositeLuma) * 0.5;
+    let gradientThreshold = gradient * 0.25;
+    var steps = EDGE_STEPS;
+
+    var positiveUv = edgeUv + edgeStep;
+    var positiveDelta = sampleLuma(positiveUv) - edgeLuma;
+    var positiveEnd = abs(positiveDelta) >= gradientThreshold;
+    for (var i = 1; i < EDGE_STEP_COUNT && !positiveEnd; i++) {
+        positiveUv += edgeStep * steps[i];
+        positiveDelta = sampleLuma(positiveUv) - edgeLuma;
+        positiveEnd = abs(positiveDelta) >= gradientThreshold;
+    }
+    if (!positiveEnd) {
+        positiveUv += edgeStep * EDGE_GUESS;
+    }
+
+    var negativeUv = edgeUv - edgeStep;
+    var negativeDelta = sampleLuma(negativeUv) - edgeLuma;
+    var negativeEnd = abs(negativeDelta) >= gradientThreshold;
+    for (var i = 1; i < EDGE_STEP_COUNT && !negativeEnd; i++) {
+        negativeUv -= edgeStep * steps[i];
+        negativeDelta = sampleLuma(negativeUv) - edgeLuma;
+        negativeEnd = abs(negativeDelta) >= gradientThreshold;
+    }
+    if (!negativeEnd) {
+        negativeUv -= edgeStep * EDGE_GUESS;
+    }
+
+    // edge blending: pixels nearer the end of the edge segment are shifted more, the end on the side
+    // where the luma crosses over decides whether this pixel is blended at all
+    let positiveDistance = select(positiveUv.y - uv.y, positiveUv.x - uv.x, isHorizontal);
+    let negativeDistance = select(uv.y - negativeUv.y, uv.x - negativeUv.x, isHorizontal);
+    let shortest = min(positiveDistance, negativeDistance);
+    let deltaSign = select(negativeDelta >= 0.0, positiveDelta >= 0.0, positiveDistance <= negativeDistance);
+    var edgeBlend = 0.0;
+    if (deltaSign != (m - edgeLuma >= 0.0)) {
+        edgeBlend = 0.5 - shortest / (positiveDistance + negativeDistance);
+    }
+
+    let blend = max(subpixelBlend, edgeBlend);
+    let blendUv = uv + select(vec2<f32>(pixelStep * blend, 0.0), vec2<f32>(0.0, pixelStep * blend), isHorizontal);
+    return textureSampleLevel(sceneTexture, sceneSampler, blendUv, 0.0);
+}
@@ -0,0 +1,159 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, Point3};
+
+use crate::{render_target, resource_tracker::ResourceTracker, transforms::DepthMode, vertex_data};
+
+// how far from the camera the lines have faded out completely, cycled through with fade_distance.
+// they start fading at half the distance
+pub const FADE_DISTANCES: [f32; 3] = [10.0, 25.0, 50.0];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Uniforms {
+    view_projection: [[f32; 4]; 4],
+    camera_position: [f32; 3],
+    spacing: f32,
+    color: [f32; 3],
+    height: f32,
+    fade_start: f32,
+    fade_end: f32,
+    antialias: f32,
+    _padding: f32,
+}
+
+unsafe impl Pod for Uniforms {}
+unsafe impl Zeroable for Uniforms {}
+
+// the rectangular reference grid drawn by grid.wgsl on a plane instead of as line geometry. the
+// lines are computed per pixel, so they stay one pixel wide and antialiased at any distance, and fade
+// out toward fade_distance from the camera where line geometry turns into aliased noise.
+// drawn blended after the opaque scene with depth testing but without depth writes
+pub struct ShaderGrid {
+    pub pipeline: wgpu::RenderPipeline,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_bind_group: wgpu::BindGroup,
+    pub spacing: f32,
+    pub height: f32,
+    pub color: [f32; 3],
+    pub fade_distance: f32,
+    // fwidth smoothed line edges, off for hard one pixel lines to compare against
+    pub antialias: bool,
+}
+
+impl ShaderGrid {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode, spacing: f32, height: f32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Grid Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("grid.wgsl").into()),
+        });
+
+        let uniform_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Grid Uniform Buffer"),
+            size: std::mem::size_of::<Uniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Grid Uniform Bind Group Layout"),
+        });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Grid Uniform Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Grid Pipeline Layout"),
+            bind_group_layouts: &[&uniform_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Grid Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // seen from below as well as from above
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        ShaderGrid {
+            pipeline,
+            uniform_buffer,
+            uniform_bind_group,
+            spacing,
+            height,
+            color: vertex_data::GRID_COLOR,
+            fade_distance: FADE_DISTANCES[1],
+            antialias: true,
+        }
+    }
+
+    // the grid follows the camera across the plane, call whenever either the camera or the settings change
+    pub fn write(&self, queue: &wgpu::Queue, view_projection: Matrix4<f32>, camera_position: Point3<f32>) {
+        let uniforms = Uniforms {
+            view_projection: view_projection.into(),
+            camera_position: camera_position.into(),
+            spacing: self.spacing,
+            color: self.color,
+            height: self.height,
+            fade_start: self.fade_distance * 0.5,
+            fade_end: self.fade_distance,
+            antialias: if self.antialias { 1.0 } else { 0.0 },
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    // the next of FADE_DISTANCES, write again afterwards
+    pub fn cycle_fade_distance(&mut self) {
+        let index = FADE_DISTANCES.iter().position(|&d| d == self.fade_distance).map_or(0, |i| (i + 1) % FADE_DISTANCES.len());
+        self.fade_distance = FADE_DISTANCES[index];
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.draw(0..6, 0..1);
+    }
+}
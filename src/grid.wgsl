@@ -0,0 +1,54 @@
+struct Uniforms {
+    viewProjection : mat4x4<f32>,
+    cameraPosition : vec3<f32>,
+    spacing : f32,
+    color : vec3<f32>,
+    height : f32,
+    fadeStart : f32,
+    fadeEnd : f32,
+    antialias : f32,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vWorld : vec3<f32>,
+};
+
+// a square on the grid plane under the camera, just reaching the distance where the lines have faded
+// out, so the grid looks endless. two triangles from the vertex index, no vertex buffer
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> Output {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(-1.0, 1.0), vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[index] * uniforms.fadeEnd + uniforms.cameraPosition.xz;
+    let world = vec3<f32>(corner.x, uniforms.height, corner.y);
+
+    var output: Output;
+    output.Position = uniforms.viewProjection * vec4<f32>(world, 1.0);
+    output.vWorld = world;
+    return output;
+}
+
+// the lines are found analytically from the distance to the nearest multiple of spacing. measured in
+// pixels through fwidth they keep about one pixel wide at any distance and fade out smoothly over
+// their edge; without antialias they are cut off hard at one pixel. far away, where cells shrink
+// below a few pixels and the lines would merge into moire, the grid fades out with distance
+@fragment
+fn fs_main(@location(0) vWorld: vec3<f32>) -> @location(0) vec4<f32> {
+    let coord = vWorld.xz / uniforms.spacing;
+    let width = fwidth(coord);
+    let pixels = abs(fract(coord - 0.5) - 0.5) / width;
+    let line = min(pixels.x, pixels.y);
+
+    var coverage = 1.0 - min(line, 1.0);
+    if (uniforms.antialias == 0.0) {
+        coverage = select(0.0, 1.0, line < 0.5);
+    }
+
+    let distance = length(vWorld - uniforms.cameraPosition);
+    let fade = 1.0 - smoothstep(uniforms.fadeStart, uniforms.fadeEnd, distance);
+    return vec4<f32>(uniforms.color, coverage * fade);
+}
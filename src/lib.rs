@@ -1,5 +1,32 @@
-mod transforms;
-mod vertex_data;
+pub mod app_config;
+pub mod blit;
+pub mod depth_view;
+pub mod double_buffer;
+pub mod error;
+pub mod frame_timing;
+pub mod fxaa;
+pub mod grid;
+pub mod lights;
+pub mod lines;
+pub mod mesh;
+pub mod model;
+pub mod outline;
+pub mod particles;
+pub mod pipeline_cache;
+pub mod points;
+pub mod procedural;
+pub mod reflection;
+pub mod render_target;
+pub mod resource_tracker;
+pub mod split_screen;
+pub mod text;
+pub mod texture;
+pub mod transforms;
+pub mod translucent;
+pub mod uniform_ring;
+pub mod util;
+pub mod vertex_data;
+pub mod wave;
 
 use winit::{
     event::{Event, WindowEvent}, 
@@ -15,7 +42,7 @@ pub async fn run(event_loop: EventLoop<()>, window: &Window) {
         ..Default::default()
     });
 
-    let surface = instance.create_surface(&window).unwrap();
+    let surface = instance.create_surface(window).unwrap();
 
     let adapter = instance
         .request_adapter(&wgpu::RequestAdapterOptionsBase {
@@ -45,7 +72,7 @@ pub async fn run(event_loop: EventLoop<()>, window: &Window) {
         height: size.height,
         present_mode: wgpu::PresentMode::Fifo,
         alpha_mode: surface_capabilities.alpha_modes[0],
-        desired_maximum_frame_latency: 1,
+        desired_maximum_frame_latency: transforms::DEFAULT_FRAMES_IN_FLIGHT,
         view_formats: vec![]
     };
 
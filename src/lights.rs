@@ -0,0 +1,143 @@
+use bytemuck::{Pod, Zeroable};
+use std::f32::consts::PI;
+
+pub const MAX_LIGHTS: usize = 16;
+
+// point light as laid out in the shader's Lights uniform: a vec3 followed by an f32 packs into 16 bytes
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Light {
+    pub position: [f32; 3],
+    // distance at which the light's contribution has faded to zero
+    pub range: f32,
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Lights {
+    pub lights: [Light; MAX_LIGHTS],
+    pub count: u32,
+    // pads the struct to the 16 byte multiple wgsl expects for uniform structs
+    pub _padding: [u32; 3],
+}
+
+unsafe impl Pod for Light {}
+unsafe impl Zeroable for Light {}
+
+unsafe impl Pod for Lights {}
+unsafe impl Zeroable for Lights {}
+
+// array<Light, 16> has a 32 byte stride in wgsl, followed by count rounded up to 16 bytes
+const _: () = assert!(std::mem::size_of::<Lights>() == MAX_LIGHTS * 32 + 16);
+
+impl Lights {
+    pub fn new(active: &[Light]) -> Self {
+        let count = active.len().min(MAX_LIGHTS);
+        let mut lights = [Light::default(); MAX_LIGHTS];
+        lights[..count].copy_from_slice(&active[..count]);
+
+        Lights {
+            lights,
+            count: count as u32,
+            _padding: [0; 3],
+        }
+    }
+}
+
+// colors the active light cycles through, see LightEdit
+pub const COLOR_PRESETS: [(&str, [f32; 3]); 6] = [
+    ("white", [1.0, 1.0, 1.0]),
+    ("warm", [1.0, 0.75, 0.5]),
+    ("cool", [0.55, 0.75, 1.0]),
+    ("red", [1.0, 0.2, 0.15]),
+    ("green", [0.25, 1.0, 0.3]),
+    ("blue", [0.25, 0.35, 1.0]),
+];
+pub const INTENSITY_STEP: f32 = 0.25;
+// the range is multiplied or divided by RANGE_STEP and kept at MIN_RANGE or more
+pub const RANGE_STEP: f32 = 1.25;
+pub const MIN_RANGE: f32 = 0.5;
+
+// changes made to one light from the keyboard, applied over what the lighting mode generates so
+// animated lights keep moving. None keeps the generated value
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LightEdit {
+    pub intensity: Option<f32>,
+    pub range: Option<f32>,
+    // index into COLOR_PRESETS
+    pub color: Option<usize>,
+}
+
+impl LightEdit {
+    pub fn apply(&self, light: &mut Light) {
+        if let Some(intensity) = self.intensity {
+            light.intensity = intensity;
+        }
+        if let Some(range) = self.range {
+            light.range = range;
+        }
+        if let Some(color) = self.color {
+            light.color = COLOR_PRESETS[color].1;
+        }
+    }
+
+    // light is the unedited one, the first step starts from its values
+    pub fn step_intensity(&mut self, light: &Light, up: bool) {
+        let intensity = self.intensity.unwrap_or(light.intensity);
+        let step = if up { INTENSITY_STEP } else { -INTENSITY_STEP };
+        self.intensity = Some((intensity + step).max(0.0));
+    }
+
+    pub fn step_range(&mut self, light: &Light, up: bool) {
+        let range = self.range.unwrap_or(light.range);
+        let range = if up { range * RANGE_STEP } else { range / RANGE_STEP };
+        self.range = Some(range.max(MIN_RANGE));
+    }
+
+    // continues from the preset matching the light's color, or starts at the first
+    pub fn cycle_color(&mut self, light: &Light) {
+        let current = self.color.or_else(|| COLOR_PRESETS.iter().position(|(_, color)| *color == light.color));
+        self.color = Some(current.map_or(0, |index| (index + 1) % COLOR_PRESETS.len()));
+    }
+}
+
+pub fn white_light() -> Light {
+    Light {
+        position: [4.0, 5.0, 3.0],
+        range: 20.0,
+        color: [1.0, 1.0, 1.0],
+        intensity: 1.0,
+    }
+}
+
+// count colored lights orbiting the origin, each on its own radius, height and speed, at time seconds
+pub fn disco_lights(count: usize, time: f32) -> Vec<Light> {
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let radius = 2.5 + 2.5 * t;
+            let speed = if i % 2 == 0 { 0.6 + t } else { -(0.4 + t) };
+            let angle = 2.0 * PI * t + speed * time;
+            let height = 2.0 * (3.0 * t * PI + time * 0.5).sin();
+
+            Light {
+                position: [radius * angle.cos(), height, radius * angle.sin()],
+                range: 6.0,
+                color: hue_to_rgb(t),
+                intensity: 1.5,
+            }
+        })
+        .collect()
+}
+
+// fully saturated color for a hue in [0, 1)
+fn hue_to_rgb(hue: f32) -> [f32; 3] {
+    let channel = |offset: f32| {
+        let k = (hue * 6.0 + offset) % 6.0;
+        1.0 - (k.min(4.0 - k).clamp(0.0, 1.0))
+    };
+
+    [channel(5.0), channel(3.0), channel(1.0)]
+}
@@ -0,0 +1,300 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+
+use crate::{double_buffer::DoubleBuffer, render_target, resource_tracker::ResourceTracker, transforms::DepthMode, vertex_data};
+
+// world space vertex of a line segment, every two vertices form one segment
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct LineVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+}
+
+unsafe impl Pod for LineVertex {}
+unsafe impl Zeroable for LineVertex {}
+
+impl LineVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0=>Float32x3, 1=>Float32x3];
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// draws LineList segments and indexed LineStrip polylines into the scene pass, depth tested against
+// the scene but not writing depth
+pub struct LinePipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    pub strip_pipeline: wgpu::RenderPipeline,
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl LinePipeline {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("lines.wgsl").into()),
+        });
+
+        let view_projection: [[f32; 4]; 4] = Matrix4::identity().into();
+        let uniform_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Line Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[view_projection]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Line Bind Group Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Line Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, topology, strip_index_format| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[LineVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology,
+                strip_index_format,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        LinePipeline {
+            pipeline: create_pipeline("Line Pipeline", wgpu::PrimitiveTopology::LineList, None),
+            strip_pipeline: create_pipeline("Line Strip Pipeline", wgpu::PrimitiveTopology::LineStrip, Some(wgpu::IndexFormat::Uint32)),
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    pub fn write_view_projection(&self, queue: &wgpu::Queue, view_projection: Matrix4<f32>) {
+        let view_projection: [[f32; 4]; 4] = view_projection.into();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[view_projection]));
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, lines: &'pass LineBuffer) {
+        if lines.len == 0 {
+            return;
+        }
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, lines.buffer.current().slice(..));
+        pass.draw(0..lines.len, 0..1);
+    }
+
+    pub fn draw_strips<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, strips: &'pass LineStrips) {
+        if strips.index_count == 0 {
+            return;
+        }
+
+        pass.set_pipeline(&self.strip_pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, strips.vertex_buffer.slice(..));
+        pass.set_index_buffer(strips.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..strips.index_count, 0, 0..1);
+    }
+}
+
+// index that ends the current strip, see strip_indices
+pub const STRIP_RESTART: u32 = u32::MAX;
+
+// indices for polylines stored back to back in one vertex buffer, strip_lengths vertices each. a
+// strip shares each vertex between its two segments, half the vertices of the same path as a LineList.
+// disjoint strips are separated by STRIP_RESTART: setting strip_index_format on the pipeline turns on
+// primitive restart, so all of them go in a single draw. without that (or with non-indexed strips)
+// the fallback is a draw per strip over its own range, which is what the restart saves
+pub fn strip_indices(strip_lengths: &[usize]) -> Vec<u32> {
+    let mut indices = Vec::new();
+    let mut first = 0;
+
+    for (i, &length) in strip_lengths.iter().enumerate() {
+        if i > 0 {
+            indices.push(STRIP_RESTART);
+        }
+        indices.extend(first..first + length as u32);
+        first += length as u32;
+    }
+
+    indices
+}
+
+// static polylines for LinePipeline::draw_strips, each given as its list of points
+pub struct LineStrips {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+}
+
+impl LineStrips {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, strips: &[Vec<LineVertex>]) -> Self {
+        let vertices: Vec<LineVertex> = strips.iter().flatten().copied().collect();
+        let lengths: Vec<usize> = strips.iter().map(Vec::len).collect();
+        let indices = strip_indices(&lengths);
+
+        let vertex_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Line Strip Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Line Strip Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        LineStrips {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+        }
+    }
+}
+
+// vertex buffer of line segments rewritten as needed, reallocated only when the data outgrows it. the
+// bounding boxes and normals are rewritten every frame, so it alternates between two buffers
+pub struct LineBuffer {
+    pub buffer: DoubleBuffer,
+    pub capacity: usize,
+    pub len: u32,
+}
+
+impl LineBuffer {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, capacity: usize) -> Self {
+        LineBuffer {
+            buffer: create_line_buffer(device, tracker, capacity),
+            capacity,
+            len: 0,
+        }
+    }
+
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker, vertices: &[LineVertex]) {
+        if vertices.len() > self.capacity {
+            self.capacity = vertices.len().next_power_of_two();
+            self.buffer = create_line_buffer(device, tracker, self.capacity);
+        }
+
+        self.buffer.write(queue, bytemuck::cast_slice(vertices));
+        self.len = vertices.len() as u32;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+// immediate mode debug drawing: anything can add world space segments during a frame, flush uploads
+// them once before the scene pass and starts the next frame empty. segments beyond the capacity given
+// to new are dropped with a warning, so a runaway loop can't grow the buffer without bound
+pub struct DebugLines {
+    pub vertices: Vec<LineVertex>,
+    pub buffer: LineBuffer,
+    pub max_segments: usize,
+    overflowed: bool,
+}
+
+impl DebugLines {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, max_segments: usize) -> Self {
+        DebugLines {
+            vertices: Vec::new(),
+            buffer: LineBuffer::new(device, tracker, max_segments * 2),
+            max_segments,
+            overflowed: false,
+        }
+    }
+
+    pub fn debug_line(&mut self, start: [f32; 3], end: [f32; 3], color: [f32; 3]) {
+        if self.vertices.len() / 2 >= self.max_segments {
+            if !self.overflowed {
+                log::warn!("more than {} debug lines this frame, the rest are dropped", self.max_segments);
+                self.overflowed = true;
+            }
+            return;
+        }
+
+        self.vertices.push(LineVertex { position: start, color });
+        self.vertices.push(LineVertex { position: end, color });
+    }
+
+    // the 12 edges of an axis aligned box
+    pub fn debug_aabb(&mut self, min: [f32; 3], max: [f32; 3], color: [f32; 3]) {
+        for segment in vertex_data::aabb_lines(min, max, color).chunks_exact(2) {
+            self.debug_line(segment[0].position, segment[1].position, color);
+        }
+    }
+
+    // uploads this frame's segments for drawing and clears them for the next one
+    pub fn flush(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) {
+        if self.vertices.is_empty() {
+            self.buffer.clear();
+        } else {
+            self.buffer.write(device, queue, tracker, &self.vertices);
+        }
+        self.vertices.clear();
+        self.overflowed = false;
+    }
+}
+
+fn create_line_buffer(device: &wgpu::Device, tracker: &ResourceTracker, capacity: usize) -> DoubleBuffer {
+    DoubleBuffer::new(device, tracker, &wgpu::BufferDescriptor {
+        label: Some("Line Vertex Buffer"),
+        size: (capacity.max(1) * std::mem::size_of::<LineVertex>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    })
+}
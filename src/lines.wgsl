@@ -0,0 +1,23 @@
+struct Uniforms {
+    viewProjectionMatrix : mat4x4<f32>,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vColor : vec4<f32>,
+};
+
+// lines are given in world space
+@vertex
+fn vs_main(@location(0) pos: vec3<f32>, @location(1) color: vec3<f32>) -> Output {
+    var output: Output;
+    output.Position = uniforms.viewProjectionMatrix * vec4<f32>(pos, 1.0);
+    output.vColor = vec4<f32>(color, 1.0);
+    return output;
+}
+
+@fragment
+fn fs_main(@location(0) vColor: vec4<f32>) -> @location(0) vec4<f32> {
+    return vColor;
+}
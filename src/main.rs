@@ -1,315 +1,4381 @@
-use bytemuck:: {Pod, Zeroable, cast_slice};
-use cgmath::Matrix4;
-use wgpu::{util::DeviceExt, StoreOp};
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
+
+use bytemuck:: {Pod, Zeroable};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix, Matrix4, MetricSpace, Point3, Rad, SquareMatrix, Vector3};
+use render::{app_config::{self, AppConfig, CameraConfig}, blit::{Blit, BlitBindGroups}, depth_view::{DepthView, DepthViewMode}, error::Error, frame_timing::{DrawCounter, FrameStats, GpuTimer, TimingWindow}, fxaa::Fxaa, grid::ShaderGrid, lights::{self, Light, LightEdit, Lights}, lines::{DebugLines, LineBuffer, LinePipeline, LineStrips, LineVertex}, mesh::{self, GpuMesh, Mesh}, model, outline::Outline, particles::Particles, points::{self, PointCloud},
+    pipeline_cache::{PipelineCache, PipelineKey}, procedural::Procedural, reflection::{self, ReflectionProbe}, render_target::{self, PipelineTargets, PixelReadback, RenderTarget}, resource_tracker::ResourceTracker, split_screen::{self, AxisView},
+    text::TextRenderer, texture::{self, AnisotropyBindGroups, Texture}, transforms::{self, DepthMode, FOVY},
+    translucent::{AlphaMode, DrawOrder, TranslucentCubes, TranslucentMesh, TranslucentQuad}, uniform_ring::UniformRing, util::rng, vertex_data::{self, value_noise, Vertex}, wave::{self, Wave, WaveParameter}};
+use wgpu::StoreOp;
 use winit::{
-    dpi::PhysicalPosition, event::{Event, WindowEvent}, event_loop::EventLoop, window::{Window, WindowBuilder}
+    dpi::{LogicalSize, PhysicalPosition},
+    event::{DeviceEvent, ElementState, Event, KeyEvent, MouseButton, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    keyboard::{KeyCode, ModifiersState, PhysicalKey},
+    window::{CursorGrabMode, Window, WindowBuilder, WindowId, WindowLevel}
 };
 
-mod transforms;
-mod vertex_data;
-
 const IS_PERSPECTIVE:bool = true;
 const ANIMATION_SPEED:f32 = 1.0;
+const TITLE:&str = "cube with distinct face colors";
+
+// instanced culling demo: a GRID_COUNT x GRID_COUNT grid of cubes GRID_SPACING apart, both adjustable
+// with keys within the limits below
+const GRID_COUNT:u32 = 32;
+const GRID_SPACING:f32 = 4.0;
+const GRID_COUNT_MIN:u32 = 2;
+const GRID_COUNT_MAX:u32 = 50;
+const GRID_SPACING_MIN:f32 = 2.5;
+const GRID_SPACING_MAX:f32 = 10.0;
+const GRID_SPACING_STEP:f32 = 0.5;
+
+// the scene is rendered at RENDER_SCALE_MIN..=RENDER_SCALE_MAX of the window size and upscaled
+const RENDER_SCALE_MIN:f32 = 0.25;
+const RENDER_SCALE_MAX:f32 = 1.0;
+const RENDER_SCALE_STEP:f32 = 0.25;
+// supersampling renders at this many times the render scale in each direction and box filters down,
+// anti-aliasing shading and textures as well as edges at factor squared the pixel cost
+const SSAA_FACTORS:[u32; 3] = [1, 2, 4];
+// the factor AaMode::Ssaa starts at, shift+[ and shift+] step through the others
+const AA_MODE_SSAA_FACTOR:u32 = 2;
+
+const MORPH_STEP:f32 = 0.1;
+// fps camera: radians of rotation per unit of raw mouse motion, how far the pitch may go from
+// level (just short of straight up or down, where look_at_rh breaks down) and distance per key press
+const MOUSE_SENSITIVITY:f32 = 0.002;
+const PITCH_LIMIT:f32 = 1.55;
+const MOVE_STEP:f32 = 0.25;
+// middle drag orbits the camera around what it looks at, ORBIT_SENSITIVITY radians per pixel. on release
+// the orbit keeps going at the drag's speed over its last ORBIT_VELOCITY_WINDOW seconds, slowing down by
+// a factor of e every 1 / ORBIT_DAMPING seconds until it drops below ORBIT_MIN_VELOCITY radians per second
+const ORBIT_SENSITIVITY:f32 = 0.005;
+const ORBIT_VELOCITY_WINDOW:f32 = 0.1;
+const ORBIT_DAMPING:f32 = 3.0;
+const ORBIT_MIN_VELOCITY:f32 = 0.02;
+// the second window looks at the scene from further out, above and behind the main camera
+const SECOND_WINDOW_TITLE:&str = "second camera";
+const SECOND_CAMERA_POSITION:[f32; 3] = [-8.0, 10.0, 14.0];
+// the inspection camera whose frustum is drawn for teaching projections: where it starts, the depth
+// range it sees (short, so the whole frustum fits in the main view), the radius its orthographic
+// projection fits, how far each arrow key turns it around its target, and the edge colors
+const INSPECTION_CAMERA_POSITION:[f32; 3] = [4.0, 2.0, -3.0];
+const INSPECTION_NEAR:f32 = 0.5;
+const INSPECTION_FAR:f32 = 4.0;
+const INSPECTION_ORTHO_RADIUS:f32 = 1.0;
+const INSPECTION_ORBIT_STEP:f32 = 0.1;
+const FRUSTUM_NEAR_COLOR:[f32; 3] = [0.2, 0.9, 0.3];
+const FRUSTUM_FAR_COLOR:[f32; 3] = [0.9, 0.25, 0.2];
+const FRUSTUM_SIDE_COLOR:[f32; 3] = [0.85, 0.85, 0.5];
+// the reference grids lie at the bottom of the unit cube, the rectangular one with lines every
+// REFERENCE_GRID_SPACING and the polar one with REFERENCE_GRID_RINGS rings and REFERENCE_GRID_SPOKES spokes
+const REFERENCE_GRID_HEIGHT:f32 = -1.0;
+const REFERENCE_GRID_EXTENT:f32 = 10.0;
+const REFERENCE_GRID_SPACING:f32 = 1.0;
+const REFERENCE_GRID_RINGS:u32 = 10;
+const REFERENCE_GRID_SPOKES:u32 = 12;
+// control points of the demo camera path, a loop around the scene drawn as a smooth spline over its
+// control polygon
+const CAMERA_PATH_POINTS:[[f32; 3]; 6] = [
+    [6.0, 2.0, 0.0], [3.0, 4.0, 5.0], [-3.0, 1.5, 5.0], [-6.0, 3.0, 0.0], [-3.0, 1.0, -5.0], [3.0, 3.5, -5.0],
+];
+const CAMERA_PATH_SEGMENTS:u32 = 16;
+const CAMERA_PATH_COLOR:[f32; 3] = [1.0, 0.85, 0.2];
+const CAMERA_PATH_CONTROL_COLOR:[f32; 3] = [0.5, 0.45, 0.3];
+// the gpu particle fountain, and the longest step it is integrated over so a stalled frame doesn't
+// fling the particles through the floor
+const PARTICLE_COUNT:u32 = 16384;
+const PARTICLE_GRAVITY:[f32; 3] = [0.0, -4.0, 0.0];
+const MAX_PARTICLE_STEP:f32 = 0.05;
+// the fixed timestep mode advances the animation and the particles in steps of FIXED_DT, at most
+// MAX_SUBSTEPS of them per frame
+const FIXED_DT:f32 = 1.0 / 60.0;
+const MAX_SUBSTEPS:u32 = 5;
+// length of the hedgehog segments drawn along each vertex normal
+const NORMAL_LINE_LENGTH:f32 = 0.3;
+// the model scale keys multiply or divide by MODEL_SCALE_STEP, each axis kept within the limits so the
+// model never collapses or turns inside out
+const MODEL_SCALE_STEP:f32 = 1.1;
+const MODEL_SCALE_MIN:f32 = 0.1;
+const MODEL_SCALE_MAX:f32 = 10.0;
+const MODEL_SCALE_DEFAULT:[f32; 3] = [1.0, 1.0, 1.0];
+// how long a dolly zoom takes, in seconds, and how close to the subject it moves the camera, as a
+// fraction of the starting distance
+const DOLLY_ZOOM_DURATION:f32 = 3.0;
+const DOLLY_ZOOM_FACTOR:f32 = 0.35;
+// view distance at the far end of the depth view's ramp, the whole scene would be a single shade
+// against Z_FAR
+const DEPTH_VIEW_RANGE:f32 = 20.0;
+// half length of the segments of the marker placed at the picked point
+const MARKER_SIZE:f32 = 0.2;
+// the last pick's ray is left in the scene as a debug line, up to the hit or PICK_RAY_LENGTH on a miss
+const PICK_RAY_LENGTH:f32 = 50.0;
+const PICK_RAY_COLOR:[f32; 3] = [1.0, 0.3, 0.8];
+// the selection outline around the picked object, drawn as a copy this much larger
+const OUTLINE_COLOR:[f32; 4] = [1.0, 0.6, 0.0, 1.0];
+const OUTLINE_SCALE:f32 = 1.05;
+// where the reflection probe renders from, the mirror sphere sitting there beside the model
+const PROBE_POSITION:[f32; 3] = [2.5, 0.0, 0.0];
+const PROBE_SPHERE_RADIUS:f32 = 0.75;
+// segments DebugLines accepts per frame
+const MAX_DEBUG_LINES:usize = 4096;
+// the alternate color scheme ramps along y, bottom to top
+const GRADIENT_AXIS:usize = 1;
+// each level quadruples the triangle count
+const MAX_SUBDIVISIONS:u32 = 4;
+
+const DISCO_LIGHT_COUNT:usize = 12;
+
+const BOUNDING_BOX_COLOR:[f32; 3] = [1.0, 1.0, 1.0];
+
+// requested msaa level, lowered at startup to what the adapter supports
+const MSAA_SAMPLES:u32 = 4;
+// present in the temp dir while the app runs. finding it at startup means the last run never shut down
+// cleanly, most likely a driver crash, so that run starts in safe mode
+const RUNNING_MARKER:&str = "render.running";
+// the translucent quad sits between the camera and the cube, facing the camera
+const QUAD_POSITION:[f32; 3] = [1.5, 0.75, 1.5];
+const QUAD_SIZE:f32 = 0.75;
+
+// hud text, in font pixels scaled up by HUD_SCALE, both in logical pixels so the hud keeps its
+// size on high dpi screens
+const HUD_SCALE:f32 = 2.0;
+const HUD_MARGIN:f32 = 8.0;
+const HUD_COLOR:[f32; 4] = [1.0, 1.0, 1.0, 0.9];
+// crosshair arm length from the center and line thickness in logical pixels, scaled by the window's
+// scale factor so it keeps its size on hidpi displays
+const CROSSHAIR_SIZE:f32 = 8.0;
+const CROSSHAIR_THICKNESS:f32 = 2.0;
+const CROSSHAIR_COLOR:[f32; 4] = [1.0, 1.0, 1.0, 0.8];
+// weight of the newest frame in the smoothed fps
+const FPS_SMOOTHING:f32 = 0.1;
+
+const CLEAR_COLOR:wgpu::Color = wgpu::Color {
+    r: 0.2,
+    g: 0.247,
+    b: 0.314,
+    a: 1.0,
+};
+// with a premultiplied alpha surface the background shows through wherever nothing is drawn
+const TRANSPARENT_CLEAR_COLOR:wgpu::Color = wgpu::Color::TRANSPARENT;
+// the background while a file is dragged over the window, a hint that dropping it loads it
+const FILE_HOVER_CLEAR_COLOR:wgpu::Color = wgpu::Color {
+    r: 0.1,
+    g: 0.35,
+    b: 0.2,
+    a: 1.0,
+};
+
+// fragment stages that can be swapped in behind the shared vertex stage in shader.wgsl. they all use
+// the same bind groups, so the pipeline layout is kept when switching
+struct ShaderVariant {
+    name: &'static str,
+    fragment: &'static str,
+}
+
+const SHADER_VARIANTS: [ShaderVariant; 5] = [
+    ShaderVariant { name: "lit", fragment: include_str!("shader_lit.wgsl") },
+    ShaderVariant { name: "flat", fragment: include_str!("shader_flat.wgsl") },
+    ShaderVariant { name: "normals", fragment: include_str!("shader_normals.wgsl") },
+    ShaderVariant { name: "uv", fragment: include_str!("shader_uv.wgsl") },
+    ShaderVariant { name: "hue", fragment: include_str!("shader_hue.wgsl") },
+];
+// the variant toggle_hue_cycle switches to
+const HUE_SHADER: usize = 4;
+
+// blend components the scene pipeline's color and alpha channels cycle through independently. the
+// result is src * src_factor (operation) dst * dst_factor, with src the fragment and dst the target
+const BLEND_COMPONENTS: [(&str, wgpu::BlendComponent); 5] = [
+    ("replace", wgpu::BlendComponent::REPLACE),
+    ("alpha", wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+        operation: wgpu::BlendOperation::Add,
+    }),
+    ("additive", wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    }),
+    // src * dst + dst * 0, darkens the target by the fragment
+    ("multiply", wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Dst,
+        dst_factor: wgpu::BlendFactor::Zero,
+        operation: wgpu::BlendOperation::Add,
+    }),
+    ("max", wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Max,
+    }),
+];
+
+// only drawn by the wireframe overlay, never part of the variant cycle. its module follows the
+// SHADER_VARIANTS ones in State::shaders
+const WIREFRAME_OVERLAY_VARIANT: ShaderVariant = ShaderVariant { name: "wireframe", fragment: include_str!("shader_wireframe.wgsl") };
+const WIREFRAME_OVERLAY_SHADER: usize = SHADER_VARIANTS.len();
+
+// how far the wireframe overlay is pulled toward the camera so its edges win against the faces they
+// lie on. constant is in units of the smallest depth step at the primitive's depth, slope_scale scales
+// with how steeply the face recedes, clamp caps the total (0 leaves it uncapped). webgpu only
+// biases triangles, so the LineList overlays (grids, boxes) can't use it
+const OVERLAY_DEPTH_BIAS: wgpu::DepthBiasState = wgpu::DepthBiasState {
+    constant: 2,
+    slope_scale: 1.0,
+    clamp: 0.0,
+};
+
+// the Output fields of shader.wgsl that get @interpolate(linear) in the linear interpolation demo
+const PERSPECTIVE_CORRECT_OUTPUTS: [&str; 3] = ["@location(0) vColor", "@location(3) vUv", "@location(4) vUv1"];
+
+// linear_interpolation interpolates the colors and uvs in screen space, without the division by w
+// that makes the default perspective correct. across a large quad seen at an angle the texture then
+// bends along the triangles' diagonal, the affine warping of early software renderers and the ps1
+fn create_shader(device: &wgpu::Device, variant: &ShaderVariant, linear_interpolation: bool) -> wgpu::ShaderModule {
+    let mut source = include_str!("shader.wgsl").to_string();
+    if linear_interpolation {
+        for output in PERSPECTIVE_CORRECT_OUTPUTS {
+            debug_assert!(source.contains(output), "{} is not in shader.wgsl", output);
+            let (location, name) = output.split_once(' ').unwrap_or((output, ""));
+            source = source.replace(output, &format!("{} @interpolate(linear) {}", location, name));
+        }
+    }
+
+    device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(variant.name),
+        source: wgpu::ShaderSource::Wgsl(format!("{}\n{}", source, variant.fragment).into()),
+    })
+}
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
-struct Vertex {
-    position: [f32; 4],
-    color: [f32; 4],
+struct Instance {
+    model: [[f32; 4]; 4],
 }
 
-unsafe impl Pod for Vertex {}
-unsafe impl Zeroable for Vertex {}
+unsafe impl Pod for Instance {}
+unsafe impl Zeroable for Instance {}
 
-impl Vertex {
-    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0=>Float32x4, 1=>Float32x4];
+impl Instance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![8=>Float32x4, 9=>Float32x4, 10=>Float32x4, 11=>Float32x4];
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
             attributes: &Self::ATTRIBUTES,
         }
     }
 }
 
-fn vertex(p:[i8;3], c:[i8; 3]) -> Vertex {
+impl From<Matrix4<f32>> for Instance {
+    fn from(model: Matrix4<f32>) -> Self {
+        Instance { model: model.into() }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Uniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    model_matrix: [[f32; 4]; 4],
+    morph_weight: f32,
+    // width in pixels of the single pass edge highlight, 0 when it is off
+    edge_width: f32,
+    fog_density: f32,
+    // 1 blends fragments toward fog_color with their distance from camera_position, 0 leaves them
+    fog_enabled: u32,
+    // world space, w is 1. the fog distance and any view dependent shading are measured from it
+    camera_position: [f32; 4],
+    // rgb, a is unused
+    fog_color: [f32; 4],
+    // seconds of animation time, for shading that moves on its own
+    time: f32,
+    // pads the struct to the 16 byte multiple wgsl expects for uniform structs
+    _padding: [f32; 3],
+}
+
+unsafe impl Pod for Uniforms {}
+unsafe impl Zeroable for Uniforms {}
+
+// byte offsets of the fields of Uniforms, so one can be rewritten without the others: the view
+// projection at 0 (64 bytes), the model matrix at 64 (64 bytes), the morph weight at 128 (4 bytes) and the camera position at
+// 144 (16 bytes)
+const VIEW_PROJECTION_OFFSET: wgpu::BufferAddress = std::mem::offset_of!(Uniforms, view_projection_matrix) as wgpu::BufferAddress;
+const MODEL_OFFSET: wgpu::BufferAddress = std::mem::offset_of!(Uniforms, model_matrix) as wgpu::BufferAddress;
+const MORPH_WEIGHT_OFFSET: wgpu::BufferAddress = std::mem::offset_of!(Uniforms, morph_weight) as wgpu::BufferAddress;
+const CAMERA_POSITION_OFFSET: wgpu::BufferAddress = std::mem::offset_of!(Uniforms, camera_position) as wgpu::BufferAddress;
+
+// width in pixels of the edges drawn by the single pass edge highlight
+const EDGE_WIDTH: f32 = 1.5;
+
+// exponential squared distance fog, 1 - exp(-(density * distance)^2) of the fog color. the density is
+// stepped by a factor so each step looks about as strong as the last
+const FOG_DENSITY: f32 = 0.08;
+const FOG_DENSITY_STEP: f32 = 1.25;
+const FOG_DENSITY_RANGE: (f32, f32) = (0.005, 1.0);
+// the colors the fog fades toward, None for the scene's clear color so distant geometry melts into
+// the background
+const FOG_COLORS: [(&str, Option<[f32; 3]>); 3] = [
+    ("background", None),
+    ("haze", Some([0.75, 0.78, 0.82])),
+    ("night", Some([0.02, 0.02, 0.04])),
+];
+
+// the split screen's orthographic views never zoom in further than this, for tiny or empty meshes
+const SPLIT_VIEW_MIN_RADIUS: f32 = 0.1;
+
+// the lit sphere scene's sphere
+const SPHERE_RADIUS: f32 = 1.2;
+const SPHERE_RINGS: u32 = 24;
+const SPHERE_SEGMENTS: u32 = 48;
+
+// the waves scene's plane, deformed by wave.wgsl
+const WAVE_COLOR: [f32; 3] = [0.1, 0.35, 0.65];
+
+// the terrain scene's heightmap, TERRAIN_CELLS unit cells a side scaled down by TERRAIN_SCALE
+const TERRAIN_CELLS: u32 = 64;
+const TERRAIN_SCALE: f32 = 0.06;
+
+// diameters in pixels of the points at the origin and at the mesh radius when sized by distance
+const POINT_SIZE_MIN: f32 = 2.0;
+const POINT_SIZE_MAX: f32 = 12.0;
+
+// the reference grid under the model, cycled with a key
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ReferenceGrid {
+    Off,
+    Rectangular,
+    Polar,
+    // the rectangular grid computed per pixel in grid.wgsl, antialiased and fading with distance
+    Shader,
+}
+
+// one of the built in demo scenes cycled with tab, each showing off one feature. State holds the active
+// one and calls it: setup puts the scene's mesh, lighting and toggles in place when it becomes active
+// and teardown undoes them before the next one is set up. the cube scene is whatever was loaded at
+// startup, the others that show their own mesh put it back on teardown
+trait Scene {
+    fn name(&self) -> &'static str;
+
+    // the scene after this one in the tab order
+    fn next(&self) -> Box<dyn Scene>;
+
+    fn setup(&mut self, state: &mut State);
+
+    fn teardown(&mut self, _state: &mut State) {}
+
+    // what the title says about the scene, nothing for the startup one
+    fn title(&self, _state: &State) -> Option<String> {
+        Some(format!("{} scene", self.name()))
+    }
+
+    // F1, F2 and F4 are left to the scene, true when it used the key
+    fn key(&mut self, _state: &mut State, _key: KeyCode, _modifiers: ModifiersState) -> bool {
+        false
+    }
+
+    // the texture the mesh is drawn with in place of the model's
+    fn texture<'state>(&self, _state: &'state State) -> Option<&'state AnisotropyBindGroups> {
+        None
+    }
+
+    // draws the mesh with the scene's own pipeline, false leaves it to the cached scene pipelines
+    fn draw_mesh<'pass>(&self, _state: &'pass State, _render_pass: &mut wgpu::RenderPass<'pass>,
+        _uniform_bind_group: &'pass wgpu::BindGroup, _instance_count: u32) -> bool {
+        false
+    }
+}
+
+struct CubeScene;
+
+impl Scene for CubeScene {
+    fn name(&self) -> &'static str {
+        "cube"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(InstancedGridScene)
+    }
+
+    fn setup(&mut self, _state: &mut State) {}
+
+    fn title(&self, _state: &State) -> Option<String> {
+        None
+    }
+}
+
+struct InstancedGridScene;
+
+impl Scene for InstancedGridScene {
+    fn name(&self) -> &'static str {
+        "instanced grid"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(TexturedCubeScene)
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_instances = true;
+        state.visible_instances = 0;
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.show_instances = false;
+        state.visible_instances = 0;
+    }
+}
+
+// the vertex data cube drawn with the checkerboard
+struct TexturedCubeScene;
+
+impl Scene for TexturedCubeScene {
+    fn name(&self) -> &'static str {
+        "textured cube"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(LitSphereScene)
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_scene_mesh(Mesh::from_vertices(create_vertices(state.srgb_vertex_colors)));
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.restore_startup_mesh();
+    }
+
+    fn texture<'state>(&self, state: &'state State) -> Option<&'state AnisotropyBindGroups> {
+        Some(&state.checker_bind_groups)
+    }
+}
+
+struct LitSphereScene;
+
+impl Scene for LitSphereScene {
+    fn name(&self) -> &'static str {
+        "lit sphere"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(TerrainScene { shape: TerrainShape::Noise })
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_scene_mesh(mesh::uv_sphere(SPHERE_RADIUS, SPHERE_RINGS, SPHERE_SEGMENTS, [0.8, 0.8, 0.8]));
+        state.set_lighting(Lighting::White);
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.restore_startup_mesh();
+        state.set_lighting(Lighting::Off);
+    }
+}
+
+// a lit heightmap, F4 regenerates it with the next height function
+struct TerrainScene {
+    shape: TerrainShape,
+}
+
+impl Scene for TerrainScene {
+    fn name(&self) -> &'static str {
+        "terrain"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(WavesScene)
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_scene_mesh(self.shape.mesh(state.seed));
+        state.set_lighting(Lighting::White);
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.restore_startup_mesh();
+        state.set_lighting(Lighting::Off);
+    }
+
+    fn title(&self, _state: &State) -> Option<String> {
+        Some(format!("{} scene ({} terrain)", self.name(), self.shape.name()))
+    }
+
+    fn key(&mut self, state: &mut State, key: KeyCode, _modifiers: ModifiersState) -> bool {
+        if key != KeyCode::F4 {
+            return false;
+        }
+
+        self.shape = self.shape.next();
+        state.cpu_mesh = self.shape.mesh(state.seed);
+        (state.mesh_bounds, state.mesh_radius) = mesh_extent(&state.cpu_mesh);
+        state.upload_mesh();
+        true
+    }
+}
+
+// a lit plane moved by wave.wgsl. F1 and F2 step the amplitude and frequency, control F1 the speed,
+// down with shift
+struct WavesScene;
+
+impl Scene for WavesScene {
+    fn name(&self) -> &'static str {
+        "waves"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(ParticlesScene)
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_scene_mesh(mesh::plane(wave::PLANE_SIZE, wave::PLANE_SUBDIVISIONS, WAVE_COLOR));
+        state.set_lighting(Lighting::White);
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.restore_startup_mesh();
+        state.set_lighting(Lighting::Off);
+    }
+
+    fn title(&self, state: &State) -> Option<String> {
+        Some(format!("{} scene (amplitude {:.2}, frequency {:.2}, speed {:.2})", self.name(), state.wave.amplitude,
+            state.wave.frequency, state.wave.speed))
+    }
+
+    fn key(&mut self, state: &mut State, key: KeyCode, modifiers: ModifiersState) -> bool {
+        let parameter = match key {
+            KeyCode::F1 if modifiers.control_key() => WaveParameter::Speed,
+            KeyCode::F1 => WaveParameter::Amplitude,
+            KeyCode::F2 => WaveParameter::Frequency,
+            _ => return false,
+        };
+
+        state.wave.step(parameter, !modifiers.shift_key());
+        state.wave.write(&state.init.queue);
+        true
+    }
+
+    // the plane is moved in the vertex shader, the wireframe overlay would show it flat
+    fn draw_mesh<'pass>(&self, state: &'pass State, render_pass: &mut wgpu::RenderPass<'pass>,
+        uniform_bind_group: &'pass wgpu::BindGroup, instance_count: u32) -> bool {
+        let mesh = state.drawn_mesh();
+        state.wave.bind(render_pass, uniform_bind_group);
+        render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
+        render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        state.draw_batched(render_pass, mesh, instance_count);
+        true
+    }
+}
+
+struct ParticlesScene;
+
+impl Scene for ParticlesScene {
+    fn name(&self) -> &'static str {
+        "particles"
+    }
+
+    fn next(&self) -> Box<dyn Scene> {
+        Box::new(CubeScene)
+    }
+
+    fn setup(&mut self, state: &mut State) {
+        state.show_particles = true;
+    }
+
+    fn teardown(&mut self, state: &mut State) {
+        state.show_particles = false;
+    }
+}
+
+// the height function of the terrain scene, in the heightmap's unit cells
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum TerrainShape {
+    Flat,
+    Sine,
+    Noise,
+}
+
+impl TerrainShape {
+    fn next(self) -> Self {
+        match self {
+            TerrainShape::Flat => TerrainShape::Sine,
+            TerrainShape::Sine => TerrainShape::Noise,
+            TerrainShape::Noise => TerrainShape::Flat,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TerrainShape::Flat => "flat",
+            TerrainShape::Sine => "sine",
+            TerrainShape::Noise => "noise",
+        }
+    }
+
+    // sine is two crossing waves with a faster ripple on top, noise four octaves of value noise each
+    // twice the frequency and half the amplitude of the last, laid out by seed
+    fn height(self, x: f32, z: f32, seed: u32) -> f32 {
+        match self {
+            TerrainShape::Flat => 0.0,
+            TerrainShape::Sine => 3.0 * (x * 0.15).sin() * (z * 0.12).cos() + 0.8 * (x * 0.4 + z * 0.3).sin(),
+            TerrainShape::Noise => (0..4)
+                .map(|octave| {
+                    let frequency = 0.06 * 2.0_f32.powi(octave);
+                    value_noise(x * frequency, z * frequency, seed) * 12.0 / 2.0_f32.powi(octave)
+                })
+                .sum::<f32>() - 10.0,
+        }
+    }
+
+    fn mesh(self, seed: u64) -> Mesh {
+        let seed = rng::seed_u32(seed);
+        let heightmap = vertex_data::heightmap(TERRAIN_CELLS, TERRAIN_CELLS, |x, z| self.height(x, z, seed));
+        mesh::merge(&[(Matrix4::from_scale(TERRAIN_SCALE), &heightmap)])
+    }
+}
+
+// what the scene target does against aliasing, cycled with F3. msaa uses the startup sample count and
+// only multiplies the coverage samples, ssaa renders the target at a multiple of the surface size and
+// shades every one of its pixels before the blit filters it down, fxaa replaces the blit with a pass
+// that smooths the edges it finds in the single sampled target
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum AaMode {
+    None,
+    Msaa,
+    Ssaa,
+    Fxaa,
+}
+
+impl AaMode {
+    fn next(self) -> Self {
+        match self {
+            AaMode::None => AaMode::Msaa,
+            AaMode::Msaa => AaMode::Ssaa,
+            AaMode::Ssaa => AaMode::Fxaa,
+            AaMode::Fxaa => AaMode::None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            AaMode::None => "no aa",
+            AaMode::Msaa => "msaa",
+            AaMode::Ssaa => "ssaa",
+            AaMode::Fxaa => "fxaa",
+        }
+    }
+
+    // the scene target's sample count and supersampling factor in this mode
+    fn settings(self, msaa_samples: u32) -> (u32, u32) {
+        match self {
+            AaMode::None | AaMode::Fxaa => (1, 1),
+            AaMode::Msaa => (msaa_samples, 1),
+            AaMode::Ssaa => (1, AA_MODE_SSAA_FACTOR),
+        }
+    }
+}
+
+// how the instance grid reaches the gpu, cycled with a key to compare their frame rates: one instanced
+// draw, one draw per object, or every object baked into one merged mesh (see mesh::merge)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum GridBatching {
+    Instanced,
+    PerObject,
+    Merged,
+}
+
+impl GridBatching {
+    const ALL: [GridBatching; 3] = [GridBatching::Instanced, GridBatching::PerObject, GridBatching::Merged];
+
+    fn next(self) -> Self {
+        match self {
+            GridBatching::Instanced => GridBatching::PerObject,
+            GridBatching::PerObject => GridBatching::Merged,
+            GridBatching::Merged => GridBatching::Instanced,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            GridBatching::Instanced => "instanced",
+            GridBatching::PerObject => "per object",
+            GridBatching::Merged => "merged",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Lighting {
+    Off,
+    White,
+    Disco,
+}
+
+// command line options: render [--model <file.gltf|file.glb>] [--reversed-z] [--transparent] [--srgb-vertex-colors]
+//     [--turntable <frames>] [--turntable-dir <dir>] [--list-adapters] [--lightmap] [--lightmap-file <image>]
+//     [--quad-texture <image>] [--seed <number>]
+#[derive(Default)]
+struct Args {
+    model: Option<PathBuf>,
+    // json geometry shown instead of the cube and reloaded with a key, see model::load_vertex_file
+    vertex_file: Option<PathBuf>,
+    // the model is z up, see transforms::z_up_to_y_up
+    z_up: bool,
+    // the model comes from a left handed tool and is mirrored here, see transforms::flip_handedness
+    left_handed: bool,
+    // faceted instead of smooth normals wherever they are computed: the vertex file, and models without any
+    flat_normals: bool,
+    reversed_z: bool,
+    transparent: bool,
+    srgb_vertex_colors: bool,
+    // render this many frames of a full turn to turntable_dir and exit instead of opening interactively
+    turntable_frames: Option<u32>,
+    turntable_dir: Option<PathBuf>,
+    // print every adapter on every backend and exit
+    list_adapters: bool,
+    // multiply the scene by a lightmap through the second uv set, generated unless lightmap_file is given
+    lightmap: bool,
+    lightmap_file: Option<PathBuf>,
+    // an image with transparency for the translucent quad in place of its generated cutout
+    quad_texture: Option<PathBuf>,
+    // no msaa and no optional device features, set by --safe-mode or after a crash, see RUNNING_MARKER
+    safe_mode: bool,
+    // build the common scene pipeline variants before the first frame, see State::precompile_pipelines
+    precompile: bool,
+    frames_in_flight: u32,
+    // run this many particle simulation steps without a window, then print a summary and exit
+    headless_steps: Option<u32>,
+    // where settings are loaded from and saved to, app_config::DEFAULT_CONFIG_FILE without --config
+    config_path: Option<PathBuf>,
+    // window attributes, see window_builder. the position is the outer top left corner in physical
+    // pixels, the size the inner size in logical pixels
+    no_decorations: bool,
+    always_on_top: bool,
+    no_resize: bool,
+    window_position: Option<(i32, i32)>,
+    window_size: Option<(u32, u32)>,
+    // seeds everything procedural so it comes out the same each run, see util::rng
+    seed: u64,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = Args {
+            frames_in_flight: transforms::DEFAULT_FRAMES_IN_FLIGHT,
+            seed: rng::DEFAULT_SEED,
+            ..Default::default()
+        };
+        let mut iter = std::env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--model" => args.model = iter.next().map(PathBuf::from),
+                "--vertex-file" => args.vertex_file = iter.next().map(PathBuf::from),
+                "--z-up" => args.z_up = true,
+                "--left-handed" => args.left_handed = true,
+                "--flat-normals" => args.flat_normals = true,
+                "--reversed-z" => args.reversed_z = true,
+                "--transparent" => args.transparent = true,
+                "--srgb-vertex-colors" => args.srgb_vertex_colors = true,
+                "--turntable" => match iter.next().map(|frames| frames.parse::<u32>()) {
+                    Some(Ok(frames)) if frames > 0 => args.turntable_frames = Some(frames),
+                    _ => log::warn!("--turntable expects a frame count above 0"),
+                },
+                "--turntable-dir" => args.turntable_dir = iter.next().map(PathBuf::from),
+                "--list-adapters" => args.list_adapters = true,
+                "--safe-mode" => args.safe_mode = true,
+                "--precompile" => args.precompile = true,
+                "--frames-in-flight" => match iter.next().map(|frames| frames.parse::<u32>()) {
+                    Some(Ok(frames)) if frames > 0 => args.frames_in_flight = frames,
+                    _ => log::warn!("--frames-in-flight expects a count above 0"),
+                },
+                "--headless" => match iter.next().map(|steps| steps.parse::<u32>()) {
+                    Some(Ok(steps)) => args.headless_steps = Some(steps),
+                    _ => log::warn!("--headless expects a step count"),
+                },
+                "--seed" => match iter.next().map(|seed| seed.parse::<u64>()) {
+                    Some(Ok(seed)) => args.seed = seed,
+                    _ => log::warn!("--seed expects a number"),
+                },
+                "--config" => args.config_path = iter.next().map(PathBuf::from),
+                "--lightmap" => args.lightmap = true,
+                "--lightmap-file" => {
+                    args.lightmap = true;
+                    args.lightmap_file = iter.next().map(PathBuf::from);
+                }
+                "--quad-texture" => args.quad_texture = iter.next().map(PathBuf::from),
+                "--no-decorations" => args.no_decorations = true,
+                "--always-on-top" => args.always_on_top = true,
+                "--no-resize" => args.no_resize = true,
+                "--window-position" => match iter.next().as_deref().and_then(|value| parse_pair(value, ',')) {
+                    Some(position) => args.window_position = Some(position),
+                    None => log::warn!("--window-position expects x,y"),
+                },
+                "--window-size" => match iter.next().as_deref().and_then(|value| parse_pair(value, 'x')) {
+                    Some((width, height)) if width > 0 && height > 0 => args.window_size = Some((width, height)),
+                    _ => log::warn!("--window-size expects a width x height above 0, like 640x360"),
+                },
+                _ => log::warn!("ignoring unknown argument {}", arg),
+            }
+        }
+
+        args
+    }
+}
+
+// two numbers separated by separator, like the 10,20 of --window-position
+fn parse_pair<T: std::str::FromStr>(value: &str, separator: char) -> Option<(T, T)> {
+    let (a, b) = value.split_once(separator)?;
+    Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
+
+// the main window as the window arguments ask. an undecorated window's inner size is its whole size,
+// which is what the surface is configured with either way, so the surface needs nothing special
+fn window_builder(args: &Args) -> WindowBuilder {
+    let mut builder = WindowBuilder::new()
+        .with_transparent(args.transparent)
+        .with_decorations(!args.no_decorations)
+        .with_resizable(!args.no_resize);
+    if args.always_on_top {
+        builder = builder.with_window_level(WindowLevel::AlwaysOnTop);
+    }
+    if let Some((x, y)) = args.window_position {
+        builder = builder.with_position(PhysicalPosition::new(x, y));
+    }
+    if let Some((width, height)) = args.window_size {
+        builder = builder.with_inner_size(LogicalSize::new(width, height));
+    }
+    builder
+}
+
+fn vertex(p:[i8;3], m:[i8; 3], c:[i8; 3], n:[i8; 3], mn:[f32; 3], uv:[f32; 2]) -> Vertex {
     Vertex {
         position: [p[0] as f32, p[1] as f32, p[2] as f32, 1.0],
         color: [c[0] as f32, c[1] as f32, c[2] as f32, 1.0],
+        morph_position: [m[0] as f32, m[1] as f32, m[2] as f32, 1.0],
+        normal: [n[0] as f32, n[1] as f32, n[2] as f32],
+        morph_normal: mn,
+        uv,
+        uv1: uv,
+        barycentric: [1.0; 3],
     }
 }
 
-fn create_vertices() -> Vec<Vertex> {
+fn create_instance_buffer(device: &wgpu::Device, tracker: &ResourceTracker, capacity: usize) -> wgpu::Buffer {
+    tracker.create_buffer(device, &wgpu::BufferDescriptor {
+        label: Some("Instance Buffer"),
+        size: (capacity * std::mem::size_of::<Instance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+// rightmost factor of the model matrix, standing z up models up and unmirroring left handed ones. a
+// rotation times a mirror, so its inverse is its transpose
+fn model_basis(z_up: bool, left_handed: bool) -> Matrix4<f32> {
+    let up = if z_up { transforms::z_up_to_y_up() } else { Matrix4::identity() };
+    if left_handed { transforms::flip_handedness(up) } else { up }
+}
+
+// the mesh's bounding box and the radius around the origin enclosing it
+fn mesh_extent(mesh: &Mesh) -> (([f32; 3], [f32; 3]), f32) {
+    let positions: Vec<[f32; 3]> = mesh.vertices
+        .iter()
+        .map(|v| [v.position[0], v.position[1], v.position[2]])
+        .collect();
+    let radius = positions
+        .iter()
+        .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+        .fold(0.0, f32::max);
+
+    (vertex_data::bounding_box(&positions), radius)
+}
+
+// the built in cube unless a vertex file is given and loads
+fn load_vertex_file_or_cube(path: Option<&std::path::Path>, srgb_colors: bool, flat_normals: bool) -> Mesh {
+    let loaded = path.and_then(|path| match model::load_vertex_file(path, flat_normals) {
+        Ok(mesh) => Some(mesh),
+        Err(e) => {
+            log::error!("{:#}, showing the cube instead", e);
+            None
+        }
+    });
+
+    loaded.unwrap_or_else(|| Mesh::from_vertices(create_vertices(srgb_colors)))
+}
+
+// srgb_colors treats the cube colors as srgb and stores them linear, see vertex_data::srgb_to_linear
+fn create_vertices(srgb_colors: bool) -> Vec<Vertex> {
     let pos = vertex_data::cube_positions();
+    let morph = vertex_data::pyramid_positions();
     let col = vertex_data::cube_colors();
+    let normal = vertex_data::cube_normals();
+    let morph_normal = vertex_data::pyramid_normals();
+    let uv = vertex_data::cube_uvs();
     let mut data:Vec<Vertex> = Vec::with_capacity(pos.len());
 
-    for i in 0..pos.len() {
-        data.push(vertex(pos[i], col[i]));
+    for i in 0..pos.len() {
+        let mut v = vertex(pos[i], morph[i], col[i], normal[i], morph_normal[i], uv[i]);
+        if srgb_colors {
+            let [r, g, b] = vertex_data::srgb_to_linear([v.color[0], v.color[1], v.color[2]]);
+            v.color = [r, g, b, v.color[3]];
+        }
+        data.push(v);
+    }
+
+    data.to_vec()
+}
+
+// the scene's projection for the given aspect ratio and depth settings. ortho_radius is the radius the
+// orthographic projection fits, None for perspective. the clip correction only changes the depth range,
+// never the volume, so toggling it shows nothing but the mismatch it corrects
+fn scene_projection(aspect: f32, fovy: Rad<f32>, ortho_radius: Option<f32>, depth_mode: DepthMode, apply_clip_correction: bool) -> Matrix4<f32> {
+    let projection = match ortho_radius {
+        Some(radius) => transforms::create_projection_ortho_fit_with_correction(radius, aspect, transforms::Z_NEAR,
+            transforms::Z_FAR, apply_clip_correction),
+        None => transforms::create_projection_with_fovy(fovy, aspect, true, apply_clip_correction),
+    };
+    depth_mode.projection(projection)
+}
+
+// where turntable captures go without --turntable-dir
+const TURNTABLE_DIR:&str = "frames";
+// screenshots are numbered from screenshot_0000.png up, skipping numbers already taken
+const SCREENSHOT_PREFIX:&str = "screenshot_";
+
+// a camera move toward or away from look_direction over DOLLY_ZOOM_DURATION, with the field of view
+// following so the subject keeps its size. reference_distance is where the view is back at FOVY
+struct DollyZoom {
+    start: std::time::Instant,
+    from: f32,
+    to: f32,
+    reference_distance: f32,
+}
+
+fn pixel_readback_text(position: [u32; 2], pixel: &PixelReadback, depth_mode: DepthMode) -> String {
+    let [r, g, b, a] = pixel.color;
+    let mut text = format!("pixel {} {}: rgba {} {} {} {}", position[0], position[1], r, g, b, a);
+
+    match pixel.depth {
+        Some(depth) if depth == depth_mode.clear_value() => text += ", depth cleared",
+        Some(depth) => text += &format!(", depth {:.5} ({:.2} away)", depth,
+            depth_mode.linearize(depth, transforms::Z_NEAR, transforms::Z_FAR)),
+        None => text += ", depth not readable with msaa",
+    }
+
+    text
+}
+
+// the scene pass into target, clearing color and depth
+fn begin_scene_pass<'pass>(encoder: &'pass mut wgpu::CommandEncoder, target: &'pass RenderTarget, clear_color: wgpu::Color,
+    depth_mode: DepthMode) -> wgpu::RenderPass<'pass> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Render Pass"),
+        color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Clear(clear_color)))],
+        //depth_stencil_attachment: None,
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: &target.depth_view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(depth_mode.clear_value()),
+                // kept for the depth view
+                store: StoreOp::Store,
+            }),
+            stencil_ops: None,
+        }),
+        ..Default::default()
+    })
+}
+
+fn create_pipeline(device: &wgpu::Device, shader: &wgpu::ShaderModule, layout: &wgpu::PipelineLayout,
+    targets: &PipelineTargets, key: &PipelineKey, depth_mode: DepthMode) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[Vertex::desc(), Instance::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: targets.color_format,
+                blend: Some(key.blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: key.primitive(),
+        //depth_stencil: None,
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: targets.depth_format,
+            depth_write_enabled: true,
+            depth_compare: depth_mode.compare(),
+            stencil: wgpu::StencilState::default(),
+            bias: if key.depth_bias { depth_mode.toward_camera(OVERLAY_DEPTH_BIAS) } else { wgpu::DepthBiasState::default() },
+        }),
+        multisample: wgpu::MultisampleState {
+            count: targets.sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    })
+}
+
+// checks the scene pipelines against the main render target and the second window's, if open
+fn validate_render_config(state: &State) -> Result<(), String> {
+    render_target::validate_render_config(&state.init.adapter, &state.target, &state.pipeline_targets)?;
+
+    if let Some(second) = &state.second_window {
+        render_target::validate_render_config(&state.init.adapter, &second.target, &state.pipeline_targets)
+            .map_err(|e| format!("second window: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// a second window showing the scene from its own camera. it shares the device, pipelines, mesh and
+// instance buffer with the main window but has its own surface, render target (and so depth buffer)
+// and uniforms. the instances are still culled against the main camera, which lets the culling be
+// watched from outside the main frustum. lines, the translucent quad and the hud are main window only
+struct SecondWindow {
+    surface: transforms::WindowSurface,
+    target: RenderTarget,
+    blit_bind_groups: BlitBindGroups,
+    view_matrix: Matrix4<f32>,
+    projection_matrix: Matrix4<f32>,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+// a camera that draws nothing, only its frustum is shown from the main camera. it keeps the main
+// window's aspect ratio and looks at the origin
+struct InspectionCamera {
+    position: Point3<f32>,
+    perspective: bool,
+}
+
+impl InspectionCamera {
+    fn view_projection(&self, aspect: f32) -> Matrix4<f32> {
+        let projection = if self.perspective {
+            transforms::create_perspective_projection(FOVY, aspect, INSPECTION_NEAR, INSPECTION_FAR)
+        } else {
+            transforms::create_projection_ortho_fit(INSPECTION_ORTHO_RADIUS, aspect, INSPECTION_NEAR, INSPECTION_FAR)
+        };
+        projection * transforms::create_view(self.position, Point3::origin(), Vector3::unit_y())
+    }
+}
+
+// one orthographic quadrant of the split screen, the one at its index in split_screen::AXIS_VIEWS, with its own copy of the scene uniforms
+struct SplitView {
+    axis: AxisView,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+struct State<'window> {
+    init: transforms::InitWgpu<'window>,
+    window: &'window Window,
+    // one module per entry of SHADER_VARIANTS
+    shaders: Vec<wgpu::ShaderModule>,
+    // the same modules with linear instead of perspective correct interpolation
+    linear_shaders: Vec<wgpu::ShaderModule>,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: PipelineCache,
+    pipeline_key: PipelineKey,
+    // indices into BLEND_COMPONENTS of pipeline_key's blend state
+    blend_color: usize,
+    blend_alpha: usize,
+    wireframe_overlay: bool,
+    overlay_depth_bias: bool,
+    pipeline_targets: PipelineTargets,
+    render_scale: f32,
+    // one of SSAA_FACTORS, multiplies render_scale
+    ssaa_factor: u32,
+    // the sample count AaMode::Msaa switches to, the requested one lowered to what the adapter supports
+    msaa_samples: u32,
+    fxaa: Fxaa,
+    fxaa_bind_group: wgpu::BindGroup,
+    // AaMode::Fxaa, unless supersampling is on
+    fxaa_enabled: bool,
+    target: RenderTarget,
+    blit: Blit,
+    // shadertoy mode: procedural.wgsl fills the window instead of the scene
+    procedural: Procedural,
+    show_procedural: bool,
+    blit_bind_groups: BlitBindGroups,
+    blit_filter: wgpu::FilterMode,
+    // the depth buffer drawn in place of the scene
+    depth_view: DepthView,
+    depth_view_bind_group: wgpu::BindGroup,
+    depth_view_mode: DepthViewMode,
+    cpu_mesh: Mesh,
+    // the mesh is z up and turned upright in the model matrix
+    z_up: bool,
+    // the mesh is left handed and mirrored back in the model matrix
+    left_handed: bool,
+    // what the cube is replaced with and reloaded from, and how the cube is colored when that fails
+    vertex_file: Option<PathBuf>,
+    srgb_vertex_colors: bool,
+    // normals computed for the vertex file are faceted rather than smooth
+    flat_normals: bool,
+    // faces left out of the cube, see vertex_data::cube_indices_excluding
+    hidden_faces: Vec<usize>,
+    mesh: GpuMesh,
+    subdivisions: u32,
+    gradient_colors: bool,
+    animating: bool,
+    animation_time: std::time::Duration,
+    last_elapsed: std::time::Duration,
+    // framerate independent updates, see update. the accumulator is the real time not yet stepped through
+    fixed_timestep: bool,
+    accumulator: f32,
+    // the animation time before the last step, the frame shown lies between it and animation_time
+    previous_animation_time: std::time::Duration,
+    // simulation steps taken since the last frame, for the particles to catch up on in render
+    particle_steps: u32,
+    particle_dt: f32,
+    // only a loaded model's texture benefits from anisotropic filtering, the white fallback doesn't
+    textured: bool,
+    lightmap_bind_group: wgpu::BindGroup,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    texture_bind_groups: AnisotropyBindGroups,
+    // a file is being dragged over the window
+    file_hovered: bool,
+    // a dropped model, loaded once a frame saying so has been shown
+    loading_file: Option<PathBuf>,
+    anisotropy_index: usize,
+    scene: Box<dyn Scene>,
+    // the checkerboard the textured cube scene draws with in place of the model's texture
+    checker_bind_groups: AnisotropyBindGroups,
+    // the startup mesh and its subdivision count, put aside while a scene with its own mesh is shown
+    saved_mesh: Option<(Mesh, u32)>,
+    // from --seed, for procedural content generated after startup
+    seed: u64,
+    instance_buffer: wgpu::Buffer,
+    // instances instance_buffer has room for, it only grows
+    instance_capacity: usize,
+    grid_count: u32,
+    grid_spacing: f32,
+    // the scene Uniforms, one slot per frame in flight; the lights are shared by every slot
+    uniforms: UniformRing,
+    lights_buffer: wgpu::Buffer,
+    uniform_bind_group_layout: wgpu::BindGroupLayout,
+    camera_position: Point3<f32>,
+    look_direction: Point3<f32>,
+    up_direction: Vector3<f32>,
+    model_matrix: Matrix4<f32>,
+    // the model's scale along x, y and z, non-uniform when an axis was scaled on its own
+    model_scale: [f32; 3],
+    view_matrix: Matrix4<f32>,
+    projection_matrix: Matrix4<f32>,
+    // FOVY unless the config file sets another, the second window and the dolly zoom's reference
+    default_fovy: Rad<f32>,
+    // default_fovy except during and after a dolly zoom
+    fovy: Rad<f32>,
+    // IS_PERSPECTIVE unless the config file sets it
+    perspective: bool,
+    // what the orthographic projection fits around the look target, see scene_projection
+    ortho_radius: f32,
+    dolly_zoom: Option<DollyZoom>,
+    depth_mode: DepthMode,
+    // off only to demonstrate the opengl to wgpu depth range mismatch, see create_projection_with_correction
+    apply_clip_correction: bool,
+    instance_positions: Vec<[f32; 3]>,
+    show_instances: bool,
+    visible_instances: usize,
+    grid_batching: GridBatching,
+    // the grid baked into one mesh while grid_batching is merged, rebuilt by upload_mesh
+    merged_mesh: Option<GpuMesh>,
+    // the uploaded mesh with one vertex per index, drawn without the index buffer when indexed_drawing
+    // is off. both are kept so the switch is immediate and their memory can be compared
+    expanded_mesh: GpuMesh,
+    indexed_drawing: bool,
+    // the smoothed fps last seen with each batching mode, indexed like GridBatching::ALL
+    batching_fps: [f32; 3],
+    line_pipeline: LinePipeline,
+    bounding_box_lines: LineBuffer,
+    mesh_bounds: ([f32; 3], [f32; 3]),
+    // radius around the origin enclosing the mesh, used for culling
+    mesh_radius: f32,
+    // what the point list mode draws, every point sized by its distance from the origin when sized_points is on
+    point_cloud: PointCloud,
+    // draws the waves scene's plane in place of the scene pipelines
+    wave: Wave,
+    // the stencil outline around the object last picked, in instance space like the instance buffer
+    outline: Outline,
+    show_outline: bool,
+    selected_instance: Option<Matrix4<f32>>,
+    // the dynamic reflection and the pipeline its faces are drawn with, see toggle_reflection_probe
+    reflection_probe: Option<(ReflectionProbe, wgpu::RenderPipeline)>,
+    sized_points: bool,
+    show_bounding_boxes: bool,
+    reference_grid: ReferenceGrid,
+    particles: Particles,
+    show_particles: bool,
+    show_camera_path: bool,
+    camera_path: LineStrips,
+    rectangular_grid_lines: LineBuffer,
+    polar_grid_lines: LineBuffer,
+    shader_grid: ShaderGrid,
+    show_normals: bool,
+    normal_lines: LineBuffer,
+    cursor_position: PhysicalPosition<f64>,
+    // world space point of the last click on the mesh, None after a miss
+    picked_point: Option<Point3<f32>>,
+    marker_lines: LineBuffer,
+    inspection_camera: Option<InspectionCamera>,
+    frustum_lines: LineBuffer,
+    pick_ray: Option<(Point3<f32>, Point3<f32>)>,
+    debug_lines: DebugLines,
+    // target pixel under the last right click and what was read back there
+    pixel_readback: Option<([u32; 2], PixelReadback)>,
+    morph_weight: f32,
+    // triangle edges drawn by the fragment shader from barycentrics, in the same pass as the faces
+    edge_highlight: bool,
+    // the shader variant toggle_hue_cycle goes back to when hue cycling is turned off
+    shader_before_hue: usize,
+    lighting: Lighting,
+    // keyboard edits of the lighting mode's lights, cleared when the mode changes
+    light_edits: [LightEdit; lights::MAX_LIGHTS],
+    // index of the light the edits go to
+    active_light: usize,
+    modifiers: ModifiersState,
+    // fps camera mode: the cursor is grabbed and the camera looks around by yaw and pitch
+    fps_camera: bool,
+    yaw: f32,
+    pitch: f32,
+    // orbit drag: recent (time, yaw, pitch) steps, and the inertia left after letting go in radians per second
+    orbit_dragging: bool,
+    orbit_steps: VecDeque<(std::time::Instant, f32, f32)>,
+    orbit_velocity: [f32; 2],
+    second_window: Option<SecondWindow>,
+    // the split screen's orthographic quadrants, None for the usual single view
+    split_views: Option<Vec<SplitView>>,
+    // set by the key, the event loop opens or closes the window since that needs the event loop target
+    second_window_requested: bool,
+    text: TextRenderer,
+    translucent: TranslucentQuad,
+    translucent_mode: Option<AlphaMode>,
+    translucent_cubes: TranslucentCubes,
+    // None hides the cubes
+    translucent_cubes_order: Option<DrawOrder>,
+    translucent_mesh: TranslucentMesh,
+    // None hides the mesh, back to front sorts its triangles
+    translucent_mesh_order: Option<DrawOrder>,
+    clear_color: wgpu::Color,
+    show_hud: bool,
+    show_crosshair: bool,
+    fog: bool,
+    fog_density: f32,
+    // index into FOG_COLORS
+    fog_color_index: usize,
+    // physical pixels per logical pixel, for anything sized in logical pixels
+    scale_factor: f64,
+    last_frame: std::time::Instant,
+    fps: f32,
+    // cpu time recording each frame and gpu time executing it, averaged over frame_timing::TIMING_WINDOW
+    cpu_times: TimingWindow,
+    gpu_timer: Option<GpuTimer>,
+    update_start: Option<std::time::Instant>,
+    // the last rendered frame's cost, filled in by render from draw_counter and the timers
+    frame_stats: FrameStats,
+    draw_counter: DrawCounter,
+    // the window was resized to zero, nothing is drawn or animated until it has a size again. the surface
+    // keeps the last size it was configured with, a zero sized surface can't be configured
+    minimized: bool,
+    // the settings loaded at startup, see State::app_config for what is saved on exit
+    app_config: AppConfig,
+}
+
+impl<'window> State<'window> {
+    // State is only built from a windowed init, which always has a surface configuration
+    fn config(&self) -> &wgpu::SurfaceConfiguration {
+        self.init.config.as_ref().expect("windowed init has a surface configuration")
+    }
+
+    async fn new(window: &'window Window, args: &Args, app_config: AppConfig) -> Result<Self, Error> {
+        let backends = app_config.backends.unwrap_or(transforms::BACKENDS);
+        let mut init = transforms::InitWgpu::init_wgpu(window, args.transparent, args.safe_mode, args.frames_in_flight, backends).await?;
+        if let (Some(mode), Some(surface), Some(config)) = (app_config.present_mode, &init.surface, &mut init.config) {
+            let modes = surface.get_capabilities(&init.adapter).present_modes;
+            if modes.contains(&mode) {
+                config.present_mode = mode;
+                surface.configure(&init.device, config);
+            } else {
+                log::warn!("the configured present mode {:?} is not supported (supported: {:?}), keeping {:?}", mode, modes, config.present_mode);
+            }
+        }
+        let config = init.config.clone().ok_or(Error::Unsupported("the window has no surface configuration".to_string()))?;
+        let depth_mode = if args.reversed_z { DepthMode::Reversed } else { DepthMode::Standard };
+        let clear_color = if let Some([r, g, b, a]) = app_config.clear_color {
+            wgpu::Color { r, g, b, a }
+        } else if config.alpha_mode == wgpu::CompositeAlphaMode::PreMultiplied {
+            TRANSPARENT_CLEAR_COLOR
+        } else {
+            CLEAR_COLOR
+        };
+
+        // the cube unless a model was given and loads
+        let model = args.model.as_deref().and_then(|path| match model::load_gltf(path, args.flat_normals) {
+            Ok(model) => Some(model),
+            Err(e) => {
+                log::error!("{:#}, showing the cube instead", e);
+                None
+            }
+        });
+        let (mesh, base_color_image) = match model {
+            Some(model) => (model.mesh, model.base_color_texture),
+            None => (load_vertex_file_or_cube(args.vertex_file.as_deref(), args.srgb_vertex_colors, args.flat_normals), None),
+        };
+
+        let texture = match &base_color_image {
+            Some(image) => Texture::from_image(&init.device, &init.queue, &init.tracker, image, "Base Color Texture"),
+            None => Texture::white(&init.device, &init.queue, &init.tracker),
+        };
+        let texture_bind_group_layout = Texture::bind_group_layout(&init.device);
+        let textured = base_color_image.is_some();
+        let texture_bind_groups = AnisotropyBindGroups::new(&init.device, &init.adapter, &texture, &texture_bind_group_layout);
+        let checker = Texture::from_image(&init.device, &init.queue, &init.tracker, &Texture::checker_image(), "Checker Texture");
+        let checker_bind_groups = AnisotropyBindGroups::new(&init.device, &init.adapter, &checker, &texture_bind_group_layout);
+
+        let lightmap_image = match &args.lightmap_file {
+            Some(path) => match image::open(path) {
+                Ok(image) => Some(image.to_rgba8()),
+                Err(e) => {
+                    log::warn!("could not load lightmap {}: {}, using a generated one", path.display(), e);
+                    Some(Texture::gradient_lightmap())
+                }
+            },
+            None => args.lightmap.then(Texture::gradient_lightmap),
+        };
+        let lightmap = match &lightmap_image {
+            Some(image) => Texture::from_image(&init.device, &init.queue, &init.tracker, image, "Lightmap Texture"),
+            None => Texture::white(&init.device, &init.queue, &init.tracker),
+        };
+        let lightmap_bind_group = lightmap.bind_group(&init.device, &texture_bind_group_layout);
+
+        let create_shaders = |linear_interpolation| -> Vec<wgpu::ShaderModule> {
+            SHADER_VARIANTS
+                .iter()
+                .chain([&WIREFRAME_OVERLAY_VARIANT])
+                .map(|variant| create_shader(&init.device, variant, linear_interpolation))
+                .collect()
+        };
+        let shaders = create_shaders(false);
+        let linear_shaders = create_shaders(true);
+
+        // uniform data
+        let camera = app_config.camera.unwrap_or(CameraConfig { position: [3.0, 1.5, 3.0], target: [0.0, 0.0, 0.0] });
+        let camera_position = Point3::from(camera.position);
+        let look_direction = Point3::from(camera.target);
+        let up_direction = cgmath::Vector3::unit_y();
+        let default_fovy = app_config.fovy_degrees.map_or(FOVY, |degrees| cgmath::Deg(degrees).into());
+        let perspective = app_config.perspective.unwrap_or(IS_PERSPECTIVE);
+        // an orthographic view as large as the perspective one is at the look target
+        let ortho_radius = camera_position.distance(look_direction) * (default_fovy.0 / 2.0).tan();
+        
+        let model_matrix = transforms::create_transforms([0.0,0.0,0.0], [0.0,0.0,0.0], MODEL_SCALE_DEFAULT) * model_basis(args.z_up, args.left_handed);
+        let view_matrix = transforms::create_view(camera_position, look_direction, up_direction);
+        let projection_matrix = scene_projection(config.width as f32 / config.height as f32, default_fovy, (!perspective).then_some(ortho_radius),
+            depth_mode, true);
+        let view_projection_matrix = projection_matrix * view_matrix;
+        
+        let uniforms = Uniforms {
+            view_projection_matrix: view_projection_matrix.into(),
+            model_matrix: model_matrix.into(),
+            morph_weight: 0.0,
+            edge_width: 0.0,
+            fog_density: FOG_DENSITY,
+            fog_enabled: 0,
+            camera_position: camera_position.to_homogeneous().into(),
+            fog_color: [0.0; 4],
+            time: 0.0,
+            _padding: [0.0; 3],
+        };
+        let lights_buffer = init.tracker.create_buffer_init(&init.device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Lights Buffer"),
+            contents: bytemuck::cast_slice(&[Lights::new(&[])]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group_layout = init.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor{
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }, wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Uniform Bind Group Layout"),
+        });
+
+        let uniforms = UniformRing::new(&init.device, &init.tracker, &uniform_bind_group_layout, bytemuck::bytes_of(&uniforms),
+            &[wgpu::BindGroupEntry {
+                binding: 1,
+                resource: lights_buffer.as_entire_binding(),
+            }], args.frames_in_flight);
+
+        let pipeline_layout = init.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&uniform_bind_group_layout, &texture_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let msaa_samples = render_target::clamp_sample_count(&init.adapter, &init.device, config.format, app_config.msaa_samples.unwrap_or(MSAA_SAMPLES));
+        let sample_count = if args.safe_mode { 1 } else { msaa_samples };
+        log::info!("{}", render_target::aa_report(&init.adapter, &init.device, config.format, sample_count, (config.width, config.height)));
+        let pipeline_key = PipelineKey {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            front_face: wgpu::FrontFace::Ccw,
+            //cull_mode: Some(wgpu::Face::Back),
+            cull_mode: None,
+            blend: wgpu::BlendState::REPLACE,
+            shader: 0,
+            depth_bias: false,
+            linear_interpolation: false,
+            sample_count,
+        };
+        let pipeline_targets = PipelineTargets {
+            color_format: config.format,
+            depth_format: render_target::DEPTH_FORMAT,
+            sample_count,
+        };
+        let mut pipelines = PipelineCache::default();
+        pipelines.get_or_insert_with(pipeline_key, |key| {
+            create_pipeline(&init.device, &shaders[key.shader], &pipeline_layout, &pipeline_targets, key, depth_mode)
+        });
+
+        let render_scale = RENDER_SCALE_MAX;
+        let (target_width, target_height) = render_target::scaled_size(config.width, config.height, render_scale);
+        let target = RenderTarget::new(&init.device, &init.tracker, config.format, target_width, target_height, sample_count);
+        let blit = Blit::new(&init.device, config.format);
+        let procedural = Procedural::new(&init.device, &init.tracker, config.format);
+        let particles = Particles::new(&init.device, &init.tracker, config.format, sample_count, depth_mode, PARTICLE_COUNT, args.seed);
+        let blit_bind_groups = blit.bind_groups(&init.device, &target.color_view);
+        let fxaa = Fxaa::new(&init.device, &init.tracker, config.format);
+        let fxaa_bind_group = fxaa.bind_group(&init.device, &target.color_view);
+        fxaa.write(&init.queue, (target.width, target.height));
+        let depth_view = DepthView::new(&init.device, &init.tracker, config.format, sample_count);
+        let depth_view_bind_group = depth_view.bind_group(&init.device, &target.depth_view);
+
+        let (mesh_bounds, mesh_radius) = mesh_extent(&mesh);
+        let cpu_mesh = mesh;
+        let mesh = GpuMesh::new(&init.device, &init.tracker, &mesh::deduplicate(&cpu_mesh));
+        let expanded_mesh = GpuMesh::new(&init.device, &init.tracker, &mesh::expand(&cpu_mesh));
+        let mut point_cloud = PointCloud::new(&init.device, &init.tracker, config.format, sample_count, depth_mode,
+            &uniform_bind_group_layout, Instance::desc());
+        point_cloud.set_points(&init.device, &init.tracker, &cpu_mesh.vertices, None);
+        point_cloud.write_viewport(&init.queue, [target.width as f32, target.height as f32]);
+        let wave = Wave::new(&init.device, &init.tracker, config.format, sample_count, depth_mode, &uniform_bind_group_layout,
+            Instance::desc());
+        let outline = Outline::new(&init.device, &init.tracker, config.format, target.width, target.height,
+            &uniform_bind_group_layout, Instance::desc());
+
+        // sized for the whole grid, only the visible instances are rewritten each frame
+        let instance_positions = vertex_data::instance_grid(GRID_COUNT, GRID_SPACING);
+        let instance_buffer = create_instance_buffer(&init.device, &init.tracker, instance_positions.len());
+
+        let line_pipeline = LinePipeline::new(&init.device, &init.tracker, config.format, sample_count, depth_mode);
+        line_pipeline.write_view_projection(&init.queue, view_projection_matrix);
+        // one box of 24 vertices per instance at most
+        let bounding_box_lines = LineBuffer::new(&init.device, &init.tracker, 24 * instance_positions.len());
+        let marker_lines = LineBuffer::new(&init.device, &init.tracker, 6);
+        let frustum_lines = LineBuffer::new(&init.device, &init.tracker, 24);
+        let debug_lines = DebugLines::new(&init.device, &init.tracker, MAX_DEBUG_LINES);
+        let normal_lines = LineBuffer::new(&init.device, &init.tracker, 0);
+        let grid_buffer = |mut lines: Vec<LineVertex>| {
+            for line in &mut lines {
+                line.position[1] += REFERENCE_GRID_HEIGHT;
+            }
+            let mut buffer = LineBuffer::new(&init.device, &init.tracker, lines.len());
+            buffer.write(&init.device, &init.queue, &init.tracker, &lines);
+            buffer
+        };
+        let rectangular_grid_lines = grid_buffer(vertex_data::rectangular_grid(REFERENCE_GRID_EXTENT, REFERENCE_GRID_SPACING));
+        // two strips in one draw: the spline and, after a restart, its closed control polygon
+        let line_strip = |points: Vec<[f32; 3]>, color| points.into_iter().map(|position| LineVertex { position, color }).collect();
+        let camera_path = LineStrips::new(&init.device, &init.tracker, &[
+            line_strip(vertex_data::catmull_rom_loop(&CAMERA_PATH_POINTS, CAMERA_PATH_SEGMENTS), CAMERA_PATH_COLOR),
+            line_strip(CAMERA_PATH_POINTS.iter().chain(CAMERA_PATH_POINTS.first()).copied().collect(), CAMERA_PATH_CONTROL_COLOR),
+        ]);
+        let polar_grid_lines = grid_buffer(vertex_data::polar_grid(REFERENCE_GRID_RINGS, REFERENCE_GRID_SPOKES, REFERENCE_GRID_EXTENT));
+        let gpu_timer = GpuTimer::new(&init.device, &init.queue, &init.tracker);
+        if gpu_timer.is_none() {
+            log::info!("the adapter has no timestamp queries, the hud shows cpu frame time only");
+        }
+        let shader_grid = ShaderGrid::new(&init.device, &init.tracker, config.format, sample_count, depth_mode, REFERENCE_GRID_SPACING,
+            REFERENCE_GRID_HEIGHT);
+        shader_grid.write(&init.queue, view_projection_matrix, camera_position);
+
+        let text = TextRenderer::new(&init.device, &init.queue, &init.tracker, config.format);
+        let mut translucent = TranslucentQuad::new(&init.device, &init.queue, &init.tracker, config.format, sample_count, depth_mode);
+        if let Some(path) = &args.quad_texture {
+            let straight = texture::load_texture(&init.device, &init.queue, &init.tracker, path);
+            let premultiplied = texture::load_texture_premultiplied(&init.device, &init.queue, &init.tracker, path);
+            match straight.and_then(|straight| Ok((straight, premultiplied?))) {
+                Ok((straight, premultiplied)) => translucent.set_textures(&init.device, &straight, &premultiplied),
+                Err(e) => log::warn!("{:#}, the translucent quad keeps its cutout", e),
+            }
+        }
+        let translucent_cubes = TranslucentCubes::new(&init.device, &init.tracker, config.format, sample_count, depth_mode);
+        let translucent_mesh = TranslucentMesh::new(&init.device, &init.tracker, config.format, sample_count, depth_mode);
+
+        Ok(Self {
+            init,
+            window,
+            shaders,
+            linear_shaders,
+            pipeline_layout,
+            pipelines,
+            pipeline_key,
+            blend_color: 0,
+            blend_alpha: 0,
+            wireframe_overlay: false,
+            overlay_depth_bias: true,
+            pipeline_targets,
+            render_scale,
+            ssaa_factor: 1,
+            msaa_samples,
+            fxaa,
+            fxaa_bind_group,
+            fxaa_enabled: false,
+            target,
+            blit,
+            procedural,
+            show_procedural: false,
+            blit_bind_groups,
+            blit_filter: wgpu::FilterMode::Linear,
+            depth_view,
+            depth_view_bind_group,
+            depth_view_mode: DepthViewMode::Off,
+            cpu_mesh,
+            z_up: args.z_up,
+            left_handed: args.left_handed,
+            vertex_file: args.vertex_file.clone(),
+            srgb_vertex_colors: args.srgb_vertex_colors,
+            flat_normals: args.flat_normals,
+            hidden_faces: Vec::new(),
+            mesh,
+            subdivisions: 0,
+            gradient_colors: false,
+            animating: true,
+            animation_time: std::time::Duration::ZERO,
+            last_elapsed: std::time::Duration::ZERO,
+            fixed_timestep: false,
+            accumulator: 0.0,
+            previous_animation_time: std::time::Duration::ZERO,
+            particle_steps: 0,
+            particle_dt: 0.0,
+            textured,
+            lightmap_bind_group,
+            texture_bind_group_layout,
+            texture_bind_groups,
+            file_hovered: false,
+            loading_file: None,
+            anisotropy_index: 0,
+            scene: Box::new(CubeScene),
+            checker_bind_groups,
+            saved_mesh: None,
+            seed: args.seed,
+            instance_buffer,
+            instance_capacity: instance_positions.len(),
+            grid_count: GRID_COUNT,
+            grid_spacing: GRID_SPACING,
+            uniforms,
+            lights_buffer,
+            uniform_bind_group_layout,
+            camera_position,
+            look_direction,
+            up_direction,
+            model_matrix,
+            model_scale: MODEL_SCALE_DEFAULT,
+            view_matrix,
+            projection_matrix,
+            default_fovy,
+            fovy: default_fovy,
+            perspective,
+            ortho_radius,
+            dolly_zoom: None,
+            depth_mode,
+            apply_clip_correction: true,
+            instance_positions,
+            show_instances: false,
+            visible_instances: 0,
+            grid_batching: GridBatching::Instanced,
+            merged_mesh: None,
+            expanded_mesh,
+            indexed_drawing: true,
+            batching_fps: [0.0; 3],
+            line_pipeline,
+            bounding_box_lines,
+            mesh_bounds,
+            mesh_radius,
+            point_cloud,
+            wave,
+            outline,
+            show_outline: false,
+            selected_instance: None,
+            reflection_probe: None,
+            sized_points: false,
+            show_bounding_boxes: false,
+            reference_grid: ReferenceGrid::Off,
+            particles,
+            show_particles: false,
+            show_camera_path: false,
+            camera_path,
+            rectangular_grid_lines,
+            polar_grid_lines,
+            shader_grid,
+            show_normals: false,
+            normal_lines,
+            cursor_position: PhysicalPosition::new(0.0, 0.0),
+            picked_point: None,
+            marker_lines,
+            inspection_camera: None,
+            frustum_lines,
+            pick_ray: None,
+            debug_lines,
+            pixel_readback: None,
+            morph_weight: 0.0,
+            edge_highlight: false,
+            shader_before_hue: 0,
+            lighting: Lighting::Off,
+            light_edits: Default::default(),
+            active_light: 0,
+            modifiers: ModifiersState::empty(),
+            fps_camera: false,
+            yaw: 0.0,
+            pitch: 0.0,
+            orbit_dragging: false,
+            orbit_steps: VecDeque::new(),
+            orbit_velocity: [0.0, 0.0],
+            second_window: None,
+            split_views: None,
+            second_window_requested: false,
+            text,
+            translucent,
+            translucent_mode: None,
+            translucent_cubes,
+            translucent_cubes_order: None,
+            translucent_mesh,
+            translucent_mesh_order: None,
+            clear_color,
+            show_hud: true,
+            show_crosshair: false,
+            fog: false,
+            fog_density: FOG_DENSITY,
+            fog_color_index: 0,
+            scale_factor: window.scale_factor(),
+            last_frame: std::time::Instant::now(),
+            minimized: false,
+            fps: 0.0,
+            cpu_times: TimingWindow::default(),
+            frame_stats: FrameStats::default(),
+            draw_counter: DrawCounter::default(),
+            gpu_timer,
+            update_start: None,
+            app_config,
+        })
+    }
+
+    // the settings to save on exit: what was loaded at startup with the current present mode, clear
+    // color and camera. the msaa sample count stays the requested one, not what safe mode or the
+    // adapter clamped it to
+    fn app_config(&self) -> AppConfig {
+        AppConfig {
+            present_mode: Some(self.config().present_mode),
+            clear_color: Some([self.clear_color.r, self.clear_color.g, self.clear_color.b, self.clear_color.a]),
+            perspective: Some(self.perspective),
+            fovy_degrees: Some(cgmath::Deg::from(self.default_fovy).0),
+            camera: Some(CameraConfig {
+                position: self.camera_position.into(),
+                target: self.look_direction.into(),
+            }),
+            ..self.app_config.clone()
+        }
+    }
+
+    fn toggle_clip_correction(&mut self) {
+        self.apply_clip_correction = !self.apply_clip_correction;
+        self.update_projection();
+        self.update_title();
+    }
+
+    // moves the color or the alpha blend component to the next entry of BLEND_COMPONENTS, the
+    // other one stays as it is
+    fn cycle_blend_component(&mut self, alpha: bool) {
+        let index = if alpha { &mut self.blend_alpha } else { &mut self.blend_color };
+        *index = (*index + 1) % BLEND_COMPONENTS.len();
+
+        self.set_pipeline_key(PipelineKey {
+            blend: wgpu::BlendState {
+                color: BLEND_COMPONENTS[self.blend_color].1,
+                alpha: BLEND_COMPONENTS[self.blend_alpha].1,
+            },
+            ..self.pipeline_key
+        });
+        self.update_title();
+    }
+
+    fn toggle_hue_cycle(&mut self) {
+        let shader = if self.pipeline_key.shader == HUE_SHADER {
+            self.shader_before_hue
+        } else {
+            self.shader_before_hue = self.pipeline_key.shader;
+            HUE_SHADER
+        };
+        self.set_pipeline_key(PipelineKey { shader, ..self.pipeline_key });
+        self.update_title();
+    }
+
+    fn cycle_shader(&mut self) {
+        self.set_pipeline_key(PipelineKey {
+            shader: (self.pipeline_key.shader + 1) % SHADER_VARIANTS.len(),
+            ..self.pipeline_key
+        });
+        self.update_title();
+    }
+
+    // switch to the pipeline for key, creating it only the first time that combination is used
+    fn set_pipeline_key(&mut self, key: PipelineKey) {
+        self.pipeline_key = key;
+        self.ensure_pipeline(key);
+        if self.wireframe_overlay {
+            self.ensure_pipeline(self.overlay_key());
+        }
+        self.check_render_config();
+    }
+
+    fn ensure_pipeline(&mut self, key: PipelineKey) {
+        let cached = self.pipelines.len();
+        let shaders = if key.linear_interpolation { &self.linear_shaders } else { &self.shaders };
+        self.pipelines.get_or_insert_with(key, |key| {
+            create_pipeline(&self.init.device, &shaders[key.shader], &self.pipeline_layout, &self.pipeline_targets,
+                key, self.depth_mode)
+        });
+        if self.pipelines.len() == cached {
+            log::debug!("reusing cached pipeline for {:?}", key);
+        }
+    }
+
+    // builds the variants the toggles switch to into the pipeline cache up front, so the first switch
+    // to each doesn't stall a frame on pipeline compilation: every shader filled and blended, points,
+    // and where POLYGON_MODE_LINE is available wireframe and the wireframe overlay with and without
+    // its depth bias. variants for a feature or backend the toggles would refuse are skipped
+    fn precompile_pipelines(&mut self) {
+        let start = std::time::Instant::now();
+        let cached = self.pipelines.len();
+        let base = self.pipeline_key;
+        let line_mode = self.init.device.features().contains(wgpu::Features::POLYGON_MODE_LINE);
+        let linear = self.init.adapter.get_info().backend != wgpu::Backend::Gl;
+        let alpha_blend = wgpu::BlendState {
+            color: BLEND_COMPONENTS[1].1,
+            alpha: BLEND_COMPONENTS[1].1,
+        };
+
+        let mut keys = Vec::new();
+        for shader in 0..SHADER_VARIANTS.len() {
+            let solid = PipelineKey { shader, ..base };
+            keys.push(solid);
+            keys.push(PipelineKey { blend: alpha_blend, ..solid });
+            keys.push(PipelineKey { topology: wgpu::PrimitiveTopology::PointList, polygon_mode: wgpu::PolygonMode::Fill, ..solid });
+            if line_mode {
+                keys.push(PipelineKey { topology: wgpu::PrimitiveTopology::TriangleList, polygon_mode: wgpu::PolygonMode::Line, ..solid });
+            }
+            if linear {
+                keys.push(PipelineKey { linear_interpolation: !base.linear_interpolation, ..solid });
+            }
+        }
+        if line_mode {
+            for depth_bias in [true, false] {
+                keys.push(PipelineKey { depth_bias, ..self.overlay_key() });
+            }
+        }
+
+        for key in keys {
+            self.ensure_pipeline(key);
+        }
+        log::info!("precompiled {} pipelines in {:.1} ms", self.pipelines.len() - cached, start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    // the wireframe overlay draws the same triangles as lines over the current pipeline's faces
+    fn overlay_key(&self) -> PipelineKey {
+        PipelineKey {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            polygon_mode: wgpu::PolygonMode::Line,
+            shader: WIREFRAME_OVERLAY_SHADER,
+            depth_bias: self.overlay_depth_bias,
+            ..self.pipeline_key
+        }
+    }
+
+    fn toggle_wireframe_overlay(&mut self) {
+        if !self.init.device.features().contains(wgpu::Features::POLYGON_MODE_LINE) {
+            log::warn!("the wireframe overlay requires POLYGON_MODE_LINE, which this adapter does not support");
+            return;
+        }
+
+        self.wireframe_overlay = !self.wireframe_overlay;
+        if self.wireframe_overlay {
+            self.ensure_pipeline(self.overlay_key());
+        }
+        self.update_title();
+    }
+
+    // without the bias the overlay's edges z-fight with the faces they lie on
+    fn toggle_overlay_depth_bias(&mut self) {
+        self.overlay_depth_bias = !self.overlay_depth_bias;
+        if self.wireframe_overlay {
+            self.ensure_pipeline(self.overlay_key());
+        }
+        self.update_title();
+    }
+
+    // switch to the pipeline for these rasterizer settings, built the first time and taken from the cache after
+    fn rebuild_pipeline(&mut self, topology: wgpu::PrimitiveTopology, polygon_mode: wgpu::PolygonMode,
+        front_face: wgpu::FrontFace, cull_mode: Option<wgpu::Face>) {
+        self.set_pipeline_key(PipelineKey {
+            topology,
+            polygon_mode,
+            front_face,
+            cull_mode,
+            ..self.pipeline_key
+        });
+    }
+
+    fn toggle_wireframe(&mut self) {
+        if !self.init.device.features().contains(wgpu::Features::POLYGON_MODE_LINE) {
+            log::warn!("wireframe mode requires POLYGON_MODE_LINE, which this adapter does not support");
+            return;
+        }
+
+        let polygon_mode = match self.pipeline_key.polygon_mode {
+            wgpu::PolygonMode::Line => wgpu::PolygonMode::Fill,
+            _ => wgpu::PolygonMode::Line,
+        };
+        self.rebuild_pipeline(wgpu::PrimitiveTopology::TriangleList, polygon_mode, self.pipeline_key.front_face, self.pipeline_key.cull_mode);
+    }
+
+    fn toggle_points(&mut self) {
+        let topology = match self.pipeline_key.topology {
+            wgpu::PrimitiveTopology::PointList => wgpu::PrimitiveTopology::TriangleList,
+            _ => wgpu::PrimitiveTopology::PointList,
+        };
+        self.rebuild_pipeline(topology, wgpu::PolygonMode::Fill, self.pipeline_key.front_face, self.pipeline_key.cull_mode);
+    }
+
+    // @interpolate(linear) becomes noperspective in glsl, which opengl es (and so webgl) only has through
+    // the NV_shader_noperspective_interpolation extension, so the gl backend is left out. vulkan, metal
+    // and dx12 all support it
+    fn toggle_linear_interpolation(&mut self) {
+        if self.init.adapter.get_info().backend == wgpu::Backend::Gl {
+            log::warn!("linear interpolation is not reliably supported on the gl backend");
+            return;
+        }
+
+        self.set_pipeline_key(PipelineKey {
+            linear_interpolation: !self.pipeline_key.linear_interpolation,
+            ..self.pipeline_key
+        });
+        self.update_title();
+    }
+
+    // between the default point size and sizes growing with the distance from the origin
+    fn toggle_point_sizes(&mut self) {
+        self.sized_points = !self.sized_points;
+        self.upload_mesh();
+        self.update_title();
+    }
+
+    fn update_title(&self) {
+        let mut title = String::from(TITLE);
+
+        if let Some(path) = &self.loading_file {
+            title += &format!(" - loading {}...", path.display());
+        }
+
+        if self.ssaa_factor > 1 {
+            let cost = self.ssaa_factor * self.ssaa_factor;
+            title += &format!(" - {}x ssaa ({}x{}, {}x the pixels)", self.ssaa_factor, self.target.width, self.target.height, cost);
+        }
+
+        if self.render_scale != RENDER_SCALE_MAX {
+            title += &format!(" - render scale {:.2} ({}x{})", self.render_scale, self.target.width, self.target.height);
+        }
+
+        if !self.animating {
+            title += " - paused";
+        }
+
+        if self.edge_highlight {
+            title += " - edges";
+        }
+
+        if self.pipeline_key.linear_interpolation {
+            title += " - linear interpolation";
+        }
+
+        if self.pipeline_key.topology == wgpu::PrimitiveTopology::PointList && self.sized_points {
+            title += " - points sized by distance";
+        }
+
+        if self.fps_camera {
+            title += " - fps camera (esc to release)";
+        }
+
+        if !self.apply_clip_correction {
+            title += " - no clip correction";
+        }
+
+        if self.pipeline_key.shader != 0 {
+            title += &format!(" - {} shader", SHADER_VARIANTS[self.pipeline_key.shader].name);
+        }
+
+        if self.config().present_mode != wgpu::PresentMode::Fifo {
+            title += &format!(" - {:?} present", self.config().present_mode);
+        }
+
+        if self.blend_color != 0 || self.blend_alpha != 0 {
+            title += &format!(" - blend color {}, alpha {}", BLEND_COMPONENTS[self.blend_color].0, BLEND_COMPONENTS[self.blend_alpha].0);
+        }
+
+        if self.subdivisions > 0 {
+            title += &format!(" - subdivided {}x ({} triangles)", self.subdivisions, self.cpu_mesh.indices.len() / 3);
+        }
+
+        if self.gradient_colors {
+            title += " - height gradient";
+        }
+
+        if self.z_up {
+            title += " - z up";
+        }
+
+        if self.left_handed {
+            title += " - left handed";
+        }
+
+        match self.depth_view_mode {
+            DepthViewMode::Off => {}
+            DepthViewMode::Grayscale => title += " - depth view",
+            DepthViewMode::Ramp => title += " - depth view (color ramp)",
+        }
+
+        match self.pipeline_key.cull_mode {
+            None => {}
+            Some(wgpu::Face::Back) => title += " - back faces culled",
+            // only the far, inward facing sides are left, which shows the inside of closed meshes
+            Some(wgpu::Face::Front) => title += " - front faces culled",
+        }
+
+        if self.wireframe_overlay {
+            title += if self.overlay_depth_bias { " - wireframe overlay" } else { " - wireframe overlay without depth bias" };
+        }
+
+        if self.morph_weight > 0.0 {
+            title += &format!(" - morph {:.1}", self.morph_weight);
+        }
+
+        match self.lighting {
+            Lighting::Off => {}
+            Lighting::White => title += " - lit",
+            Lighting::Disco => title += &format!(" - disco ({} lights)", DISCO_LIGHT_COUNT),
+        }
+
+        if self.show_outline {
+            title += " - outline";
+        }
+
+        if self.model_scale != MODEL_SCALE_DEFAULT {
+            let [x, y, z] = self.model_scale;
+            if x == y && y == z {
+                title += &format!(" - scale {:.2}", x);
+            } else {
+                title += &format!(" - scale {:.2} {:.2} {:.2}", x, y, z);
+            }
+        }
+
+        if let Some((probe, _)) = &self.reflection_probe {
+            title += &format!(" - reflection probe every {} frames", probe.update_interval());
+        }
+
+        if let Some(views) = &self.split_views {
+            let names: Vec<&str> = views.iter().map(|view| view.axis.name()).collect();
+            title += &format!(" - split screen ({} and perspective)", names.join(", "));
+        }
+
+        if let Some(scene) = self.scene.title(self) {
+            title += &format!(" - {}", scene);
+        }
+
+        if self.textured || self.scene.texture(self).is_some() {
+            title += &format!(" - anisotropy {}x", self.active_texture_bind_groups().levels[self.anisotropy_index].0);
+        }
+
+        match self.translucent_mode {
+            None => {}
+            Some(AlphaMode::Straight) => title += " - straight alpha quad",
+            Some(AlphaMode::Premultiplied) => title += " - premultiplied alpha quad",
+        }
+
+        match self.translucent_cubes_order {
+            None => {}
+            Some(DrawOrder::BackToFront) => title += " - sorted translucent cubes",
+            Some(DrawOrder::Creation) => title += " - unsorted translucent cubes",
+        }
+
+        match self.translucent_mesh_order {
+            None => {}
+            Some(DrawOrder::BackToFront) => title += &format!(" - translucent torus, {} triangles sorted", self.translucent_mesh.indices.len() / 3),
+            Some(DrawOrder::Creation) => title += " - translucent torus, unsorted",
+        }
+
+        if self.blit_filter == wgpu::FilterMode::Nearest {
+            title += " - nearest filtering";
+        }
+
+        if !self.perspective {
+            title += " - orthographic";
+        }
+
+        if self.fog {
+            title += &format!(" - {} fog {:.3}", FOG_COLORS[self.fog_color_index].0, self.fog_density);
+        }
+
+        if self.fixed_timestep {
+            title += &format!(" - fixed {:.0} hz timestep", 1.0 / FIXED_DT);
+        }
+
+        if let Some(camera) = &self.inspection_camera {
+            title += if camera.perspective { " - perspective frustum" } else { " - orthographic frustum" };
+        }
+
+        if self.reference_grid == ReferenceGrid::Shader {
+            title += &format!(" - shader grid fading out at {}{}", self.shader_grid.fade_distance,
+                if self.shader_grid.antialias { "" } else { ", aliased" });
+        }
+
+        if self.show_instances {
+            let total = self.instance_positions.len();
+            title += &format!(
+                " - {}x{} grid {} apart, culled {} of {} instances ({} visible)",
+                self.grid_count, self.grid_count, self.grid_spacing, total - self.visible_instances, total, self.visible_instances
+            );
+            title += &format!(" - {} draws", self.grid_batching.name());
+        }
+
+        self.window.set_title(&title);
+    }
+
+    // (re)create the offscreen target at the current render scale and supersampling factor of the
+    // surface size. a factor that would exceed the device's texture size limit is lowered until it fits
+    fn recreate_target(&mut self) {
+        let max_size = self.init.device.limits().max_texture_dimension_2d;
+        let (mut width, mut height) = self.target_size();
+        while self.ssaa_factor > 1 && width.max(height) > max_size {
+            self.ssaa_factor /= 2;
+            log::warn!("the supersampled target is larger than the {} texel limit, lowering ssaa to {}x", max_size, self.ssaa_factor);
+            (width, height) = self.target_size();
+        }
+
+        self.target = RenderTarget::new(&self.init.device, &self.init.tracker, self.config().format, width, height,
+            self.pipeline_targets.sample_count);
+        self.blit_bind_groups = self.blit.bind_groups(&self.init.device, &self.target.color_view);
+        self.fxaa_bind_group = self.fxaa.bind_group(&self.init.device, &self.target.color_view);
+        self.fxaa.write(&self.init.queue, (width, height));
+        self.depth_view_bind_group = self.depth_view.bind_group(&self.init.device, &self.target.depth_view);
+        self.outline.resize(&self.init.device, &self.init.tracker, width, height);
+        // point sizes stay in window pixels at any render scale or supersampling
+        self.point_cloud.write_viewport(&self.init.queue, [self.config().width as f32, self.config().height as f32]);
+        self.check_render_config();
+    }
+
+    // run after anything that recreates attachments or pipelines
+    fn check_render_config(&self) {
+        if let Err(e) = validate_render_config(self) {
+            log::error!("inconsistent render configuration: {}", e);
+        }
+    }
+
+    fn cycle_anisotropy(&mut self) {
+        if !self.textured && self.scene.texture(self).is_none() {
+            log::info!("anisotropic filtering only applies to textured models, load one with --model or show the textured cube scene");
+            return;
+        }
+
+        self.anisotropy_index = (self.anisotropy_index + 1) % self.active_texture_bind_groups().levels.len();
+        self.update_title();
+    }
+
+    fn cycle_depth_view(&mut self) {
+        self.depth_view_mode = self.depth_view_mode.next();
+        self.update_title();
+    }
+
+    fn toggle_blit_filter(&mut self) {
+        self.blit_filter = match self.blit_filter {
+            wgpu::FilterMode::Linear => wgpu::FilterMode::Nearest,
+            wgpu::FilterMode::Nearest => wgpu::FilterMode::Linear,
+        };
+        self.update_title();
+    }
+
+    fn target_size(&self) -> (u32, u32) {
+        render_target::scaled_size(self.config().width, self.config().height, self.render_scale * self.ssaa_factor as f32)
+    }
+
+    fn aa_mode(&self) -> AaMode {
+        if self.ssaa_factor > 1 {
+            AaMode::Ssaa
+        } else if self.fxaa_enabled {
+            AaMode::Fxaa
+        } else if self.pipeline_targets.sample_count > 1 {
+            AaMode::Msaa
+        } else {
+            AaMode::None
+        }
+    }
+
+    // msaa is skipped where the adapter has no multisampling for the surface format
+    fn cycle_aa_mode(&mut self) {
+        let mut mode = self.aa_mode().next();
+        if mode == AaMode::Msaa && self.msaa_samples == 1 {
+            mode = mode.next();
+        }
+
+        let (sample_count, ssaa_factor) = mode.settings(self.msaa_samples);
+        self.ssaa_factor = ssaa_factor;
+        self.fxaa_enabled = mode == AaMode::Fxaa;
+        if sample_count != self.pipeline_targets.sample_count {
+            self.set_sample_count(sample_count);
+        } else {
+            self.recreate_target();
+        }
+        log::info!("anti-aliasing: {}", self.aa_text());
+        self.update_title();
+    }
+
+    // only what has the sample count baked in is rebuilt: the scene targets, the scene pipelines and
+    // the pipelines of the renderers drawing into the scene pass. the scene pipelines are cached per
+    // sample count, so switching back reuses them. the other renderers keep their buffers and
+    // textures and only take the pipelines of ones built for the new count
+    fn set_sample_count(&mut self, sample_count: u32) {
+        let (device, tracker, format, depth_mode) = (&self.init.device, &self.init.tracker, self.config().format, self.depth_mode);
+        self.line_pipeline = LinePipeline::new(device, tracker, format, sample_count, depth_mode);
+        self.translucent_cubes.pipeline = TranslucentCubes::new(device, tracker, format, sample_count, depth_mode).pipeline;
+        self.translucent_mesh.pipeline = TranslucentMesh::new(device, tracker, format, sample_count, depth_mode).pipeline;
+        self.shader_grid.pipeline = ShaderGrid::new(device, tracker, format, sample_count, depth_mode, self.shader_grid.spacing,
+            self.shader_grid.height).pipeline;
+        self.particles.render_pipeline = Particles::new(device, tracker, format, sample_count, depth_mode, 1, self.seed).render_pipeline;
+        self.point_cloud.pipeline = PointCloud::new(device, tracker, format, sample_count, depth_mode,
+            &self.uniform_bind_group_layout, Instance::desc()).pipeline;
+        self.wave.pipeline = Wave::new(device, tracker, format, sample_count, depth_mode, &self.uniform_bind_group_layout,
+            Instance::desc()).pipeline;
+        let translucent = TranslucentQuad::new(device, &self.init.queue, tracker, format, sample_count, depth_mode);
+        self.translucent.straight_pipeline = translucent.straight_pipeline;
+        self.translucent.premultiplied_pipeline = translucent.premultiplied_pipeline;
+        // a multisampled depth texture needs a different binding, recreate_target makes the bind group
+        self.depth_view = DepthView::new(device, tracker, format, sample_count);
+
+        self.pipeline_targets.sample_count = sample_count;
+        self.recreate_target();
+        if let Some(second) = &mut self.second_window {
+            second.target = RenderTarget::new(&self.init.device, &self.init.tracker, second.surface.config.format,
+                second.surface.config.width, second.surface.config.height, sample_count);
+            second.blit_bind_groups = self.blit.bind_groups(&self.init.device, &second.target.color_view);
+        }
+        if self.reflection_probe.is_some() {
+            // drops the probe and builds it again with the new sphere pipeline
+            self.toggle_reflection_probe();
+            self.toggle_reflection_probe();
+        }
+        self.set_pipeline_key(PipelineKey { sample_count, ..self.pipeline_key });
+    }
+
+    // the active mode and what it costs over rendering without anti-aliasing
+    fn aa_text(&self) -> String {
+        let mut text = String::from(self.aa_mode().name());
+        let samples = self.pipeline_targets.sample_count;
+        if samples > 1 {
+            text += &format!(", {}x msaa ({}x the color and depth memory, shaded once per pixel)", samples, samples);
+        }
+        if self.ssaa_factor > 1 {
+            text += &format!(", {}x ssaa ({}x{}, {}x the pixels shaded)", self.ssaa_factor, self.target.width, self.target.height,
+                self.ssaa_factor * self.ssaa_factor);
+        }
+        if self.aa_mode() == AaMode::Fxaa {
+            text += &format!(" (one pass over {}x{}, no extra memory)", self.target.width, self.target.height);
+        }
+        text
+    }
+
+    // steps up or down through SSAA_FACTORS
+    fn step_ssaa_factor(&mut self, up: bool) {
+        let current = SSAA_FACTORS.iter().position(|&factor| factor == self.ssaa_factor).unwrap_or(0);
+        let next = if up { (current + 1).min(SSAA_FACTORS.len() - 1) } else { current.saturating_sub(1) };
+        if SSAA_FACTORS[next] != self.ssaa_factor {
+            self.ssaa_factor = SSAA_FACTORS[next];
+            self.recreate_target();
+            self.update_title();
+        }
+    }
+
+    fn set_render_scale(&mut self, render_scale: f32) {
+        let render_scale = render_scale.clamp(RENDER_SCALE_MIN, RENDER_SCALE_MAX);
+        if render_scale != self.render_scale {
+            self.render_scale = render_scale;
+            self.recreate_target();
+            self.update_title();
+        }
+    }
+
+    // rebuilds the instance grid, reallocating the instance buffer only when the grid outgrows it
+    fn set_instance_grid(&mut self, count: u32, spacing: f32) {
+        self.grid_count = count.clamp(GRID_COUNT_MIN, GRID_COUNT_MAX);
+        self.grid_spacing = spacing.clamp(GRID_SPACING_MIN, GRID_SPACING_MAX);
+        self.instance_positions = vertex_data::instance_grid(self.grid_count, self.grid_spacing);
+
+        if self.instance_positions.len() > self.instance_capacity {
+            self.instance_capacity = self.instance_positions.len();
+            self.instance_buffer = create_instance_buffer(&self.init.device, &self.init.tracker, self.instance_capacity);
+        }
+        if self.merged_mesh.is_some() {
+            self.upload_mesh();
+        }
+
+        self.update_title();
+    }
+
+    // cull the grid against the current frustum and upload the surviving instance matrices,
+    // returning the instance matrices that will be drawn
+    fn write_instances(&mut self) -> Vec<Matrix4<f32>> {
+        if !self.show_instances {
+            let identity: Instance = Matrix4::identity().into();
+            self.init.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&[identity]));
+            return vec![Matrix4::identity()];
+        }
+
+        // the merged mesh already has every object in place and can't be culled per object, it is drawn
+        // once through a single identity instance
+        if self.merged_mesh.is_some() {
+            let identity: Instance = Matrix4::identity().into();
+            self.init.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&[identity]));
+            if self.visible_instances != self.instance_positions.len() {
+                self.visible_instances = self.instance_positions.len();
+                self.update_title();
+            }
+            return self.instance_positions.iter().map(|position| Matrix4::from_translation(Vector3::from(*position))).collect();
+        }
+
+        let planes = transforms::frustum_planes(self.projection_matrix * self.view_matrix);
+        let visible: Vec<Matrix4<f32>> = self.instance_positions
+            .iter()
+            .map(|position| Matrix4::from_translation(Vector3::from(*position)))
+            .filter(|translation| {
+                let center = (self.model_matrix * translation).w.truncate();
+                transforms::frustum_contains_sphere(&planes, Point3::from_vec(center), self.scaled_mesh_radius())
+            })
+            .collect();
+
+        let instances: Vec<Instance> = visible.iter().map(|&matrix| Instance::from(matrix)).collect();
+        self.init.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+
+        if visible.len() != self.visible_instances {
+            self.visible_instances = visible.len();
+            self.update_title();
+        }
+
+        visible
+    }
+
+    // world space bounding box of every drawn object, for the line pipeline
+    fn write_bounding_boxes(&mut self, instances: &[Matrix4<f32>]) {
+        if !self.show_bounding_boxes {
+            self.bounding_box_lines.clear();
+            return;
+        }
+
+        let (min, max) = self.mesh_bounds;
+        let lines: Vec<_> = instances
+            .iter()
+            .flat_map(|instance| {
+                let (world_min, world_max) = transforms::transform_aabb(self.model_matrix * instance, min, max);
+                vertex_data::aabb_lines(world_min, world_max, BOUNDING_BOX_COLOR)
+            })
+            .collect();
+
+        self.bounding_box_lines.write(&self.init.device, &self.init.queue, &self.init.tracker, &lines);
+    }
+
+    // the hedgehog view of every drawn object: the morphed mesh's normals as world space segments
+    fn write_normal_lines(&mut self, instances: &[Matrix4<f32>]) {
+        if !self.show_normals {
+            self.normal_lines.clear();
+            return;
+        }
+
+        let posed: Vec<Vertex> = self.cpu_mesh.vertices.iter().map(|v| v.morphed(self.morph_weight)).collect();
+        let model_lines = mesh::normal_lines(&posed, NORMAL_LINE_LENGTH);
+        // the segments start on the surface and run along the normal as the shader transforms it, so they
+        // stay perpendicular and NORMAL_LINE_LENGTH long under non-uniform scale
+        let normal_matrix = transforms::normal_matrix(self.model_matrix);
+        let lines: Vec<_> = instances
+            .iter()
+            .flat_map(|instance| {
+                let world = self.model_matrix * instance;
+                model_lines.chunks_exact(2).flat_map(move |line| {
+                    let (start, end) = (Point3::from(line[0].position), Point3::from(line[1].position));
+                    let world_start = Point3::from_homogeneous(world * start.to_homogeneous());
+                    let world_end = world_start + (normal_matrix * (end - start)).normalize() * NORMAL_LINE_LENGTH;
+                    [LineVertex { position: world_start.into(), color: line[0].color },
+                        LineVertex { position: world_end.into(), color: line[1].color }]
+                })
+            })
+            .collect();
+
+        self.normal_lines.write(&self.init.device, &self.init.queue, &self.init.tracker, &lines);
+    }
+
+    // reads back the last frame's color and depth under the cursor. the target is at render scale, so
+    // the cursor is scaled into it first; what shows is the scene before the hud was drawn over it
+    fn read_pixel(&mut self) {
+        let x = (self.cursor_position.x * self.target.width as f64 / self.config().width as f64) as u32;
+        let y = (self.cursor_position.y * self.target.height as f64 / self.config().height as f64) as u32;
+
+        match self.target.read_pixel(&self.init.device, &self.init.queue, &self.init.tracker, x, y) {
+            Ok(pixel) => {
+                println!("{}", pixel_readback_text([x, y], &pixel, self.depth_mode));
+                self.pixel_readback = Some(([x, y], pixel));
+            }
+            Err(e) => {
+                log::warn!("could not read back the pixel under the cursor: {}", e);
+                self.pixel_readback = None;
+            }
+        }
+    }
+
+    // casts a ray through the cursor against every drawn copy of the mesh, as posed this frame
+    // (including the morph), and moves the marker to the nearest hit
+    fn pick(&mut self) {
+        let Some(ray) = transforms::screen_ray(self.projection_matrix * self.view_matrix, self.cursor_position.x as f32,
+            self.cursor_position.y as f32, self.config().width as f32, self.config().height as f32) else {
+            return;
+        };
+
+        let instances: Vec<Matrix4<f32>> = if self.show_instances {
+            self.instance_positions.iter().map(|position| Matrix4::from_translation(Vector3::from(*position))).collect()
+        } else {
+            vec![Matrix4::identity()]
+        };
+
+        let mut nearest: Option<(f32, Matrix4<f32>)> = None;
+        for instance in instances {
+            let world = self.model_matrix * instance;
+            if !transforms::ray_hits_sphere(&ray, Point3::from_vec(world.w.truncate()), self.scaled_mesh_radius()) {
+                continue;
+            }
+
+            let world_position = |index: u32| {
+                let v = self.cpu_mesh.vertices[index as usize].morphed(self.morph_weight);
+                Point3::from_homogeneous(world * cgmath::Vector4::from(v.position))
+            };
+
+            for triangle in self.cpu_mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(world_position);
+                if let Some(distance) = transforms::ray_triangle(&ray, a, b, c) {
+                    if nearest.is_none_or(|(nearest, _)| distance < nearest) {
+                        nearest = Some((distance, instance));
+                    }
+                }
+            }
+        }
+
+        self.picked_point = nearest.map(|(distance, _)| ray.at(distance));
+        self.selected_instance = nearest.map(|(_, instance)| instance);
+        self.pick_ray = Some((ray.origin, ray.at(nearest.map_or(PICK_RAY_LENGTH, |(distance, _)| distance))));
+        match self.picked_point {
+            Some(point) => {
+                let lines = vertex_data::marker_lines(point.into(), MARKER_SIZE);
+                self.marker_lines.write(&self.init.device, &self.init.queue, &self.init.tracker, &lines);
+            }
+            None => self.marker_lines.clear(),
+        }
+    }
+
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        self.minimized = new_size.width == 0 || new_size.height == 0;
+        if new_size.width > 0 && new_size.height > 0 {
+            self.init.instance.poll_all(true);
+            self.init.size = new_size;
+            if let Some(config) = &mut self.init.config {
+                config.width = new_size.width;
+                config.height = new_size.height;
+                if let Some(surface) = &self.init.surface {
+                    surface.configure(&self.init.device, config);
+                }
+            }
+            self.recreate_target();
+
+            self.projection_matrix = self.main_projection();
+            self.write_uniforms();
+            self.write_frustum_lines();
+        }
+    }
+
+    // steps through the present modes the surface supports: fifo waits for vblank, mailbox replaces the
+    // queued frame without tearing, immediate presents at once and may tear
+    fn cycle_present_mode(&mut self) {
+        let (Some(surface), Some(config)) = (&self.init.surface, &mut self.init.config) else {
+            return;
+        };
+
+        let modes = surface.get_capabilities(&self.init.adapter).present_modes;
+        let current = modes.iter().position(|&mode| mode == config.present_mode);
+        let next = current.map_or(0, |i| (i + 1) % modes.len());
+        let Some(&mode) = modes.get(next) else {
+            return;
+        };
+
+        config.present_mode = mode;
+        surface.configure(&self.init.device, config);
+        log::info!("present mode {:?} (supported: {:?})", mode, modes);
+        self.update_title();
+    }
+
+    fn write_uniforms(&self) {
+        let uniforms = self.scene_uniforms(self.projection_matrix * self.view_matrix, self.camera_position);
+        self.init.queue.write_buffer(self.uniforms.buffer(), 0, bytemuck::cast_slice(&[uniforms]));
+        self.write_camera_dependents();
+    }
+
+    // only the model matrix, for when the animation moves the model under a still camera
+    fn update_model_uniform(&self) {
+        let model_matrix: [[f32; 4]; 4] = self.model_matrix.into();
+        self.init.queue.write_buffer(self.uniforms.buffer(), MODEL_OFFSET, bytemuck::cast_slice(&[model_matrix]));
+    }
+
+    // only the view projection matrix and camera position, plus everything else drawn with the camera
+    fn update_view_projection_uniform(&self) {
+        let view_projection_matrix: [[f32; 4]; 4] = (self.projection_matrix * self.view_matrix).into();
+        self.init.queue.write_buffer(self.uniforms.buffer(), VIEW_PROJECTION_OFFSET, bytemuck::cast_slice(&[view_projection_matrix]));
+        let camera_position: [f32; 4] = self.camera_position.to_homogeneous().into();
+        self.init.queue.write_buffer(self.uniforms.buffer(), CAMERA_POSITION_OFFSET, bytemuck::cast_slice(&[camera_position]));
+        self.write_camera_dependents();
+    }
+
+    fn edge_width(&self) -> f32 {
+        if self.edge_highlight { EDGE_WIDTH } else { 0.0 }
+    }
+
+    // the scene uniforms for a camera at camera_position, everything but the view projection shared by
+    // all views of the scene
+    fn scene_uniforms(&self, view_projection: Matrix4<f32>, camera_position: Point3<f32>) -> Uniforms {
+        Uniforms {
+            view_projection_matrix: view_projection.into(),
+            model_matrix: self.model_matrix.into(),
+            morph_weight: self.morph_weight,
+            edge_width: self.edge_width(),
+            fog_density: self.fog_density,
+            fog_enabled: self.fog as u32,
+            camera_position: camera_position.to_homogeneous().into(),
+            fog_color: self.fog_color(),
+            time: self.animation_time.as_secs_f32(),
+            _padding: [0.0; 3],
+        }
+    }
+
+    fn fog_color(&self) -> [f32; 4] {
+        let [r, g, b] = FOG_COLORS[self.fog_color_index].1.unwrap_or_else(|| {
+            let clear = self.clear_color;
+            [clear.r as f32, clear.g as f32, clear.b as f32]
+        });
+        [r, g, b, 1.0]
+    }
+
+    fn toggle_fog(&mut self) {
+        self.fog = !self.fog;
+        self.write_uniforms();
+        self.update_title();
+    }
+
+    fn cycle_fog_color(&mut self) {
+        self.fog_color_index = (self.fog_color_index + 1) % FOG_COLORS.len();
+        self.write_uniforms();
+        self.update_title();
+    }
+
+    fn step_fog_density(&mut self, denser: bool) {
+        let density = if denser { self.fog_density * FOG_DENSITY_STEP } else { self.fog_density / FOG_DENSITY_STEP };
+        self.fog_density = density.clamp(FOG_DENSITY_RANGE.0, FOG_DENSITY_RANGE.1);
+        self.write_uniforms();
+        self.update_title();
+    }
+
+    fn update_morph_weight_uniform(&self) {
+        self.init.queue.write_buffer(self.uniforms.buffer(), MORPH_WEIGHT_OFFSET, bytemuck::cast_slice(&[self.morph_weight]));
+    }
+
+    // the line and translucent quad uniforms, which only depend on the camera
+    fn write_camera_dependents(&self) {
+        self.particles.write_camera(&self.init.queue, self.view_matrix, self.projection_matrix);
+        self.line_pipeline.write_view_projection(&self.init.queue, self.projection_matrix * self.view_matrix);
+        self.shader_grid.write(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position);
+
+        let quad_model = Matrix4::from_translation(QUAD_POSITION.into())
+            * Matrix4::from_angle_y(cgmath::Deg(45.0))
+            * Matrix4::from_scale(QUAD_SIZE);
+        self.translucent.write_mvp(&self.init.queue, self.projection_matrix * self.view_matrix * quad_model);
+        self.translucent_cubes.write(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position,
+            self.translucent_cubes_order.unwrap_or(DrawOrder::BackToFront));
+        // sorting the triangles is the expensive part, skipped while the mesh is hidden
+        if let Some(order) = self.translucent_mesh_order {
+            self.translucent_mesh.write(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position, order);
+        }
+    }
+
+    // off -> rectangular -> polar -> shader -> off
+    fn cycle_reference_grid(&mut self) {
+        self.reference_grid = match self.reference_grid {
+            ReferenceGrid::Off => ReferenceGrid::Rectangular,
+            ReferenceGrid::Rectangular => ReferenceGrid::Polar,
+            ReferenceGrid::Polar => ReferenceGrid::Shader,
+            ReferenceGrid::Shader => ReferenceGrid::Off,
+        };
+        self.update_title();
+    }
+
+    // how the shader grid fades and whether its lines are antialiased, see ShaderGrid
+    fn cycle_grid_fade(&mut self) {
+        self.shader_grid.cycle_fade_distance();
+        self.shader_grid.write(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position);
+        self.update_title();
+    }
+
+    fn toggle_grid_antialias(&mut self) {
+        self.shader_grid.antialias = !self.shader_grid.antialias;
+        self.shader_grid.write(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position);
+        self.update_title();
+    }
+
+    // off -> straight -> premultiplied -> off
+    fn cycle_translucent_mode(&mut self) {
+        self.translucent_mode = match self.translucent_mode {
+            None => Some(AlphaMode::Straight),
+            Some(AlphaMode::Straight) => Some(AlphaMode::Premultiplied),
+            Some(AlphaMode::Premultiplied) => None,
+        };
+        self.update_title();
+    }
+
+    // off -> sorted back to front -> creation order -> off
+    fn cycle_translucent_cubes(&mut self) {
+        self.translucent_cubes_order = match self.translucent_cubes_order {
+            None => Some(DrawOrder::BackToFront),
+            Some(DrawOrder::BackToFront) => Some(DrawOrder::Creation),
+            Some(DrawOrder::Creation) => None,
+        };
+        self.write_camera_dependents();
+        self.update_title();
+    }
+
+    fn cycle_translucent_mesh(&mut self) {
+        self.translucent_mesh_order = match self.translucent_mesh_order {
+            None => Some(DrawOrder::BackToFront),
+            Some(DrawOrder::BackToFront) => Some(DrawOrder::Creation),
+            Some(DrawOrder::Creation) => None,
+        };
+        self.write_camera_dependents();
+        self.update_title();
+    }
+
+    fn print_transforms(&self) {
+        let mvp_matrix = self.projection_matrix * self.view_matrix * self.model_matrix;
+
+        println!("camera position: ({:.3}, {:.3}, {:.3})", self.camera_position.x, self.camera_position.y, self.camera_position.z);
+        println!("camera target:   ({:.3}, {:.3}, {:.3})", self.look_direction.x, self.look_direction.y, self.look_direction.z);
+        println!("camera up:       ({:.3}, {:.3}, {:.3})", self.up_direction.x, self.up_direction.y, self.up_direction.z);
+        if self.perspective {
+            println!("fovy: {:.2} degrees", cgmath::Deg::from(self.fovy).0);
+        } else {
+            println!("orthographic projection");
+        }
+        println!("depth mode: {:?}", self.depth_mode);
+        println!("clip space: {}", transforms::clip_space_note());
+        println!("model matrix:\n{}", transforms::MatrixDisplay(&self.model_matrix));
+        println!("view matrix:\n{}", transforms::MatrixDisplay(&self.view_matrix));
+        println!("projection matrix:\n{}", transforms::MatrixDisplay(&self.projection_matrix));
+        println!("mvp matrix (projection * view * model):\n{}", transforms::MatrixDisplay(&mvp_matrix));
+    }
+
+    // time drives the disco lights' orbits, the other modes are static
+    fn generated_lights(&self, time: f32) -> Vec<Light> {
+        match self.lighting {
+            Lighting::Off => Vec::new(),
+            Lighting::White => vec![lights::white_light()],
+            Lighting::Disco => lights::disco_lights(DISCO_LIGHT_COUNT, time),
+        }
+    }
+
+    // the generated lights with the keyboard edits applied on top, written to the lights uniform
+    fn write_lights(&self, time: f32) {
+        let mut active = self.generated_lights(time);
+        for (light, edit) in active.iter_mut().zip(&self.light_edits) {
+            edit.apply(light);
+        }
+        let lights = Lights::new(&active);
+        self.init.queue.write_buffer(&self.lights_buffer, 0, bytemuck::cast_slice(&[lights]));
+    }
+
+    fn set_lighting(&mut self, lighting: Lighting) {
+        self.lighting = lighting;
+        self.light_edits = Default::default();
+        self.active_light = 0;
+        self.write_lights(0.0);
+    }
+
+    fn toggle_lighting(&mut self, lighting: Lighting) {
+        self.set_lighting(if self.lighting == lighting { Lighting::Off } else { lighting });
+        self.update_title();
+    }
+
+    // the time the disco lights were last written for, so an edit doesn't move them
+    fn lights_time(&self) -> f32 {
+        ANIMATION_SPEED * self.animation_time.as_secs_f32()
+    }
+
+    // applies edit to the active light, given the light as the lighting mode generates it
+    fn edit_active_light(&mut self, edit: impl FnOnce(&mut LightEdit, &Light)) {
+        let time = self.lights_time();
+        let Some(light) = self.generated_lights(time).get(self.active_light).copied() else {
+            log::info!("there are no lights to edit, turn them on with K or O");
+            return;
+        };
+
+        edit(&mut self.light_edits[self.active_light], &light);
+        self.write_lights(time);
+    }
+
+    fn cycle_active_light(&mut self) {
+        let count = self.generated_lights(0.0).len();
+        if count == 0 {
+            log::info!("there are no lights to select, turn them on with K or O");
+            return;
+        }
+        self.active_light = (self.active_light + 1) % count;
+    }
+
+    // the active light with its edits, for the hud
+    fn active_light_text(&self) -> Option<String> {
+        let mut light = *self.generated_lights(self.lights_time()).get(self.active_light)?;
+        let edit = &self.light_edits[self.active_light];
+        edit.apply(&mut light);
+
+        let color = match edit.color {
+            Some(preset) => lights::COLOR_PRESETS[preset].0.to_string(),
+            None => format!("{:.2} {:.2} {:.2}", light.color[0], light.color[1], light.color[2]),
+        };
+        Some(format!("light {} of {}: intensity {:.2}, range {:.1}, color {}", self.active_light + 1,
+            self.generated_lights(0.0).len(), light.intensity, light.range, color))
+    }
+
+    // both sets have the same levels, they come from the same adapter
+    fn active_texture_bind_groups(&self) -> &AnisotropyBindGroups {
+        self.scene.texture(self).unwrap_or(&self.texture_bind_groups)
+    }
+
+    // F1, F2 and F4 belong to the active scene, see Scene::key
+    fn scene_key(&mut self, key: KeyCode) {
+        let mut scene = std::mem::replace(&mut self.scene, Box::new(CubeScene));
+        let used = scene.key(self, key, self.modifiers);
+        self.scene = scene;
+
+        if used {
+            self.update_title();
+        } else {
+            log::info!("{:?} does nothing in the {} scene", key, self.scene.name());
+        }
+    }
+
+    // tears the active scene down and sets scene up in its place
+    fn set_scene(&mut self, mut scene: Box<dyn Scene>) {
+        let mut previous = std::mem::replace(&mut self.scene, Box::new(CubeScene));
+        previous.teardown(self);
+        scene.setup(self);
+        self.scene = scene;
+    }
+
+    fn cycle_scene(&mut self) {
+        self.set_scene(self.scene.next());
+        (self.mesh_bounds, self.mesh_radius) = mesh_extent(&self.cpu_mesh);
+        self.set_pipeline_key(PipelineKey { shader: 0, ..self.pipeline_key });
+
+        self.upload_mesh();
+        self.update_title();
+        log::info!("{} scene", self.scene.name());
+    }
+
+    // shows a scene's own mesh, putting the startup mesh aside until restore_startup_mesh
+    fn show_scene_mesh(&mut self, mesh: Mesh) {
+        self.saved_mesh = Some((std::mem::replace(&mut self.cpu_mesh, mesh), self.subdivisions));
+        self.subdivisions = 0;
+    }
+
+    fn restore_startup_mesh(&mut self) {
+        if let Some((mesh, subdivisions)) = self.saved_mesh.take() {
+            self.cpu_mesh = mesh;
+            self.subdivisions = subdivisions;
+        }
+    }
+
+    fn subdivide(&mut self) {
+        if self.subdivisions == MAX_SUBDIVISIONS {
+            log::info!("already subdivided {} times", MAX_SUBDIVISIONS);
+            return;
+        }
+
+        let (vertices, indices) = mesh::subdivide(&self.cpu_mesh.vertices, &self.cpu_mesh.indices);
+        self.cpu_mesh = Mesh { vertices, indices };
+        self.upload_mesh();
+        self.subdivisions += 1;
+        self.update_title();
+    }
+
+    fn scene_clear_color(&self) -> wgpu::Color {
+        if self.file_hovered { FILE_HOVER_CLEAR_COLOR } else { self.clear_color }
+    }
+
+    // replaces the model with a file dropped on the window and frames the camera on it. the cube scene
+    // takes the new model, a demo scene's own mesh is put away. a file that fails to load leaves the
+    // current model as it was
+    fn load_model_file(&mut self, path: &std::path::Path) {
+        let model = match model::load(path, self.flat_normals) {
+            Ok(model) => model,
+            Err(e) => {
+                log::error!("{:#}, keeping the current model", e);
+                self.update_title();
+                return;
+            }
+        };
+
+        self.set_scene(Box::new(CubeScene));
+
+        let texture = match &model.base_color_texture {
+            Some(image) => Texture::from_image(&self.init.device, &self.init.queue, &self.init.tracker, image, "Base Color Texture"),
+            None => Texture::white(&self.init.device, &self.init.queue, &self.init.tracker),
+        };
+        self.texture_bind_groups = AnisotropyBindGroups::new(&self.init.device, &self.init.adapter, &texture, &self.texture_bind_group_layout);
+        self.textured = model.base_color_texture.is_some();
+        self.anisotropy_index = 0;
+        // a dropped vertex file is what f5 reloads from then on
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+            self.vertex_file = Some(path.to_path_buf());
+        }
+
+        self.cpu_mesh = model.mesh;
+        (self.mesh_bounds, self.mesh_radius) = mesh_extent(&self.cpu_mesh);
+        self.subdivisions = 0;
+        self.hidden_faces.clear();
+        self.selected_instance = None;
+        self.upload_mesh();
+        self.frame_model();
+        self.update_title();
+        log::info!("loaded {}: {} vertices, {} triangles", path.display(), self.cpu_mesh.vertices.len(), self.cpu_mesh.indices.len() / 3);
+    }
+
+    // rereads the vertex file and rebuilds the mesh from scratch, undoing subdivision and hidden faces
+    fn reload_vertex_file(&mut self) {
+        if self.textured {
+            log::info!("the vertex file only replaces the cube, not a --model");
+            return;
+        }
+        let Some(path) = self.vertex_file.clone() else {
+            log::info!("no vertex file to reload, give one with --vertex-file");
+            return;
+        };
+
+        self.cpu_mesh = load_vertex_file_or_cube(Some(&path), self.srgb_vertex_colors, self.flat_normals);
+        (self.mesh_bounds, self.mesh_radius) = mesh_extent(&self.cpu_mesh);
+        self.subdivisions = 0;
+        self.hidden_faces.clear();
+        self.upload_mesh();
+        self.update_title();
+        log::info!("reloaded {}: {} vertices, {} triangles", path.display(), self.cpu_mesh.vertices.len(), self.cpu_mesh.indices.len() / 3);
+    }
+
+    // shows or hides one face of the built in cube; with back faces culled the inside of the open box
+    // shows only its far walls
+    fn toggle_cube_face(&mut self, face: usize) {
+        if self.textured || self.cpu_mesh.vertices.len() != 36 || self.subdivisions > 0 {
+            log::info!("faces can only be removed from the unsubdivided built in cube");
+            return;
+        }
+
+        match self.hidden_faces.iter().position(|&hidden| hidden == face) {
+            Some(i) => {
+                self.hidden_faces.remove(i);
+            }
+            None => self.hidden_faces.push(face),
+        }
+        log::info!("{} face {}", vertex_data::CUBE_FACES[face], if self.hidden_faces.contains(&face) { "hidden" } else { "shown" });
+
+        self.cpu_mesh.indices = vertex_data::cube_indices_excluding(&self.hidden_faces);
+        self.upload_mesh();
+    }
+
+    // none, then back, then front faces culled
+    fn cycle_culling(&mut self) {
+        let cull_mode = match self.pipeline_key.cull_mode {
+            None => Some(wgpu::Face::Back),
+            Some(wgpu::Face::Back) => Some(wgpu::Face::Front),
+            Some(wgpu::Face::Front) => None,
+        };
+        self.rebuild_pipeline(self.pipeline_key.topology, self.pipeline_key.polygon_mode, self.pipeline_key.front_face, cull_mode);
+        self.update_title();
+    }
+
+    fn toggle_gradient_colors(&mut self) {
+        self.gradient_colors = !self.gradient_colors;
+        self.upload_mesh();
+        self.update_title();
+    }
+
+    // unlike the wireframe overlay this needs no POLYGON_MODE_LINE and no second draw, the mesh is
+    // uploaded unwelded instead so every triangle has its own barycentrics
+    fn toggle_edge_highlight(&mut self) {
+        self.edge_highlight = !self.edge_highlight;
+        self.upload_mesh();
+        self.write_uniforms();
+        self.update_title();
+    }
+
+    // cpu_mesh keeps its own colors and indexing, the gradient and the unwelding for the edge highlight
+    // are applied to the uploaded copy only. the indexed upload shares every identical vertex, the
+    // expanded one repeats them per index
+    fn upload_mesh(&mut self) {
+        let gradient = self.gradient_colors.then(|| self.gradient_mesh());
+        let mesh = gradient.as_ref().unwrap_or(&self.cpu_mesh);
+        let unwelded = self.edge_highlight.then(|| mesh::unweld(mesh));
+        let indexed = mesh::deduplicate(unwelded.as_ref().unwrap_or(mesh));
+        let expanded = mesh::expand(&indexed);
+        let gpu_mesh = GpuMesh::new(&self.init.device, &self.init.tracker, &indexed);
+        self.expanded_mesh = GpuMesh::new(&self.init.device, &self.init.tracker, &expanded);
+
+        self.merged_mesh = (self.show_instances && self.grid_batching == GridBatching::Merged).then(|| {
+            let uploaded = if self.indexed_drawing { &indexed } else { &expanded };
+            let objects: Vec<_> = self.instance_positions
+                .iter()
+                .map(|position| (Matrix4::from_translation(Vector3::from(*position)), uploaded))
+                .collect();
+            GpuMesh::new(&self.init.device, &self.init.tracker, &mesh::merge(&objects))
+        });
+
+        let sizes = self.sized_points.then(|| points::sizes_by_distance(&mesh.vertices, self.mesh_radius, POINT_SIZE_MIN, POINT_SIZE_MAX));
+        self.point_cloud.set_points(&self.init.device, &self.init.tracker, &mesh.vertices, sizes.as_deref());
+        self.mesh = gpu_mesh;
+    }
+
+    fn toggle_indexed_drawing(&mut self) {
+        self.indexed_drawing = !self.indexed_drawing;
+        if self.merged_mesh.is_some() {
+            self.upload_mesh();
+        }
+        self.update_title();
+    }
+
+    fn gradient_mesh(&self) -> Mesh {
+        let positions: Vec<[f32; 3]> = self.cpu_mesh.vertices
+            .iter()
+            .map(|v| [v.position[0], v.position[1], v.position[2]])
+            .collect();
+        let colors = vertex_data::gradient_colors(&positions, GRADIENT_AXIS);
+        let vertices = self.cpu_mesh.vertices
+            .iter()
+            .zip(colors)
+            .map(|(v, [r, g, b])| Vertex { color: [r, g, b, v.color[3]], ..*v })
+            .collect();
+
+        Mesh { vertices, indices: self.cpu_mesh.indices.clone() }
+    }
+
+    fn set_morph_weight(&mut self, morph_weight: f32) {
+        self.morph_weight = morph_weight.clamp(0.0, 1.0);
+        self.update_morph_weight_uniform();
+        self.update_title();
+    }
+
+    fn input(&mut self, event: &WindowEvent) -> bool {
+        let key = match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+                return false;
+            }
+            WindowEvent::Focused(false) => {
+                self.set_fps_camera(false);
+                return false;
+            }
+            WindowEvent::HoveredFile(_) => {
+                self.file_hovered = true;
+                return true;
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.file_hovered = false;
+                return true;
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.file_hovered = false;
+                self.loading_file = Some(path.clone());
+                self.update_title();
+                return true;
+            }
+            // a Resized with the new physical size follows, which recreates the targets
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = *scale_factor;
+                return false;
+            }
+            WindowEvent::CursorMoved { position, .. } if self.orbit_dragging => {
+                let yaw = -(position.x - self.cursor_position.x) as f32 * ORBIT_SENSITIVITY;
+                let pitch = (position.y - self.cursor_position.y) as f32 * ORBIT_SENSITIVITY;
+                self.cursor_position = *position;
+                self.orbit_camera(yaw, pitch);
+                let now = std::time::Instant::now();
+                self.orbit_steps.push_back((now, yaw, pitch));
+                while self.orbit_steps.front().is_some_and(|(time, ..)| (now - *time).as_secs_f32() > ORBIT_VELOCITY_WINDOW) {
+                    self.orbit_steps.pop_front();
+                }
+                return true;
+            }
+            // remembered for picking, the model dragging still happens in the event loop
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = *position;
+                // the procedural shader follows the cursor even while nothing animates
+                if self.show_procedural {
+                    self.window.request_redraw();
+                }
+                return false;
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } if !self.fps_camera => {
+                self.pick();
+                return true;
+            }
+            WindowEvent::MouseInput { state, button: MouseButton::Middle, .. } if !self.fps_camera => {
+                self.set_orbit_dragging(*state == ElementState::Pressed);
+                return true;
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Right, .. } if !self.fps_camera => {
+                self.read_pixel();
+                return true;
+            }
+            WindowEvent::KeyboardInput {
+                event: KeyEvent { physical_key: PhysicalKey::Code(key), state: ElementState::Pressed, repeat: false, .. },
+                ..
+            } => key,
+            _ => return false,
+        };
+
+        match key {
+            KeyCode::Tab => self.cycle_scene(),
+            KeyCode::Backslash => self.toggle_split_screen(),
+            KeyCode::Quote => self.toggle_outline(),
+            KeyCode::Minus => self.toggle_reflection_probe(),
+            KeyCode::PageUp | KeyCode::PageDown => {
+                let factor = if *key == KeyCode::PageUp { MODEL_SCALE_STEP } else { 1.0 / MODEL_SCALE_STEP };
+                // shift, control and alt scale x, y and z alone
+                let axis = if self.modifiers.shift_key() {
+                    Some(0)
+                } else if self.modifiers.control_key() {
+                    Some(1)
+                } else if self.modifiers.alt_key() {
+                    Some(2)
+                } else {
+                    None
+                };
+                self.scale_model(factor, axis);
+            }
+            KeyCode::End => self.set_model_scale(MODEL_SCALE_DEFAULT),
+            KeyCode::Equal => self.cycle_probe_interval(),
+            // control edits the active light
+            KeyCode::KeyL if self.modifiers.control_key() => self.cycle_active_light(),
+            KeyCode::KeyK if self.modifiers.control_key() => self.edit_active_light(|edit, light| edit.step_intensity(light, true)),
+            KeyCode::KeyJ if self.modifiers.control_key() => self.edit_active_light(|edit, light| edit.step_intensity(light, false)),
+            KeyCode::KeyC if self.modifiers.shift_key() => self.toggle_hue_cycle(),
+            KeyCode::KeyC if self.modifiers.control_key() => self.edit_active_light(LightEdit::cycle_color),
+            KeyCode::Period if self.modifiers.control_key() => self.edit_active_light(|edit, light| edit.step_range(light, true)),
+            KeyCode::Comma if self.modifiers.control_key() => self.edit_active_light(|edit, light| edit.step_range(light, false)),
+            KeyCode::KeyI if self.modifiers.shift_key() => self.cycle_grid_batching(),
+            KeyCode::KeyI => {
+                self.show_instances = !self.show_instances;
+                self.visible_instances = 0;
+                self.upload_mesh();
+                self.update_title();
+            }
+            KeyCode::KeyL if self.modifiers.shift_key() => self.toggle_wireframe_overlay(),
+            KeyCode::KeyL => self.toggle_wireframe(),
+            KeyCode::KeyU => self.toggle_overlay_depth_bias(),
+            KeyCode::KeyP if self.modifiers.shift_key() => self.toggle_point_sizes(),
+            KeyCode::KeyP => self.toggle_points(),
+            KeyCode::KeyN => self.toggle_blit_filter(),
+            KeyCode::Period if self.modifiers.shift_key() => self.set_instance_grid(self.grid_count, self.grid_spacing + GRID_SPACING_STEP),
+            KeyCode::Comma if self.modifiers.shift_key() => self.set_instance_grid(self.grid_count, self.grid_spacing - GRID_SPACING_STEP),
+            KeyCode::Period => self.set_instance_grid(self.grid_count + 1, self.grid_spacing),
+            KeyCode::Comma => self.set_instance_grid(self.grid_count.saturating_sub(1), self.grid_spacing),
+            KeyCode::BracketLeft if self.modifiers.shift_key() => self.step_ssaa_factor(false),
+            KeyCode::BracketRight if self.modifiers.shift_key() => self.step_ssaa_factor(true),
+            KeyCode::BracketLeft => self.set_render_scale(self.render_scale - RENDER_SCALE_STEP),
+            KeyCode::BracketRight => self.set_render_scale(self.render_scale + RENDER_SCALE_STEP),
+            // M morphs the cube towards the pyramid, shift + M back towards the cube
+            KeyCode::KeyM if self.modifiers.shift_key() => self.set_morph_weight(self.morph_weight - MORPH_STEP),
+            KeyCode::KeyM => self.set_morph_weight(self.morph_weight + MORPH_STEP),
+            KeyCode::KeyD => self.print_transforms(),
+            KeyCode::KeyK => self.toggle_lighting(Lighting::White),
+            KeyCode::KeyO => self.toggle_lighting(Lighting::Disco),
+            KeyCode::KeyA => self.cycle_anisotropy(),
+            KeyCode::KeyS => self.subdivide(),
+            KeyCode::KeyQ if self.modifiers.shift_key() => self.cycle_translucent_cubes(),
+            KeyCode::KeyQ if self.modifiers.control_key() => self.cycle_translucent_mesh(),
+            KeyCode::KeyQ => self.cycle_translucent_mode(),
+            KeyCode::KeyV if self.modifiers.shift_key() => self.cycle_present_mode(),
+            KeyCode::KeyV => self.cycle_shader(),
+            KeyCode::Digit7 => self.cycle_blend_component(false),
+            KeyCode::Digit8 => self.cycle_blend_component(true),
+            KeyCode::KeyH if self.modifiers.shift_key() => self.show_crosshair = !self.show_crosshair,
+            KeyCode::KeyH => self.show_hud = !self.show_hud,
+            KeyCode::KeyB => self.show_bounding_boxes = !self.show_bounding_boxes,
+            KeyCode::Numpad1 => self.snap_to_axis_view(AxisView::Front),
+            KeyCode::Numpad3 => self.snap_to_axis_view(AxisView::Side),
+            KeyCode::Numpad7 => self.snap_to_axis_view(AxisView::Top),
+            KeyCode::Numpad5 => self.toggle_perspective(),
+            KeyCode::KeyR if self.modifiers.shift_key() => self.cycle_grid_fade(),
+            KeyCode::KeyR if self.modifiers.control_key() => self.toggle_grid_antialias(),
+            KeyCode::KeyR => self.cycle_reference_grid(),
+            KeyCode::KeyE => self.show_camera_path = !self.show_camera_path,
+            KeyCode::KeyX => self.show_particles = !self.show_particles,
+            KeyCode::Digit1 => self.toggle_cube_face(0),
+            KeyCode::Digit2 => self.toggle_cube_face(1),
+            KeyCode::Digit3 => self.toggle_cube_face(2),
+            KeyCode::Digit4 => self.toggle_cube_face(3),
+            KeyCode::Digit5 => self.toggle_cube_face(4),
+            KeyCode::Digit6 => self.toggle_cube_face(5),
+            KeyCode::Digit0 => self.cycle_culling(),
+            KeyCode::Digit9 => self.toggle_edge_highlight(),
+            KeyCode::Semicolon => self.toggle_linear_interpolation(),
+            KeyCode::KeyT => self.show_procedural = !self.show_procedural,
+            KeyCode::KeyY => self.cycle_depth_view(),
+            KeyCode::F1 | KeyCode::F2 | KeyCode::F4 => self.scene_key(*key),
+            KeyCode::F3 => self.cycle_aa_mode(),
+            KeyCode::F5 => self.reload_vertex_file(),
+            KeyCode::F6 => self.toggle_fixed_timestep(),
+            KeyCode::F7 => self.toggle_inspection_camera(),
+            KeyCode::F8 => self.toggle_inspection_projection(),
+            KeyCode::F9 if self.modifiers.shift_key() => self.cycle_fog_color(),
+            KeyCode::F9 => self.toggle_fog(),
+            KeyCode::F10 if self.modifiers.shift_key() => self.step_fog_density(false),
+            KeyCode::F10 => self.step_fog_density(true),
+            KeyCode::F11 => self.toggle_indexed_drawing(),
+            KeyCode::F12 => self.save_screenshot(),
+            KeyCode::KeyJ => self.show_normals = !self.show_normals,
+            KeyCode::KeyF if self.modifiers.shift_key() => self.frame_model(),
+            KeyCode::KeyF => self.set_fps_camera(!self.fps_camera),
+            KeyCode::KeyW => self.second_window_requested = true,
+            KeyCode::KeyC => self.toggle_clip_correction(),
+            KeyCode::KeyG => self.toggle_gradient_colors(),
+            KeyCode::KeyZ if self.modifiers.shift_key() => self.toggle_z_up(),
+            KeyCode::KeyZ if self.modifiers.control_key() => self.toggle_left_handed(),
+            KeyCode::KeyZ => self.start_dolly_zoom(),
+            KeyCode::Space => self.toggle_animation(),
+            KeyCode::Escape if self.fps_camera => self.set_fps_camera(false),
+            KeyCode::ArrowUp if self.fps_camera => self.move_camera(1.0, 0.0),
+            KeyCode::ArrowDown if self.fps_camera => self.move_camera(-1.0, 0.0),
+            KeyCode::ArrowRight if self.fps_camera => self.move_camera(0.0, 1.0),
+            KeyCode::ArrowLeft if self.fps_camera => self.move_camera(0.0, -1.0),
+            KeyCode::ArrowUp if self.inspection_camera.is_some() => self.orbit_inspection_camera(0.0, INSPECTION_ORBIT_STEP),
+            KeyCode::ArrowDown if self.inspection_camera.is_some() => self.orbit_inspection_camera(0.0, -INSPECTION_ORBIT_STEP),
+            KeyCode::ArrowRight if self.inspection_camera.is_some() => self.orbit_inspection_camera(INSPECTION_ORBIT_STEP, 0.0),
+            KeyCode::ArrowLeft if self.inspection_camera.is_some() => self.orbit_inspection_camera(-INSPECTION_ORBIT_STEP, 0.0),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn set_fps_camera(&mut self, enabled: bool) {
+        if enabled == self.fps_camera {
+            return;
+        }
+        self.fps_camera = enabled;
+
+        if enabled {
+            // not every platform can lock the cursor in place, confining it to the window still
+            // works since the look uses raw motion deltas rather than the cursor position
+            let grab = self.window.set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| self.window.set_cursor_grab(CursorGrabMode::Confined));
+            if let Err(e) = grab {
+                log::warn!("could not grab the cursor: {}", e);
+            }
+            self.window.set_cursor_visible(false);
+
+            let forward = (self.look_direction - self.camera_position).normalize();
+            self.yaw = forward.z.atan2(forward.x);
+            self.pitch = forward.y.asin();
+        } else {
+            if let Err(e) = self.window.set_cursor_grab(CursorGrabMode::None) {
+                log::warn!("could not release the cursor: {}", e);
+            }
+            self.window.set_cursor_visible(true);
+        }
+
+        self.update_title();
+    }
+
+    fn camera_forward(&self) -> Vector3<f32> {
+        Vector3::new(self.pitch.cos() * self.yaw.cos(), self.pitch.sin(), self.pitch.cos() * self.yaw.sin())
+    }
+
+    fn update_camera(&mut self) {
+        self.look_direction = self.camera_position + self.camera_forward();
+        self.view_matrix = transforms::create_view(self.camera_position, self.look_direction, self.up_direction);
+        self.update_view_projection_uniform();
+    }
+
+    // raw mouse deltas, only used for looking around in fps camera mode
+    fn mouse_motion(&mut self, delta: (f64, f64)) -> bool {
+        if !self.fps_camera {
+            return false;
+        }
+
+        self.yaw += delta.0 as f32 * MOUSE_SENSITIVITY;
+        self.pitch = (self.pitch - delta.1 as f32 * MOUSE_SENSITIVITY).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        self.update_camera();
+        true
+    }
+
+    // moves the fps camera along its view direction (forward) and sideways (right)
+    fn move_camera(&mut self, forward: f32, right: f32) {
+        let forward_direction = self.camera_forward();
+        let right_direction = forward_direction.cross(self.up_direction).normalize();
+        self.camera_position += forward_direction * forward * MOVE_STEP + right_direction * right * MOVE_STEP;
+        self.update_camera();
+    }
+
+    // swings the camera around the look target, yaw radians about up then pitch radians up or down
+    fn orbit_camera(&mut self, yaw: f32, pitch: f32) {
+        self.camera_position = transforms::orbit(self.camera_position, self.look_direction, self.up_direction, yaw, pitch);
+        self.view_matrix = transforms::create_view(self.camera_position, self.look_direction, self.up_direction);
+        self.update_view_projection_uniform();
+    }
+
+    // grabbing stops any inertia, letting go turns the last few steps of the drag into a velocity. a
+    // drag that stopped before the release has no recent steps and so no inertia
+    fn set_orbit_dragging(&mut self, dragging: bool) {
+        self.orbit_dragging = dragging;
+        self.orbit_velocity = [0.0, 0.0];
+
+        if !dragging {
+            let now = std::time::Instant::now();
+            let recent = self.orbit_steps.iter().filter(|(time, ..)| (now - *time).as_secs_f32() <= ORBIT_VELOCITY_WINDOW);
+            let (yaw, pitch) = recent.fold((0.0, 0.0), |(yaw, pitch), (_, step_yaw, step_pitch)| (yaw + step_yaw, pitch + step_pitch));
+            self.orbit_velocity = [yaw / ORBIT_VELOCITY_WINDOW, pitch / ORBIT_VELOCITY_WINDOW];
+        }
+        self.orbit_steps.clear();
+    }
+
+    // keeps orbiting after a drag, with exponentially decaying velocity
+    fn update_orbit_inertia(&mut self, dt: f32) {
+        let [yaw, pitch] = self.orbit_velocity;
+        if self.orbit_dragging || (yaw == 0.0 && pitch == 0.0) {
+            return;
+        }
+
+        self.orbit_camera(yaw * dt, pitch * dt);
+
+        let decay = (-ORBIT_DAMPING * dt).exp();
+        self.orbit_velocity = [yaw * decay, pitch * decay];
+        if yaw.hypot(pitch) * decay < ORBIT_MIN_VELOCITY {
+            self.orbit_velocity = [0.0, 0.0];
+        }
+    }
+
+    fn suspend(&mut self) {
+        self.init.drop_surface();
+    }
+
+    fn resume(&mut self) {
+        if self.init.surface.is_some() {
+            return;
+        }
+
+        self.init.recreate_surface(self.window);
+        self.recreate_target();
+        self.projection_matrix = self.main_projection();
+        self.write_uniforms();
+        self.window.request_redraw();
+    }
+
+    fn toggle_second_window(&mut self, event_loop_window: &EventLoopWindowTarget<()>) {
+        if self.second_window.take().is_some() {
+            return;
+        }
+
+        let window = match WindowBuilder::new().with_title(SECOND_WINDOW_TITLE).build(event_loop_window) {
+            Ok(window) => Arc::new(window),
+            Err(e) => {
+                log::error!("could not open a second window: {}", e);
+                return;
+            }
+        };
+        let Some(surface) = self.init.add_surface(window) else {
+            return;
+        };
+
+        let target = RenderTarget::new(&self.init.device, &self.init.tracker, surface.config.format,
+            surface.config.width, surface.config.height, self.target.sample_count);
+        let blit_bind_groups = self.blit.bind_groups(&self.init.device, &target.color_view);
+
+        let uniform_buffer = self.init.tracker.create_buffer(&self.init.device, &wgpu::BufferDescriptor {
+            label: Some("Second Window Uniform Buffer"),
+            size: std::mem::size_of::<Uniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor{
+            layout: &self.uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }, wgpu::BindGroupEntry {
+                binding: 1,
+                resource: self.lights_buffer.as_entire_binding(),
+            }],
+            label: Some("Second Window Uniform Bind Group"),
+        });
+
+        self.second_window = Some(SecondWindow {
+            view_matrix: transforms::create_view(SECOND_CAMERA_POSITION.into(), self.look_direction, self.up_direction),
+            projection_matrix: scene_projection(surface.aspect(), self.default_fovy, self.ortho_fit(), self.depth_mode, self.apply_clip_correction),
+            surface,
+            target,
+            blit_bind_groups,
+            uniform_buffer,
+            uniform_bind_group,
+        });
+        self.check_render_config();
+    }
+
+    // the 2x2 cad style layout: front, side and top orthographic views next to the main camera's view.
+    // the views share the pipelines and only differ in their uniforms, every quadrant has its own buffer
+    // rather than one rewritten between draws, as writes all land before the pass runs
+    fn toggle_split_screen(&mut self) {
+        if self.split_views.take().is_some() {
+            self.update_title();
+            return;
+        }
+
+        let views = split_screen::AXIS_VIEWS
+            .iter()
+            .map(|&axis| {
+                let uniform_buffer = self.init.tracker.create_buffer(&self.init.device, &wgpu::BufferDescriptor {
+                    label: Some("Split View Uniform Buffer"),
+                    size: std::mem::size_of::<Uniforms>() as wgpu::BufferAddress,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let uniform_bind_group = self.init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &self.uniform_bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniform_buffer.as_entire_binding(),
+                    }, wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.lights_buffer.as_entire_binding(),
+                    }],
+                    label: Some("Split View Uniform Bind Group"),
+                });
+                SplitView { axis, uniform_buffer, uniform_bind_group }
+            })
+            .collect();
+
+        self.split_views = Some(views);
+        self.update_title();
+    }
+
+    // every frame, the orthographic views follow the model as it turns
+    fn write_split_view_uniforms(&self) {
+        let Some(views) = &self.split_views else {
+            return;
+        };
+
+        let (center, radius) = self.scene_sphere();
+        let radius = radius.max(SPLIT_VIEW_MIN_RADIUS);
+        let aspect = self.target.width as f32 / self.target.height as f32;
+        for view in views {
+            let projection = self.depth_mode.projection(view.axis.projection(radius, aspect));
+            let (direction, _) = view.axis.direction_and_up();
+            let uniforms = self.scene_uniforms(projection * view.axis.view_matrix(center, radius), center + direction * radius * 2.0);
+            self.init.queue.write_buffer(&view.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        }
+    }
+
+    fn is_second_window(&self, window_id: WindowId) -> bool {
+        self.second_window.as_ref().is_some_and(|second| second.surface.window.id() == window_id)
+    }
+
+    fn second_window_event(&mut self, event: &WindowEvent) {
+        let ortho_radius = self.ortho_fit();
+        let Some(second) = &mut self.second_window else {
+            return;
+        };
+
+        match event {
+            WindowEvent::CloseRequested => self.second_window = None,
+            WindowEvent::Resized(size) if size.width > 0 && size.height > 0 => {
+                second.surface.resize(&self.init.device, *size);
+                second.target = RenderTarget::new(&self.init.device, &self.init.tracker, second.surface.config.format,
+                    size.width, size.height, self.pipeline_targets.sample_count);
+                second.blit_bind_groups = self.blit.bind_groups(&self.init.device, &second.target.color_view);
+                second.projection_matrix = scene_projection(second.surface.aspect(), self.default_fovy, ortho_radius, self.depth_mode, self.apply_clip_correction);
+                self.check_render_config();
+            }
+            _ => {}
+        }
+    }
+
+    // whether anything changes from frame to frame on its own. while false the event loop waits for
+    // input instead of redrawing continuously
+    fn needs_continuous_redraw(&self) -> bool {
+        // the model rotation and the disco lights both run on the animation clock, orbit inertia and dolly
+        // zooms on their own. nothing is shown while minimized, the loop waits for the restoring resize
+        !self.minimized && (self.animating || self.dolly_zoom.is_some() || self.orbit_velocity != [0.0, 0.0])
+    }
+
+    fn ortho_fit(&self) -> Option<f32> {
+        (!self.perspective).then_some(self.ortho_radius)
+    }
+
+    // the main window's projection at its current size and field of view
+    fn main_projection(&self) -> Matrix4<f32> {
+        scene_projection(self.config().width as f32 / self.config().height as f32, self.fovy, self.ortho_fit(),
+            self.depth_mode, self.apply_clip_correction)
+    }
+
+    // swaps the up axis correction at the right of the model matrix, keeping whatever rotation is on
+    // its left so a paused model doesn't jump
+    fn toggle_z_up(&mut self) {
+        let current = model_basis(self.z_up, self.left_handed);
+        self.z_up = !self.z_up;
+        self.model_matrix = self.model_matrix * current.transpose() * model_basis(self.z_up, self.left_handed);
+        self.update_model_uniform();
+        self.update_title();
+    }
+
+    // mirrors the model's z like toggle_z_up swaps its up axis. the mirror reverses the winding, so the
+    // front face swaps with it to keep culling the same faces
+    fn toggle_left_handed(&mut self) {
+        let current = model_basis(self.z_up, self.left_handed);
+        self.left_handed = !self.left_handed;
+        self.model_matrix = self.model_matrix * current.transpose() * model_basis(self.z_up, self.left_handed);
+        self.update_model_uniform();
+
+        let front_face = match self.pipeline_key.front_face {
+            wgpu::FrontFace::Ccw => wgpu::FrontFace::Cw,
+            wgpu::FrontFace::Cw => wgpu::FrontFace::Ccw,
+        };
+        self.rebuild_pipeline(self.pipeline_key.topology, self.pipeline_key.polygon_mode, front_face, self.pipeline_key.cull_mode);
+        self.update_title();
+    }
+
+    // radius around the instance origin enclosing the mesh as scaled, for culling and picking
+    fn scaled_mesh_radius(&self) -> f32 {
+        self.mesh_radius * self.model_scale.into_iter().fold(0.0, f32::max)
+    }
+
+    // scales every axis by factor, or only axis. a step that would take an axis past the limits is
+    // clamped there, so non-uniform scales lose their ratio at the limits
+    fn scale_model(&mut self, factor: f32, axis: Option<usize>) {
+        let mut scale = self.model_scale;
+        for (i, s) in scale.iter_mut().enumerate() {
+            if axis.is_none_or(|axis| axis == i) {
+                *s = (*s * factor).clamp(MODEL_SCALE_MIN, MODEL_SCALE_MAX);
+            }
+        }
+        self.set_model_scale(scale);
+    }
+
+    // the scale sits between the rotation and the up axis correction at the right of the model matrix,
+    // where the old one is divided out and the new one put in, so a paused model keeps its rotation
+    fn set_model_scale(&mut self, scale: [f32; 3]) {
+        let [ox, oy, oz] = self.model_scale;
+        let up = model_basis(self.z_up, self.left_handed);
+        let rotation = self.model_matrix * up.transpose() * Matrix4::from_nonuniform_scale(1.0 / ox, 1.0 / oy, 1.0 / oz);
+        self.model_matrix = rotation * Matrix4::from_nonuniform_scale(scale[0], scale[1], scale[2]) * up;
+        self.model_scale = scale;
+        self.update_model_uniform();
+        self.update_title();
+    }
+
+    // the model's bounding sphere, or the whole grid's with instances shown
+    fn scene_sphere(&self) -> (Point3<f32>, f32) {
+        if self.show_instances {
+            let (min, max) = vertex_data::bounding_box(&self.instance_positions);
+            let (center, radius) = transforms::bounding_sphere(min, max);
+            // the model scale spreads the grid out along with the model
+            let scale = self.model_scale.into_iter().fold(0.0, f32::max);
+            (center, (radius + self.mesh_radius) * scale)
+        } else {
+            let (center, radius) = transforms::bounding_sphere(self.mesh_bounds.0, self.mesh_bounds.1);
+            // the model turns about the origin, take its center along
+            (Point3::from_homogeneous(self.model_matrix * center.to_homogeneous()), radius * self.model_scale.into_iter().fold(0.0, f32::max))
+        }
+    }
+
+    // moves the camera along its view direction until the model's bounding sphere (or the whole grid's,
+    // with instances shown) fills the view, looking at its center
+    fn frame_model(&mut self) {
+        let (center, radius) = self.scene_sphere();
+
+        let aspect = self.config().width as f32 / self.config().height as f32;
+        let distance = transforms::framing_distance(radius, self.fovy, aspect);
+        let direction = (self.look_direction - self.camera_position).normalize();
+
+        self.dolly_zoom = None;
+        self.orbit_velocity = [0.0, 0.0];
+        self.camera_position = center - direction * distance;
+        // the fps camera keeps its look direction one unit ahead, see update_camera
+        self.look_direction = if self.fps_camera { self.camera_position + direction } else { center };
+        self.view_matrix = transforms::create_view(self.camera_position, self.look_direction, self.up_direction);
+        self.update_view_projection_uniform();
+    }
+
+    // switches between perspective and orthographic, like blender's numpad 5. the orthographic view
+    // fits what the perspective one shows at the look target, so the model keeps its size on screen
+    fn toggle_perspective(&mut self) {
+        self.perspective = !self.perspective;
+        if !self.perspective {
+            self.ortho_radius = self.camera_position.distance(self.look_direction) * (self.fovy.0 / 2.0).tan();
+        }
+        self.update_projection();
+        self.update_title();
+    }
+
+    // snaps the camera to look at the scene along one axis in an orthographic projection fitting it, like
+    // blender's numpad 1, 3 and 7. the top view looks down a hair off the y axis, straight down the view
+    // matrix would have no sideways direction with y as up
+    fn snap_to_axis_view(&mut self, axis: AxisView) {
+        self.set_fps_camera(false);
+        let (center, radius) = self.scene_sphere();
+        let (direction, _) = axis.direction_and_up();
+        let direction = if axis == AxisView::Top { (direction + Vector3::unit_z() * 1e-3).normalize() } else { direction };
+
+        let aspect = self.config().width as f32 / self.config().height as f32;
+        self.dolly_zoom = None;
+        self.orbit_velocity = [0.0, 0.0];
+        self.camera_position = center + direction * transforms::framing_distance(radius, self.fovy, aspect);
+        self.look_direction = center;
+        self.view_matrix = transforms::create_view(self.camera_position, self.look_direction, self.up_direction);
+        self.perspective = false;
+        self.ortho_radius = radius;
+        self.update_projection();
+        self.update_title();
+        log::info!("{} view", axis.name());
+    }
+
+    // the projection of both windows after a change to how it's built
+    fn update_projection(&mut self) {
+        self.projection_matrix = self.main_projection();
+        let ortho_radius = self.ortho_fit();
+        if let Some(second) = &mut self.second_window {
+            second.projection_matrix = scene_projection(second.surface.aspect(), self.default_fovy, ortho_radius, self.depth_mode,
+                self.apply_clip_correction);
+        }
+        self.write_uniforms();
+    }
+
+    // starts a dolly zoom in toward DOLLY_ZOOM_FACTOR of the distance to look_direction, or back out to
+    // default_fovy if a previous one left the view zoomed
+    fn start_dolly_zoom(&mut self) {
+        if self.fps_camera {
+            log::info!("the dolly zoom orbits the look target, leave the fps camera first");
+            return;
+        }
+
+        let distance = self.camera_position.distance(self.look_direction);
+        let reference_distance = transforms::dolly_zoom_distance(self.default_fovy, self.fovy, distance);
+        let to = if self.fovy == self.default_fovy { distance * DOLLY_ZOOM_FACTOR } else { reference_distance };
+
+        self.dolly_zoom = Some(DollyZoom {
+            start: std::time::Instant::now(),
+            from: distance,
+            to,
+            reference_distance,
+        });
+    }
+
+    fn update_dolly_zoom(&mut self) {
+        let Some(dolly) = &self.dolly_zoom else {
+            return;
+        };
+
+        let t = (dolly.start.elapsed().as_secs_f32() / DOLLY_ZOOM_DURATION).min(1.0);
+        // smoothstep, so the move eases in and out
+        let eased = t * t * (3.0 - 2.0 * t);
+        let distance = dolly.from + (dolly.to - dolly.from) * eased;
+
+        // snap to exactly default_fovy at the end of a zoom out, which is what start_dolly_zoom checks for
+        self.fovy = if t == 1.0 && dolly.to == dolly.reference_distance {
+            self.default_fovy
+        } else {
+            transforms::dolly_zoom_fovy(self.default_fovy, dolly.reference_distance, distance)
+        };
+        if t == 1.0 {
+            self.dolly_zoom = None;
+        }
+
+        let direction = (self.camera_position - self.look_direction).normalize();
+        self.camera_position = self.look_direction + direction * distance;
+        self.view_matrix = transforms::create_view(self.camera_position, self.look_direction, self.up_direction);
+        self.projection_matrix = self.main_projection();
+        self.update_view_projection_uniform();
+    }
+
+    fn toggle_animation(&mut self) {
+        self.animating = !self.animating;
+        self.update_title();
+    }
+
+    fn update(&mut self, elapsed: std::time::Duration) {
+        self.update_start = Some(std::time::Instant::now());
+        let frame_time = elapsed.saturating_sub(self.last_elapsed).as_secs_f32();
+        self.last_elapsed = elapsed;
+        self.update_dolly_zoom();
+
+        // with the fixed timestep the state only moves in whole FIXED_DT steps, so it comes out the same
+        // at any frame rate, and the frame shows the last two states blended by how far real time has got
+        // into the next step. after a stall the backlog beyond MAX_SUBSTEPS is dropped: catching up on it
+        // would make the next frames slower still, each with more steps to catch up on
+        let blend = if self.fixed_timestep {
+            self.accumulator += frame_time;
+            let mut steps = 0;
+            while self.accumulator >= FIXED_DT && steps < MAX_SUBSTEPS {
+                self.step(FIXED_DT);
+                self.accumulator -= FIXED_DT;
+                steps += 1;
+            }
+            if self.accumulator >= FIXED_DT {
+                log::debug!("dropping {:.1} ms of simulation after a stall", self.accumulator * 1000.0);
+                self.accumulator %= FIXED_DT;
+            }
+            self.accumulator / FIXED_DT
+        } else {
+            self.step(frame_time);
+            1.0
+        };
+        let previous = self.previous_animation_time.as_secs_f32();
+        let shown_time = previous + (self.animation_time.as_secs_f32() - previous) * blend;
+
+        // update uniform buffer
+        let dt = ANIMATION_SPEED * shown_time;
+        self.model_matrix = transforms::create_transforms([0.0,0.0,0.0], [dt.sin(), dt.cos(), 0.0], self.model_scale) * model_basis(self.z_up, self.left_handed);
+        self.update_model_uniform();
+
+        if self.lighting == Lighting::Disco {
+            self.write_lights(dt);
+        }
+    }
+
+    // advances everything that simulates by dt seconds. the animation clock only runs while animating,
+    // so pausing and resuming continues where it stopped
+    fn step(&mut self, dt: f32) {
+        self.previous_animation_time = self.animation_time;
+        if self.animating {
+            self.animation_time += std::time::Duration::from_secs_f32(dt);
+            self.particle_steps += 1;
+            self.particle_dt = dt.min(MAX_PARTICLE_STEP);
+        }
+        self.update_orbit_inertia(dt);
+    }
+
+    fn toggle_fixed_timestep(&mut self) {
+        self.fixed_timestep = !self.fixed_timestep;
+        self.accumulator = 0.0;
+        self.previous_animation_time = self.animation_time;
+        self.update_title();
     }
 
-    data.to_vec()
-}
+    fn update_mouse(&mut self, position: PhysicalPosition<f64>) {
+        self.model_matrix = transforms::create_transforms([0.0, 0.0, 0.0], [-(position.y/100.00) as f32, (position.x/100.00) as f32, 0.0], self.model_scale)
+            * model_basis(self.z_up, self.left_handed);
 
-struct State<'window> {
-    init: transforms::InitWgpu<'window>,
-    pipeline: wgpu::RenderPipeline,
-    vertex_buffer: wgpu::Buffer,
-    uniform_buffer: wgpu::Buffer,
-    uniform_bind_group:wgpu::BindGroup,
-    model_matrix: Matrix4<f32>,
-    view_matrix: Matrix4<f32>,
-    projection_matrix: Matrix4<f32>,
-}
+        println!("Mouse position: ({}, {})", position.x, position.y);
 
-impl<'window> State<'window> {
-    async fn new(window: &'window Window) -> Self {        
-        let init =  transforms::InitWgpu::init_wgpu(window).await;
+        self.update_model_uniform();
+    }
 
-        let shader = init.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
-        });
+    fn hud_text(&self) -> String {
+        let mut text = format!(
+            "fps {:.1} ({:.2} ms)\ncamera {:.2} {:.2} {:.2}",
+            self.fps, 1000.0 / self.fps.max(f32::EPSILON),
+            self.camera_position.x, self.camera_position.y, self.camera_position.z,
+        );
 
-        // uniform data
-        let camera_position = (3.0, 1.5, 3.0).into();
-        let look_direction = (0.0,0.0,0.0).into();
-        let up_direction = cgmath::Vector3::unit_y();
-        
-        let model_matrix = transforms::create_transforms([0.0,0.0,0.0], [0.0,0.0,0.0], [1.0,1.0,1.0]);
-        let (view_matrix, projection_matrix, view_projection_matrix) = 
-            transforms::create_view_projection(camera_position, look_direction, up_direction, init.config.width as f32 / init.config.height as f32, IS_PERSPECTIVE);
-        let mvp_mat = view_projection_matrix * model_matrix;
-        
-        let mvp_ref:&[f32; 16] = mvp_mat.as_ref();
-        let uniform_buffer = init.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Uniform Buffer"),
-            contents: bytemuck::cast_slice(mvp_ref),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        // the larger of the two bounds the frame rate; both well under the frame time means it waits on vsync
+        let cpu = self.cpu_times.average().unwrap_or(0.0);
+        match self.gpu_timer.as_ref().and_then(|timer| timer.times.average()) {
+            Some(gpu) => text += &format!("\ncpu {:.2} ms, gpu {:.2} ms ({} bound)", cpu, gpu, if cpu >= gpu { "cpu" } else { "gpu" }),
+            None => text += &format!("\ncpu {:.2} ms, gpu time unavailable", cpu),
+        }
 
-        let uniform_bind_group_layout = init.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor{
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-            label: Some("Uniform Bind Group Layout"),
-        });
+        // the previous frame's, this one is still being recorded
+        text += &format!("\n{} draw calls, {} triangles", self.frame_stats.draw_calls, self.frame_stats.triangles);
+        text += &format!("\n{}", self.aa_text());
 
-        let uniform_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor{
-            layout: &uniform_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-            label: Some("Uniform Bind Group"),
-        });
+        if let Some(light) = self.active_light_text() {
+            text += &format!("\n{}", light);
+        }
 
-        let pipeline_layout = init.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&uniform_bind_group_layout],
-            push_constant_ranges: &[],
-        });
+        if let Some(path) = &self.loading_file {
+            text += &format!("\nloading {}...", path.display());
+        }
 
-        let pipeline = init.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: init.config.format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent::REPLACE,
-                        alpha: wgpu::BlendComponent::REPLACE,
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState{
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                //cull_mode: Some(wgpu::Face::Back),
-                ..Default::default()
-            },
-            //depth_stencil: None,
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth24Plus,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::LessEqual,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-        });
+        if self.show_instances {
+            text += &format!("\ninstances {} of {}", self.visible_instances, self.instance_positions.len());
+            text += &format!("\n{} batching", self.grid_batching.name());
+            for (batching, fps) in GridBatching::ALL.iter().zip(self.batching_fps) {
+                let measured = if fps == 0.0 { "-".to_string() } else { format!("{:.1}", fps) };
+                text += &format!("\n  {} fps {}", batching.name(), measured);
+            }
+        }
 
-        let vertex_buffer = init.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: cast_slice(&create_vertices()),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
+        // the expanded vertex buffer has no index buffer beside it
+        let (indexed, expanded) = (&self.mesh, &self.expanded_mesh);
+        let indexed_bytes = indexed.vertex_bytes() + indexed.index_bytes();
+        text += &format!(
+            "\n{} drawing\n  indexed {} vertices + {} indices, {:.1} kb\n  non indexed {} vertices, {:.1} kb",
+            if self.indexed_drawing { "indexed" } else { "non indexed" },
+            indexed.vertex_count, indexed.index_count, indexed_bytes as f32 / 1024.0,
+            expanded.vertex_count, expanded.vertex_bytes() as f32 / 1024.0,
+        );
 
-        Self {
-            init,
-            pipeline,
-            vertex_buffer,
-            uniform_buffer,
-            uniform_bind_group,
-            model_matrix,
-            view_matrix,
-            projection_matrix,
+        if self.pipeline_key.linear_interpolation {
+            text += "\nlinear interpolation: colors and uvs skip the perspective\ndivide and warp along triangle diagonals";
         }
-    }
 
-    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        if new_size.width > 0 && new_size.height > 0 {
-            self.init.instance.poll_all(true);
-            self.init.size = new_size;
-            self.init.config.width = new_size.width;
-            self.init.config.height = new_size.height;
-            self.init.surface.configure(&self.init.device, &self.init.config);
+        if !self.apply_clip_correction {
+            text += "\nclip correction off: depth is in opengl's [-1, 1],\nwgpu clips the half below 0";
+        }
 
-            self.projection_matrix = transforms::create_projection(new_size.width as f32 / new_size.height as f32, IS_PERSPECTIVE);
-            let mvp_mat = self.projection_matrix * self.view_matrix * self.model_matrix;        
-            let mvp_ref:&[f32; 16] = mvp_mat.as_ref();
-            self.init.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(mvp_ref));
+        // the probe redraws the mesh once per face, a cost spread over the frames between updates
+        if let Some((probe, _)) = &self.reflection_probe {
+            let instances = if self.show_instances { self.visible_instances as u32 } else { 1 };
+            let triangles = 6 * self.mesh.index_count / 3 * instances;
+            text += &format!("\nreflection probe {}x{} x 6 faces every {} frames:\n{} triangles per update, {} per frame",
+                reflection::PROBE_SIZE, reflection::PROBE_SIZE, probe.update_interval(), triangles, triangles / probe.update_interval());
         }
-    }
 
-    #[allow(unused_variables)]
-    fn input(&mut self, event: &WindowEvent) -> bool {
-        false
-    }
+        if let Some((position, pixel)) = &self.pixel_readback {
+            text += "\n";
+            text += &pixel_readback_text(*position, pixel, self.depth_mode);
+        }
 
-    fn update(&mut self, dt: std::time::Duration) {
-        // update uniform buffer
-        let dt = ANIMATION_SPEED * dt.as_secs_f32(); 
-        let model_matrix = transforms::create_transforms([0.0,0.0,0.0], [dt.sin(), dt.cos(), 0.0], [1.0, 1.0, 1.0]);
-        let mvp_matrix = self.projection_matrix * self.view_matrix * model_matrix;        
-        let mvp_ref:&[f32; 16] = mvp_matrix.as_ref();
-        self.init.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(mvp_ref));
+        text
     }
 
-    fn update_mouse(&mut self, position: PhysicalPosition<f64>) {
-        let model_matrix = transforms::create_transforms([0.0, 0.0, 0.0], [-(position.y/100.00) as f32, (position.x/100.00) as f32, 0.0], [1.0, 1.0, 1.0]);
-        let mvp_matrix = self.projection_matrix * self.view_matrix * model_matrix;        
-        let mvp_ref:&[f32; 16] = mvp_matrix.as_ref();
+    fn render(&mut self) -> Result<(), Error> {
+        // a minimized window has no surface texture worth getting, asking for one only errors
+        if self.minimized || self.config().width == 0 || self.config().height == 0 {
+            return Ok(());
+        }
 
-        println!("Mouse position: ({}, {})", position.x, position.y);
+        let now = std::time::Instant::now();
+        // the frame's cpu time runs from update, when there was one, to the submit
+        let cpu_start = self.update_start.take().unwrap_or(now);
+        if let Some(timer) = &mut self.gpu_timer {
+            timer.poll(&self.init.device);
+        }
+        let frame_time = (now - self.last_frame).as_secs_f32();
+        let frame_fps = 1.0 / frame_time.max(f32::EPSILON);
+        self.fps = if self.fps == 0.0 { frame_fps } else { self.fps + FPS_SMOOTHING * (frame_fps - self.fps) };
+        self.last_frame = now;
+        if self.show_instances {
+            self.batching_fps[self.grid_batching as usize] = self.fps;
+        }
 
-        
-        self.init.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(mvp_ref))
-    }
+        // the second window draws after the last frame's stats were taken, its draws aren't counted
+        self.draw_counter.take();
+
+        // this frame's uniforms go into the next slot of the ring, the previous frame may still be reading its own
+        self.uniforms.advance();
+        self.write_uniforms();
+        self.write_split_view_uniforms();
+        self.write_outline();
+        self.write_reflection_sphere();
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         //let output = self.init.surface.get_current_frame()?.output;
         print!("dasdas");
 
-        let output = self.init.surface.get_current_texture()?;
+        let instances = self.write_instances();
+        let instance_count = instances.len() as u32;
+        self.write_bounding_boxes(&instances);
+        self.write_normal_lines(&instances);
+
+        if let Some((start, end)) = self.pick_ray {
+            self.debug_lines.debug_line(start.into(), end.into(), PICK_RAY_COLOR);
+        }
+        self.debug_lines.flush(&self.init.device, &self.init.queue, &self.init.tracker);
+
+        if let Some(summary) = self.init.tracker.take_changed_summary() {
+            log::info!("gpu resources: {}", summary);
+        }
+
+        // suspended, there is nothing to present to until the surface is recreated
+        let Some(surface) = &self.init.surface else {
+            return Ok(());
+        };
+        // waiting for a swapchain image is the presentation engine pacing the frame, not cpu work
+        let acquire_start = std::time::Instant::now();
+        let output = surface.get_current_texture()?;
+        let acquire_time = acquire_start.elapsed();
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        let depth_texture = self.init.device.create_texture(&wgpu::TextureDescriptor {
-            size: wgpu::Extent3d {
-                width: self.init.config.width,
-                height: self.init.config.height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format:wgpu::TextureFormat::Depth24Plus,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            label: None,
-            view_formats: &[],
-        });
-        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
-        
         let mut encoder = self
             .init.device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Render Encoder"),
             });
+        if let Some(timer) = &self.gpu_timer {
+            timer.begin(&mut encoder);
+        }
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
+        // the particles move on the animation clock, so they freeze with everything else on pause
+        let particle_steps = std::mem::take(&mut self.particle_steps);
+        if self.show_particles && particle_steps > 0 {
+            self.particles.simulate_steps(&mut encoder, &self.init.queue, PARTICLE_GRAVITY, self.particle_dt,
+                self.animation_time.as_secs_f32(), particle_steps);
+        }
+
+        if self.show_procedural {
+            let resolution = [self.config().width as f32, self.config().height as f32];
+            let mouse = [self.cursor_position.x as f32, self.cursor_position.y as f32];
+            self.procedural.write(&self.init.queue, self.animation_time.as_secs_f32(), resolution, mouse);
+            self.procedural.draw(&mut encoder, &view);
+        } else {
+            self.update_reflection_probe(&mut encoder, instance_count);
+            self.draw_scene(&mut encoder, instance_count);
+
+            if self.depth_view_mode != DepthViewMode::Off {
+                self.depth_view.write(&self.init.queue, transforms::Z_NEAR, transforms::Z_FAR, DEPTH_VIEW_RANGE, self.depth_mode,
+                    self.depth_view_mode);
+                self.depth_view.draw(&mut encoder, &view, &self.depth_view_bind_group);
+            } else if self.aa_mode() == AaMode::Fxaa {
+                // anti-aliases while upscaling the internal resolution target onto the swapchain
+                self.fxaa.draw(&mut encoder, &view, &self.fxaa_bind_group);
+            } else {
+                // upscale the internal resolution target onto the swapchain
+                self.blit.draw(&mut encoder, &view, self.blit_bind_groups.get(self.blit_filter), self.ssaa_factor > 1);
+            }
+        }
+
+        // the hud and crosshair go straight onto the swapchain so they stay sharp at any render scale
+        if self.show_hud || self.show_crosshair {
+            self.text.begin_frame(&self.init.queue, self.config().width, self.config().height);
+
+            let mut hud_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Hud Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.2,
-                            g: 0.247,
-                            b: 0.314,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Load,
                         store: StoreOp::Store,
                     },
                 })],
-                //depth_stencil_attachment: None,
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &depth_view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: StoreOp::Discard,
-                    }),
-                    stencil_ops: None,
-                }),
                 ..Default::default()
             });
 
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));           
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            render_pass.draw(0..36, 0..1);
+            let scale = self.scale_factor as f32;
+            if self.show_hud {
+                self.text.draw_text(&mut hud_pass, &self.init.queue, &self.hud_text(), HUD_MARGIN * scale, HUD_MARGIN * scale,
+                    HUD_SCALE * scale, HUD_COLOR);
+            }
+            if self.show_crosshair {
+                let rects = crosshair_rects(self.config().width, self.config().height, self.scale_factor);
+                self.text.draw_rects(&mut hud_pass, &self.init.queue, &rects, CROSSHAIR_COLOR);
+            }
+        }
+
+        if let Some(timer) = &mut self.gpu_timer {
+            timer.end(&mut encoder);
         }
+        let cpu_record_time = cpu_start.elapsed().saturating_sub(acquire_time);
+        self.cpu_times.push(cpu_record_time.as_secs_f32() * 1000.0);
+        let (draw_calls, triangles) = self.draw_counter.take();
+        self.frame_stats = FrameStats {
+            draw_calls,
+            triangles,
+            cpu_record_time,
+            gpu_time: self.gpu_timer.as_ref()
+                .and_then(|timer| timer.times.latest())
+                .map(|milliseconds| std::time::Duration::from_secs_f32(milliseconds / 1000.0)),
+        };
 
         self.init.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(timer) = &mut self.gpu_timer {
+            timer.after_submit();
+        }
         output.present();
 
+        if self.second_window.is_some() {
+            self.render_second_window(instance_count);
+        }
+
+        // the frame just shown says the file is loading, the load itself may take a while
+        if let Some(path) = self.loading_file.take() {
+            self.load_model_file(&path);
+            self.window.request_redraw();
+        }
+
+        Ok(())
+    }
+
+    // the scene pass into the offscreen target, everything but the hud
+    fn draw_scene(&self, encoder: &mut wgpu::CommandEncoder, instance_count: u32) {
+        let mut render_pass = begin_scene_pass(encoder, &self.target, self.scene_clear_color(), self.depth_mode);
+
+        // split screen: the mesh alone in the orthographic quadrants, then everything in the main camera's.
+        // the quadrants have the window's aspect ratio, so the main projection fits them unchanged
+        if let Some(views) = &self.split_views {
+            let (width, height) = (self.target.width, self.target.height);
+            for (index, view) in views.iter().enumerate() {
+                let [x, y, w, h] = split_screen::quadrant_viewport(index, width, height);
+                render_pass.set_viewport(x, y, w, h, 0.0, 1.0);
+                self.draw_mesh(&mut render_pass, &view.uniform_bind_group, instance_count);
+            }
+            let [x, y, w, h] = split_screen::quadrant_viewport(split_screen::MAIN_QUADRANT, width, height);
+            render_pass.set_viewport(x, y, w, h, 0.0, 1.0);
+        }
+
+        self.draw_mesh(&mut render_pass, self.uniforms.bind_group(), instance_count);
+        if let Some((probe, _)) = &self.reflection_probe {
+            probe.draw_sphere(&mut render_pass);
+        }
+
+        self.line_pipeline.draw(&mut render_pass, &self.bounding_box_lines);
+        self.line_pipeline.draw(&mut render_pass, &self.marker_lines);
+        self.line_pipeline.draw(&mut render_pass, &self.frustum_lines);
+        match self.reference_grid {
+            ReferenceGrid::Off => {}
+            ReferenceGrid::Rectangular => self.line_pipeline.draw(&mut render_pass, &self.rectangular_grid_lines),
+            ReferenceGrid::Polar => self.line_pipeline.draw(&mut render_pass, &self.polar_grid_lines),
+            ReferenceGrid::Shader => {}
+        }
+        self.line_pipeline.draw(&mut render_pass, &self.normal_lines);
+        self.line_pipeline.draw(&mut render_pass, &self.debug_lines.buffer);
+        if self.show_camera_path {
+            self.line_pipeline.draw_strips(&mut render_pass, &self.camera_path);
+        }
+        if self.show_particles {
+            self.particles.draw(&mut render_pass);
+        }
+
+        // translucent geometry last, over the finished opaque scene
+        if self.reference_grid == ReferenceGrid::Shader {
+            self.shader_grid.draw(&mut render_pass);
+        }
+        // the cubes, the torus and the quad are each sorted among themselves only, the quad stays last
+        if self.translucent_cubes_order.is_some() {
+            self.translucent_cubes.draw(&mut render_pass);
+        }
+        if self.translucent_mesh_order.is_some() {
+            self.translucent_mesh.draw(&mut render_pass);
+        }
+        if let Some(mode) = self.translucent_mode {
+            self.translucent.draw(&mut render_pass, mode);
+        }
+        drop(render_pass);
+
+        if self.show_outline && self.selected_instance.is_some() {
+            let viewport = self.split_views.is_some()
+                .then(|| split_screen::quadrant_viewport(split_screen::MAIN_QUADRANT, self.target.width, self.target.height));
+            self.outline.draw(encoder, &self.target, self.uniforms.bind_group(), &self.mesh, viewport);
+        }
+    }
+
+    fn write_reflection_sphere(&self) {
+        if let Some((probe, _)) = &self.reflection_probe {
+            probe.write_sphere(&self.init.queue, self.projection_matrix * self.view_matrix, self.camera_position);
+        }
+    }
+
+    fn write_outline(&self) {
+        let Some(instance) = self.selected_instance else {
+            return;
+        };
+        let (center, _) = transforms::bounding_sphere(self.mesh_bounds.0, self.mesh_bounds.1);
+        self.outline.write_settings(&self.init.queue, OUTLINE_COLOR, center.into(), OUTLINE_SCALE);
+        self.outline.write_instance(&self.init.queue, instance.into());
+    }
+
+    // a mirror sphere beside the model reflecting it through a cube map rendered from the sphere's center.
+    // the faces are drawn with the current shader into single sampled layers, so they get their own pipeline
+    fn toggle_reflection_probe(&mut self) {
+        if self.reflection_probe.take().is_some() {
+            self.update_title();
+            return;
+        }
+
+        let probe = ReflectionProbe::new(&self.init.device, &self.init.tracker, self.config().format, self.target.sample_count,
+            self.depth_mode, &self.uniform_bind_group_layout, &[wgpu::BindGroupEntry {
+                binding: 1,
+                resource: self.lights_buffer.as_entire_binding(),
+            }], std::mem::size_of::<Uniforms>() as wgpu::BufferAddress, PROBE_POSITION.into(), PROBE_SPHERE_RADIUS);
+
+        // the faces' flipped projection reverses the winding, see ReflectionProbe::view_projections
+        let key = PipelineKey {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            cull_mode: None,
+            depth_bias: false,
+            sample_count: 1,
+            ..self.pipeline_key
+        };
+        let targets = PipelineTargets {
+            color_format: self.config().format,
+            depth_format: render_target::DEPTH_FORMAT,
+            sample_count: 1,
+        };
+        let shaders = if key.linear_interpolation { &self.linear_shaders } else { &self.shaders };
+        let pipeline = create_pipeline(&self.init.device, &shaders[key.shader], &self.pipeline_layout, &targets, &key, self.depth_mode);
+
+        self.reflection_probe = Some((probe, pipeline));
+        self.update_title();
+    }
+
+    fn cycle_probe_interval(&mut self) {
+        let Some((probe, _)) = &mut self.reflection_probe else {
+            log::info!("no reflection probe, turn it on first");
+            return;
+        };
+        probe.cycle_update_interval();
+        self.update_title();
+    }
+
+    // renders the probe's six faces when an update is due, with the instances left after culling for the
+    // main view
+    fn update_reflection_probe(&mut self, encoder: &mut wgpu::CommandEncoder, instance_count: u32) {
+        if !self.reflection_probe.as_mut().is_some_and(|(probe, _)| probe.due()) {
+            return;
+        }
+
+        let Some((probe, pipeline)) = &self.reflection_probe else {
+            return;
+        };
+        for (face, view_projection) in probe.faces.iter().zip(probe.view_projections(self.depth_mode)) {
+            let uniforms = self.scene_uniforms(view_projection, probe.position);
+            self.init.queue.write_buffer(&face.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        }
+
+        for (index, face) in probe.faces.iter().enumerate() {
+            let mut pass = probe.begin_face_pass(encoder, index, self.scene_clear_color(), self.depth_mode);
+            let mesh = self.drawn_mesh();
+            pass.set_pipeline(pipeline);
+            pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+            pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            pass.set_bind_group(0, &face.uniform_bind_group, &[]);
+            pass.set_bind_group(1, &self.active_texture_bind_groups().levels[self.anisotropy_index].1, &[]);
+            pass.set_bind_group(2, &self.lightmap_bind_group, &[]);
+            self.draw_batched(&mut pass, mesh, instance_count);
+        }
+    }
+
+    // upload_mesh bakes the merged mesh only while it's the one drawn, at the largest grid it runs to
+    // tens of thousands of copies of the mesh
+    fn cycle_grid_batching(&mut self) {
+        self.grid_batching = self.grid_batching.next();
+        if !self.show_instances {
+            log::info!("{} batching applies to the instance grid, show it with i", self.grid_batching.name());
+        }
+        self.upload_mesh();
+        self.update_title();
+    }
+
+    // the frustum of a second, inspection camera drawn as a wireframe, its near plane green and its far
+    // plane red. the arrow keys turn it around the origin while it's shown
+    fn toggle_inspection_camera(&mut self) {
+        self.inspection_camera = match self.inspection_camera {
+            Some(_) => None,
+            None => Some(InspectionCamera {
+                position: INSPECTION_CAMERA_POSITION.into(),
+                perspective: true,
+            }),
+        };
+        self.write_frustum_lines();
+        self.update_title();
+    }
+
+    // a perspective frustum is a truncated pyramid, an orthographic one a box
+    fn toggle_inspection_projection(&mut self) {
+        let Some(camera) = &mut self.inspection_camera else {
+            log::info!("show the inspection camera's frustum first");
+            return;
+        };
+        camera.perspective = !camera.perspective;
+        self.write_frustum_lines();
+        self.update_title();
+    }
+
+    fn orbit_inspection_camera(&mut self, yaw: f32, pitch: f32) {
+        if let Some(camera) = &mut self.inspection_camera {
+            camera.position = transforms::orbit(camera.position, Point3::origin(), Vector3::unit_y(), yaw, pitch);
+        }
+        self.write_frustum_lines();
+    }
+
+    fn write_frustum_lines(&mut self) {
+        let Some(camera) = &self.inspection_camera else {
+            self.frustum_lines.clear();
+            return;
+        };
+        let aspect = self.config().width as f32 / self.config().height as f32;
+        let corners = transforms::frustum_corners(camera.view_projection(aspect));
+        let lines = vertex_data::frustum_lines(&corners, FRUSTUM_NEAR_COLOR, FRUSTUM_FAR_COLOR, FRUSTUM_SIDE_COLOR);
+        self.frustum_lines.write(&self.init.device, &self.init.queue, &self.init.tracker, &lines);
+    }
+
+    // outlines the object picked with the left button, through the stencil buffer
+    fn toggle_outline(&mut self) {
+        self.show_outline = !self.show_outline;
+        if self.show_outline && self.selected_instance.is_none() {
+            log::info!("nothing is selected, click an object to outline it");
+        }
+        self.update_title();
+    }
+
+    // saves the last frame's offscreen target to the next free screenshot file: at render scale, without
+    // the hud. encode_frame writes srgb whatever the target's format
+    fn save_screenshot(&self) {
+        let path = (0..)
+            .map(|n| PathBuf::from(format!("{}{:04}.png", SCREENSHOT_PREFIX, n)))
+            .find(|path| !path.exists())
+            .expect("screenshot numbers ran out");
+
+        let result = self.target
+            .read_texels(&self.init.device, &self.init.queue, &self.init.tracker)
+            .and_then(|texels| render_target::encode_frame(self.target.format, self.target.width, self.target.height, texels))
+            .and_then(|png| Ok(std::fs::write(&path, png)?));
+        match result {
+            Ok(()) => log::info!("saved {}", path.display()),
+            Err(e) => log::error!("could not save a screenshot to {}: {}", path.display(), e),
+        }
+    }
+
+    // renders frames images of the model turning once about y into dir/frame_XXXX.png. the angle comes
+    // from the frame index rather than the clock, so the same count always gives the same frames.
+    // frames are read from the offscreen target, at render scale and without the hud
+    fn capture_turntable(&mut self, frames: u32, dir: &std::path::Path) -> Result<(), Error> {
+        std::fs::create_dir_all(dir)?;
+
+        for frame in 0..frames {
+            let angle = std::f32::consts::TAU * frame as f32 / frames as f32;
+            self.model_matrix = transforms::create_transforms([0.0, 0.0, 0.0], [0.0, angle, 0.0], self.model_scale) * model_basis(self.z_up, self.left_handed);
+            self.update_model_uniform();
+
+            let instances = self.write_instances();
+            self.write_bounding_boxes(&instances);
+            self.write_normal_lines(&instances);
+
+            let mut encoder = self.init.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Turntable Encoder"),
+            });
+            self.draw_scene(&mut encoder, instances.len() as u32);
+            self.init.queue.submit(std::iter::once(encoder.finish()));
+
+            let image = self.target.read_color(&self.init.device, &self.init.queue, &self.init.tracker)?;
+            let path = dir.join(format!("frame_{:04}.png", frame));
+            image.save(&path)?;
+        }
+
         Ok(())
     }
+
+    fn draw_mesh<'pass>(&'pass self, render_pass: &mut wgpu::RenderPass<'pass>, uniform_bind_group: &'pass wgpu::BindGroup,
+        instance_count: u32) {
+        if self.pipeline_key.topology == wgpu::PrimitiveTopology::PointList {
+            self.point_cloud.draw(render_pass, uniform_bind_group, &self.instance_buffer, instance_count);
+            self.draw_counter.record(0);
+            return;
+        }
+
+        if self.scene.draw_mesh(self, render_pass, uniform_bind_group, instance_count) {
+            return;
+        }
+
+        let Some(pipeline) = self.pipelines.get(&self.pipeline_key) else {
+            return;
+        };
+        let mesh = self.drawn_mesh();
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));           
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.active_texture_bind_groups().levels[self.anisotropy_index].1, &[]);
+        render_pass.set_bind_group(2, &self.lightmap_bind_group, &[]);
+        self.draw_batched(render_pass, mesh, instance_count);
+
+        if self.wireframe_overlay {
+            if let Some(overlay) = self.pipelines.get(&self.overlay_key()) {
+                render_pass.set_pipeline(overlay);
+                self.draw_batched(render_pass, mesh, instance_count);
+            }
+        }
+    }
+
+    // the merged mesh when batching merges, otherwise the indexed or expanded upload as indexed_drawing asks
+    fn drawn_mesh(&self) -> &GpuMesh {
+        match &self.merged_mesh {
+            Some(merged) => merged,
+            None if self.indexed_drawing => &self.mesh,
+            None => &self.expanded_mesh,
+        }
+    }
+
+    // the draw calls for the bound mesh as grid_batching asks: every instance in one call, a call per
+    // instance, or the single copy of the merged mesh. without indexed_drawing the mesh is expanded
+    // and drawn straight from its vertex buffer
+    fn draw_batched(&self, render_pass: &mut wgpu::RenderPass, mesh: &GpuMesh, instance_count: u32) {
+        let mut draw = |instances: std::ops::Range<u32>| {
+            let instance_count = (instances.end - instances.start) as u64;
+            if self.indexed_drawing {
+                self.draw_counter.record(mesh.index_count as u64 / 3 * instance_count);
+                render_pass.draw_indexed(0..mesh.index_count, 0, instances);
+            } else {
+                self.draw_counter.record(mesh.vertex_count as u64 / 3 * instance_count);
+                render_pass.draw(0..mesh.vertex_count, instances);
+            }
+        };
+        match self.grid_batching {
+            GridBatching::PerObject if self.show_instances => {
+                for instance in 0..instance_count {
+                    draw(instance..instance + 1);
+                }
+            }
+            _ if self.merged_mesh.is_some() => draw(0..1),
+            _ => draw(0..instance_count),
+        }
+    }
+
+    fn render_second_window(&mut self, instance_count: u32) {
+        let Some(second) = &self.second_window else {
+            return;
+        };
+
+        let output = match second.surface.surface.get_current_texture() {
+            Ok(output) => output,
+            Err(wgpu::SurfaceError::Lost) => {
+                let size = second.surface.window.inner_size();
+                if let Some(second) = &mut self.second_window {
+                    second.surface.resize(&self.init.device, size);
+                }
+                return;
+            }
+            Err(e) => {
+                eprintln!("{:?}", e);
+                return;
+            }
+        };
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let uniforms = self.scene_uniforms(second.projection_matrix * second.view_matrix, SECOND_CAMERA_POSITION.into());
+        self.init.queue.write_buffer(&second.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut encoder = self.init.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Second Window Encoder"),
+        });
+
+        {
+            let mut render_pass = begin_scene_pass(&mut encoder, &second.target, self.scene_clear_color(), self.depth_mode);
+            self.draw_mesh(&mut render_pass, &second.uniform_bind_group, instance_count);
+        }
+
+        self.blit.draw(&mut encoder, &view, second.blit_bind_groups.get(self.blit_filter), false);
+
+        self.init.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+    }
 }
 
 fn main() {
     env_logger::init();
-    let event_loop = EventLoop::new().unwrap();
-    let window = WindowBuilder::new().build(&event_loop).unwrap();
+    let mut args = Args::parse();
+    if args.list_adapters {
+        println!("{}", transforms::adapter_report());
+        return;
+    }
+
+    // only removed after run returns, a panic or a driver abort anywhere in init or the event loop leaves it
+    let marker = std::env::temp_dir().join(RUNNING_MARKER);
+    if marker.exists() {
+        log::warn!("the previous run did not shut down cleanly ({} is still there), starting in safe mode: \
+            msaa and optional device features are off", marker.display());
+        args.safe_mode = true;
+    } else if args.safe_mode {
+        log::info!("safe mode: msaa and optional device features are off");
+    }
+    if let Err(e) = std::fs::write(&marker, std::process::id().to_string()) {
+        log::warn!("could not write {}, a crash won't enable safe mode next time: {}", marker.display(), e);
+    }
+
+    // settings from the file fill in what the command line leaves out
+    let config_path = args.config_path.clone().unwrap_or_else(|| PathBuf::from(app_config::DEFAULT_CONFIG_FILE));
+    let mut app_config = AppConfig::load(&config_path);
+    args.model = args.model.take().or(app_config.model.take());
+    app_config.model = args.model.clone();
+
+    let result = run(&args, app_config, config_path);
+
+    if let Err(e) = std::fs::remove_file(&marker) {
+        log::warn!("could not remove {}, the next run will start in safe mode: {}", marker.display(), e);
+    }
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
 
-    window.set_title(&*format!("{}", "cube with distinct face colors"));
+// a plus centered on the middle pixel boundary of a width x height window, as a horizontal bar and the
+// vertical bar's two halves above and below it so the blended color isn't doubled where they cross.
+// sizes are rounded to whole physical pixels so the lines stay crisp
+fn crosshair_rects(width: u32, height: u32, scale_factor: f64) -> [[f32; 4]; 3] {
+    let (x, y) = ((width / 2) as f32, (height / 2) as f32);
+    let arm = (CROSSHAIR_SIZE * scale_factor as f32).round();
+    let thickness = (CROSSHAIR_THICKNESS * scale_factor as f32).round().max(1.0);
+    let half = (thickness / 2.0).floor();
+
+    [
+        [x - arm, y - half, 2.0 * arm, thickness],
+        [x - half, y - arm, thickness, arm - half],
+        [x - half, y - half + thickness, thickness, arm - thickness + half],
+    ]
+}
+
+// the particle simulation on a device without a surface: steps of 1/60 s, then the particles are read
+// back and summarised. useful on machines without a display and for checking the compute shader alone
+fn run_headless(steps: u32, backends: wgpu::Backends, seed: u64) -> Result<(), Error> {
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(backends))?;
+    let particles = Particles::new(&init.device, &init.tracker, wgpu::TextureFormat::Rgba8Unorm, 1, DepthMode::Standard, PARTICLE_COUNT,
+        seed);
+
+    let dt = 1.0 / 60.0;
+    for step in 0..steps {
+        let mut encoder = init.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Particle Encoder"),
+        });
+        particles.simulate(&mut encoder, &init.queue, PARTICLE_GRAVITY, dt, step as f32 * dt);
+        init.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    let result = particles.read_back(&init.device, &init.queue, &init.tracker)?;
+    let count = result.len().max(1) as f32;
+    let mean_height = result.iter().map(|p| p.position[1]).sum::<f32>() / count;
+    let mean_life = result.iter().map(|p| p.life).sum::<f32>() / count;
+    println!("{} particles after {} steps on {}: mean height {:.3}, mean life {:.3}",
+        result.len(), steps, init.adapter.get_info().name, mean_height, mean_life);
+
+    Ok(())
+}
+
+fn run(args: &Args, app_config: AppConfig, config_path: PathBuf) -> Result<(), Error> {
+    if let Some(steps) = args.headless_steps {
+        return run_headless(steps, app_config.backends.unwrap_or(wgpu::Backends::all()), args.seed);
+    }
+
+    let event_loop = EventLoop::new()?;
+    let window = window_builder(args).build(&event_loop)?;
+
+    window.set_title(TITLE);
+
+    let mut state = pollster::block_on(State::new(&window, args, app_config))?;
+    if args.precompile {
+        state.precompile_pipelines();
+    }
+    state.check_render_config();
+
+    if let Some(frames) = args.turntable_frames {
+        let dir = args.turntable_dir.clone().unwrap_or_else(|| PathBuf::from(TURNTABLE_DIR));
+        match state.capture_turntable(frames, &dir) {
+            Ok(()) => log::info!("wrote {} turntable frames to {}", frames, dir.display()),
+            Err(e) => log::error!("turntable capture into {} failed: {}", dir.display(), e),
+        }
+        return Ok(());
+    }
 
-    let mut state = pollster::block_on(State::new(&window));
     let start_time = std::time::Instant::now();
 
     event_loop.run(move |event, event_loop_window| {
         match event {
+            // winit's replacement for RedrawEventsCleared: every pending event has been handled. poll
+            // and keep requesting frames while something animates, otherwise sleep until the next event
+            Event::AboutToWait => {
+                if state.needs_continuous_redraw() {
+                    event_loop_window.set_control_flow(ControlFlow::Poll);
+                    state.window.request_redraw();
+                } else {
+                    event_loop_window.set_control_flow(ControlFlow::Wait);
+                }
+            }
+
+            Event::Suspended => state.suspend(),
+
+            // also sent once at startup, when the surface from State::new is still there
+            Event::Resumed => state.resume(),
+
+            Event::WindowEvent { window_id, ref event } if state.is_second_window(window_id) => {
+                state.second_window_event(event);
+            }
+
+            Event::WindowEvent { ref event, .. } if state.input(event) => {
+                if std::mem::take(&mut state.second_window_requested) {
+                    state.toggle_second_window(event_loop_window);
+                }
+                state.window.request_redraw();
+            }
+
             Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
                 println!("The close button was pressed; stopping");
+                if let Err(e) = state.app_config().save(&config_path) {
+                    log::warn!("could not save the settings to {}: {}", config_path.display(), e);
+                }
                 event_loop_window.exit();
             },
 
-            Event::WindowEvent { event: WindowEvent::CursorMoved { position, ..}, .. } => {
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } if state.mouse_motion(delta) => {
+                state.window.request_redraw();
+            }
+
+            // the cursor drags the model around, except while it is grabbed by the fps camera
+            Event::WindowEvent { event: WindowEvent::CursorMoved { position, ..}, .. } if !state.fps_camera => {
                 state.update_mouse(position);
 
                 match state.render() {
                     Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost) => state.resize(state.init.size),
-                    Err(wgpu::SurfaceError::OutOfMemory) => event_loop_window.exit(),
-                    Err(e) => eprintln!("{:?}", e),
+                    Err(Error::Surface(wgpu::SurfaceError::Lost)) => state.resize(state.init.size),
+                    Err(Error::Surface(wgpu::SurfaceError::OutOfMemory)) => event_loop_window.exit(),
+                    Err(e) => eprintln!("{}", e),
                 }
             }
 
@@ -321,9 +4387,9 @@ fn main() {
 
                 match state.render() {
                     Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost) => state.resize(state.init.size),
-                    Err(wgpu::SurfaceError::OutOfMemory) => event_loop_window.exit(),
-                    Err(e) => eprintln!("{:?}", e),
+                    Err(Error::Surface(wgpu::SurfaceError::Lost)) => state.resize(state.init.size),
+                    Err(Error::Surface(wgpu::SurfaceError::OutOfMemory)) => event_loop_window.exit(),
+                    Err(e) => eprintln!("{}", e),
                 }
             }
 
@@ -333,5 +4399,7 @@ fn main() {
 
             _ => {}
         }
-    }).unwrap();
+    })?;
+
+    Ok(())
 }
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Matrix4, MetricSpace, Point3, Vector3, Vector4};
+
+use crate::{lines::LineVertex, resource_tracker::ResourceTracker, transforms, vertex_data::Vertex};
+
+// indexed triangle list on the cpu
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    // a mesh drawing every vertex once, in order
+    pub fn from_vertices(vertices: Vec<Vertex>) -> Self {
+        let indices = (0..vertices.len() as u32).collect();
+
+        Mesh {
+            vertices,
+            indices,
+        }
+    }
+}
+
+// one level of loop subdivision: every triangle is split into four through its edge midpoints, then
+// positions are smoothed with the loop weights so repeated subdivision converges to a rounded surface.
+// vertices are welded by position for the smoothing only, so seams in color or uv (like the cube's
+// per-face colors) survive; the other attributes are interpolated linearly. open edges are kept in
+// place rather than smoothed. normals are recomputed afterwards from the new surface.
+pub fn subdivide(vertices: &[Vertex], indices: &[u32]) -> (Vec<Vertex>, Vec<u32>) {
+    let (welded, positions) = weld(vertices);
+    let triangles: Vec<[u32; 3]> = indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect();
+
+    // opposite welded vertex of every triangle touching each welded edge
+    let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for t in &triangles {
+        for i in 0..3 {
+            let (a, b, c) = (welded[t[i] as usize], welded[t[(i + 1) % 3] as usize], welded[t[(i + 2) % 3] as usize]);
+            edges.entry(edge_key(a, b)).or_default().push(c);
+        }
+    }
+
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+    let mut on_boundary = vec![false; positions.len()];
+    for (&(a, b), opposite) in &edges {
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+        if opposite.len() != 2 {
+            on_boundary[a] = true;
+            on_boundary[b] = true;
+        }
+    }
+
+    let smoothed: Vec<Vector3<f32>> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &position)| {
+            let n = neighbours[i].len();
+            if on_boundary[i] || n == 0 {
+                return position;
+            }
+
+            let beta = if n == 3 { 3.0 / 16.0 } else { 3.0 / (8.0 * n as f32) };
+            let sum: Vector3<f32> = neighbours[i].iter().map(|&j| positions[j]).sum();
+            position * (1.0 - n as f32 * beta) + sum * beta
+        })
+        .collect();
+
+    let edge_point = |a: usize, b: usize| {
+        match edges[&edge_key(a, b)].as_slice() {
+            &[c, d] => (positions[a] + positions[b]) * 0.375 + (positions[c] + positions[d]) * 0.125,
+            _ => (positions[a] + positions[b]) * 0.5,
+        }
+    };
+
+    let mut builder = MeshBuilder::default();
+    for t in &triangles {
+        let corners = t.map(|i| vertices[i as usize]);
+        let ids = t.map(|i| welded[i as usize]);
+
+        let corner = |i: usize| with_position(corners[i], smoothed[ids[i]]);
+        let midpoint = |i: usize, j: usize| with_position(lerp(&corners[i], &corners[j]), edge_point(ids[i], ids[j]));
+
+        let (v0, v1, v2) = (corner(0), corner(1), corner(2));
+        let (m01, m12, m20) = (midpoint(0, 1), midpoint(1, 2), midpoint(2, 0));
+
+        builder.triangle([v0, m01, m20]);
+        builder.triangle([m01, v1, m12]);
+        builder.triangle([m20, m12, v2]);
+        builder.triangle([m01, m12, m20]);
+    }
+
+    let MeshBuilder { mut vertices, indices, .. } = builder;
+    compute_normals(&mut vertices, &indices);
+
+    (vertices, indices)
+}
+
+// every triangle gets its own three vertices with the barycentric coordinates (1, 0, 0), (0, 1, 0) and
+// (0, 0, 1), which shared vertices can't have in general. the index buffer stays, counting up
+pub fn unweld(mesh: &Mesh) -> Mesh {
+    let corners = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let vertices = mesh.indices
+        .chunks_exact(3)
+        .flat_map(|t| (0..3).map(move |i| (t[i], corners[i])))
+        .map(|(index, barycentric)| Vertex { barycentric, ..mesh.vertices[index as usize] })
+        .collect();
+
+    Mesh::from_vertices(vertices)
+}
+
+// shares every vertex whose attributes are bit for bit identical, the smallest vertex buffer the index
+// buffer allows. a cube only shrinks to its 8 corners when the faces meeting at a corner agree on
+// every attribute; with per face colors and normals it keeps 4 vertices per face
+pub fn deduplicate(mesh: &Mesh) -> Mesh {
+    let mut builder = MeshBuilder::default();
+    for t in mesh.indices.chunks_exact(3) {
+        builder.triangle([0, 1, 2].map(|i| mesh.vertices[t[i] as usize]));
+    }
+
+    Mesh { vertices: builder.vertices, indices: builder.indices }
+}
+
+// one vertex per index, in index order, for drawing without the index buffer. the indices count up
+pub fn expand(mesh: &Mesh) -> Mesh {
+    Mesh::from_vertices(mesh.indices.iter().map(|index| mesh.vertices[*index as usize]).collect())
+}
+
+// static batching: every object's mesh with its transform baked into the vertices, appended into one
+// mesh drawn in a single call. positions and morph targets go through the matrix, normals through its
+// normal matrix. the cpu does once what instancing has the gpu do every frame, at the cost of memory
+// growing with the object count and no per object culling or movement afterwards
+pub fn merge(objects: &[(Matrix4<f32>, &Mesh)]) -> Mesh {
+    let vertex_count = objects.iter().map(|(_, mesh)| mesh.vertices.len()).sum();
+    let index_count = objects.iter().map(|(_, mesh)| mesh.indices.len()).sum();
+    let mut vertices = Vec::with_capacity(vertex_count);
+    let mut indices = Vec::with_capacity(index_count);
+
+    for (transform, mesh) in objects {
+        let normal_matrix = transforms::normal_matrix(*transform);
+        let point = |p: [f32; 4]| -> [f32; 4] { (transform * Vector4::from(p)).into() };
+        let normal = |n: [f32; 3]| -> [f32; 3] {
+            let n = normal_matrix * Vector3::from(n);
+            if n.magnitude2() == 0.0 { n.into() } else { n.normalize().into() }
+        };
+
+        let base = vertices.len() as u32;
+        vertices.extend(mesh.vertices.iter().map(|v| Vertex {
+            position: point(v.position),
+            morph_position: point(v.morph_position),
+            normal: normal(v.normal),
+            morph_normal: normal(v.morph_normal),
+            ..*v
+        }));
+        indices.extend(mesh.indices.iter().map(|index| base + index));
+    }
+
+    Mesh { vertices, indices }
+}
+
+// a sphere of rings bands from pole to pole and segments slices around y, with smooth normals and
+// uvs wrapping once around. the seam and the poles repeat their vertices so the uvs don't wrap back
+pub fn uv_sphere(radius: f32, rings: u32, segments: u32, color: [f32; 3]) -> Mesh {
+    let mut vertices = Vec::with_capacity(((rings + 1) * (segments + 1)) as usize);
+    for ring in 0..=rings {
+        let v = ring as f32 / rings as f32;
+        let theta = v * std::f32::consts::PI;
+        for segment in 0..=segments {
+            let u = segment as f32 / segments as f32;
+            let phi = u * std::f32::consts::TAU;
+            let normal = [theta.sin() * phi.cos(), theta.cos(), -theta.sin() * phi.sin()];
+            let position = [normal[0] * radius, normal[1] * radius, normal[2] * radius];
+            vertices.push(Vertex::new(position, color, normal, [u, v]));
+        }
+    }
+
+    let row = segments + 1;
+    let mut indices = Vec::with_capacity((rings * segments * 6) as usize);
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * row + segment;
+            let b = a + row;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    Mesh { vertices, indices }
+}
+
+// a flat square of size by size in the xz plane centered on the origin, cut into subdivisions by
+// subdivisions cells. normals point up and the uvs span [0, 1] once over the square
+pub fn plane(size: f32, subdivisions: u32, color: [f32; 3]) -> Mesh {
+    let subdivisions = subdivisions.max(1);
+    let mut vertices = Vec::with_capacity(((subdivisions + 1) * (subdivisions + 1)) as usize);
+    for row in 0..=subdivisions {
+        let v = row as f32 / subdivisions as f32;
+        for column in 0..=subdivisions {
+            let u = column as f32 / subdivisions as f32;
+            let position = [(u - 0.5) * size, 0.0, (v - 0.5) * size];
+            vertices.push(Vertex::new(position, color, [0.0, 1.0, 0.0], [u, v]));
+        }
+    }
+
+    // counter clockwise seen from above, like vertex_data::heightmap
+    let stride = subdivisions + 1;
+    let mut indices = Vec::with_capacity((subdivisions * subdivisions * 6) as usize);
+    for row in 0..subdivisions {
+        for column in 0..subdivisions {
+            let a = row * stride + column;
+            let b = a + stride;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    Mesh { vertices, indices }
+}
+
+// a ring around y of rings tube sections, each a circle of segments vertices. major_radius is from the
+// center to the middle of the tube, minor_radius the tube's own. smooth normals, and the seams repeat
+// their vertices like uv_sphere's
+pub fn torus(major_radius: f32, minor_radius: f32, rings: u32, segments: u32, color: [f32; 3]) -> Mesh {
+    let mut vertices = Vec::with_capacity(((rings + 1) * (segments + 1)) as usize);
+    for ring in 0..=rings {
+        let u = ring as f32 / rings as f32;
+        let phi = u * std::f32::consts::TAU;
+        for segment in 0..=segments {
+            let v = segment as f32 / segments as f32;
+            let theta = v * std::f32::consts::TAU;
+            let normal = [theta.cos() * phi.cos(), theta.sin(), -theta.cos() * phi.sin()];
+            let distance = major_radius + minor_radius * theta.cos();
+            let position = [distance * phi.cos(), minor_radius * theta.sin(), -distance * phi.sin()];
+            vertices.push(Vertex::new(position, color, normal, [u, v]));
+        }
+    }
+
+    let row = segments + 1;
+    let mut indices = Vec::with_capacity((rings * segments * 6) as usize);
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * row + segment;
+            let b = a + row;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    Mesh { vertices, indices }
+}
+
+// the triangles of indices reordered from the farthest from camera_pos to the nearest by their
+// centroids, for blending a single translucent mesh that overlaps itself. it costs a sort of every
+// triangle on the cpu plus uploading the whole index buffer again each time the camera moves, so it
+// is for small meshes, a few thousand triangles at most. like translucent::sort_by_depth it orders
+// triangles rather than pixels: triangles that intersect, or overlap each other in a cycle, still
+// blend wrong where they overlap
+pub fn sort_triangles(positions: &[[f32; 3]], indices: &[u32], camera_pos: Point3<f32>) -> Vec<u32> {
+    let distances: Vec<f32> = indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            let centroid = triangle.iter().fold([0.0; 3], |sum, &index| {
+                let p = positions[index as usize];
+                [sum[0] + p[0] / 3.0, sum[1] + p[1] / 3.0, sum[2] + p[2] / 3.0]
+            });
+            camera_pos.distance2(Point3::from(centroid))
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..distances.len()).collect();
+    order.sort_by(|a, b| distances[*b].total_cmp(&distances[*a]));
+    order.into_iter().flat_map(|triangle| indices[triangle * 3..triangle * 3 + 3].iter().copied()).collect()
+}
+
+// smooth normals: each vertex gets the average of the faces around its position weighted by the
+// angle each face has at it, so vertices split only for their colors or uvs still shade as one.
+// weighting by angle rather than area keeps the result independent of how faces are triangulated:
+// a cube corner points along the diagonal however many triangles of each face meet there. vertices
+// on no triangle, or only on degenerate ones, get a zero normal
+pub fn compute_smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let (welded, unique) = weld_positions(positions);
+    let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); unique.len()];
+
+    for t in indices.chunks_exact(3) {
+        let corners = [t[0], t[1], t[2]].map(|i| welded[i as usize]);
+        let normal = (unique[corners[1]] - unique[corners[0]]).cross(unique[corners[2]] - unique[corners[0]]);
+        if normal.magnitude2() == 0.0 {
+            continue;
+        }
+        let normal = normal.normalize();
+
+        for i in 0..3 {
+            let corner = unique[corners[i]];
+            let angle = (unique[corners[(i + 1) % 3]] - corner).angle(unique[corners[(i + 2) % 3]] - corner);
+            normals[corners[i]] += normal * angle.0;
+        }
+    }
+
+    welded
+        .iter()
+        .map(|&id| if normals[id].magnitude2() > 0.0 { normals[id].normalize().into() } else { [0.0; 3] })
+        .collect()
+}
+
+// faceted shading: every triangle gets its own three vertices carrying its face normal, for the
+// morph target as well
+pub fn compute_flat_normals(mesh: &Mesh) -> Mesh {
+    let mut flat = unweld(mesh);
+
+    for triangle in flat.vertices.chunks_exact_mut(3) {
+        let face_normal = |corners: [[f32; 4]; 3]| {
+            let [a, b, c] = corners.map(|p| Vector3::new(p[0], p[1], p[2]));
+            let normal = (b - a).cross(c - a);
+            if normal.magnitude2() > 0.0 { normal.normalize().into() } else { [0.0; 3] }
+        };
+        let normal = face_normal([triangle[0].position, triangle[1].position, triangle[2].position]);
+        let morph_normal = face_normal([triangle[0].morph_position, triangle[1].morph_position, triangle[2].morph_position]);
+
+        for vertex in triangle {
+            vertex.normal = normal;
+            vertex.morph_normal = morph_normal;
+        }
+    }
+
+    flat
+}
+
+// compute_smooth_normals written into the vertices, keeping the old normal where there is none
+pub fn compute_normals(vertices: &mut [Vertex], indices: &[u32]) {
+    let positions: Vec<[f32; 3]> = vertices.iter().map(|v| [v.position[0], v.position[1], v.position[2]]).collect();
+
+    for (vertex, normal) in vertices.iter_mut().zip(compute_smooth_normals(&positions, indices)) {
+        if normal != [0.0; 3] {
+            vertex.normal = normal;
+        }
+    }
+}
+
+// the hedgehog debug view: a segment of the given length from each vertex along its normal, in the
+// vertices' own space. segments start dark and end in the normal mapped to rgb, so a normal pointing
+// inward shows as a bright tip inside the surface
+pub fn normal_lines(vertices: &[Vertex], length: f32) -> Vec<LineVertex> {
+    let mut lines = Vec::with_capacity(vertices.len() * 2);
+
+    for vertex in vertices {
+        let position = Vector3::new(vertex.position[0], vertex.position[1], vertex.position[2]);
+        let normal = Vector3::from(vertex.normal);
+        // zero normals (degenerate faces) get no segment rather than a NaN one
+        if normal.magnitude2() == 0.0 {
+            continue;
+        }
+        let normal = normal.normalize();
+
+        lines.push(LineVertex { position: position.into(), color: [0.1, 0.1, 0.1] });
+        lines.push(LineVertex {
+            position: (position + normal * length).into(),
+            color: (normal * 0.5 + Vector3::new(0.5, 0.5, 0.5)).into(),
+        });
+    }
+
+    lines
+}
+
+// index of each vertex's unique position, and those unique positions
+fn weld(vertices: &[Vertex]) -> (Vec<usize>, Vec<Vector3<f32>>) {
+    let positions: Vec<[f32; 3]> = vertices.iter().map(|v| [v.position[0], v.position[1], v.position[2]]).collect();
+    weld_positions(&positions)
+}
+
+fn weld_positions(positions: &[[f32; 3]]) -> (Vec<usize>, Vec<Vector3<f32>>) {
+    let mut ids: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut unique = Vec::new();
+
+    let welded = positions
+        .iter()
+        .map(|p| {
+            let key = p.map(f32::to_bits);
+            *ids.entry(key).or_insert_with(|| {
+                unique.push(Vector3::from(*p));
+                unique.len() - 1
+            })
+        })
+        .collect();
+
+    (welded, unique)
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn with_position(mut vertex: Vertex, position: Vector3<f32>) -> Vertex {
+    vertex.position = [position.x, position.y, position.z, 1.0];
+    vertex
+}
+
+fn lerp(a: &Vertex, b: &Vertex) -> Vertex {
+    let mix4 = |a: [f32; 4], b: [f32; 4]| [0, 1, 2, 3].map(|i| (a[i] + b[i]) * 0.5);
+    let mix3 = |a: [f32; 3], b: [f32; 3]| [0, 1, 2].map(|i| (a[i] + b[i]) * 0.5);
+    let morph_normal = Vector3::from(mix3(a.morph_normal, b.morph_normal));
+
+    Vertex {
+        position: mix4(a.position, b.position),
+        color: mix4(a.color, b.color),
+        morph_position: mix4(a.morph_position, b.morph_position),
+        normal: mix3(a.normal, b.normal),
+        morph_normal: if morph_normal.magnitude2() > 0.0 { morph_normal.normalize().into() } else { a.morph_normal },
+        uv: [(a.uv[0] + b.uv[0]) * 0.5, (a.uv[1] + b.uv[1]) * 0.5],
+        uv1: [(a.uv1[0] + b.uv1[0]) * 0.5, (a.uv1[1] + b.uv1[1]) * 0.5],
+        barycentric: mix3(a.barycentric, b.barycentric),
+    }
+}
+
+// collects triangles, sharing vertices whose attributes are bit for bit identical
+#[derive(Default)]
+struct MeshBuilder {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    lookup: HashMap<Vec<u8>, u32>,
+}
+
+impl MeshBuilder {
+    fn triangle(&mut self, corners: [Vertex; 3]) {
+        for vertex in corners {
+            let index = *self.lookup.entry(bytemuck::bytes_of(&vertex).to_vec()).or_insert_with(|| {
+                self.vertices.push(vertex);
+                self.vertices.len() as u32 - 1
+            });
+            self.indices.push(index);
+        }
+    }
+}
+
+// vertex and index buffers of a Mesh uploaded to the gpu
+pub struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+    pub vertex_count: u32,
+}
+
+impl GpuMesh {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, mesh: &Mesh) -> Self {
+        let vertex_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Vertex Buffer"),
+            contents: bytemuck::cast_slice(&mesh.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let index_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        GpuMesh {
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh.indices.len() as u32,
+            vertex_count: mesh.vertices.len() as u32,
+        }
+    }
+
+    pub fn vertex_bytes(&self) -> u64 {
+        self.vertex_buffer.size()
+    }
+
+    pub fn index_bytes(&self) -> u64 {
+        self.index_buffer.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vertex_data;
+
+    #[test]
+    fn smooth_cube_corners_point_along_the_diagonals() {
+        let positions: Vec<[f32; 3]> = vertex_data::cube_positions().iter().map(|p| p.map(f32::from)).collect();
+        let indices: Vec<u32> = (0..positions.len() as u32).collect();
+        let normals = compute_smooth_normals(&positions, &indices);
+
+        // every corner has three faces meeting at right angles, whichever of them is split in two
+        let diagonal = 1.0 / 3.0_f32.sqrt();
+        for (position, normal) in positions.iter().zip(&normals) {
+            for axis in 0..3 {
+                assert!((normal[axis] - position[axis].signum() * diagonal).abs() < 1e-5,
+                    "corner {:?} has normal {:?}", position, normal);
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{anyhow, Context};
+
+use crate::{mesh::{self, Mesh}, vertex_data::Vertex};
+
+// a mesh loaded from a file plus its base color texture, if it has one
+pub struct Model {
+    pub mesh: Mesh,
+    pub base_color_texture: Option<image::RgbaImage>,
+}
+
+// load the first primitive of the first mesh of a gltf/glb file. gltf is right-handed with +y up and
+// counter-clockwise front faces, the same conventions as this renderer, so positions, normals and
+// winding are used as they are. node transforms, animations and skins are not applied. a primitive
+// without normals gets smooth ones, or faceted ones with flat_normals
+pub fn load_gltf(path: &Path, flat_normals: bool) -> anyhow::Result<Model> {
+    let (document, buffers, images) = gltf::import(path)
+        .with_context(|| format!("failed to import {}", path.display()))?;
+
+    if document.animations().len() > 0 {
+        log::warn!("{}: animations are not supported and will be ignored", path.display());
+    }
+    if document.skins().len() > 0 {
+        log::warn!("{}: skins are not supported and will be ignored", path.display());
+    }
+    if document.meshes().len() > 1 {
+        log::warn!("{}: only the first of {} meshes is loaded", path.display(), document.meshes().len());
+    }
+
+    let mesh = document.meshes().next().ok_or_else(|| anyhow!("{} contains no meshes", path.display()))?;
+    let primitive = mesh.primitives().next().ok_or_else(|| anyhow!("{} has a mesh without primitives", path.display()))?;
+    if primitive.mode() != gltf::mesh::Mode::Triangles {
+        return Err(anyhow!("{}: only triangle primitives are supported, found {:?}", path.display(), primitive.mode()));
+    }
+
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions: Vec<[f32; 3]> = reader
+        .read_positions()
+        .ok_or_else(|| anyhow!("{}: primitive has no positions", path.display()))?
+        .collect();
+
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let (normals, generate_flat_normals): (Vec<[f32; 3]>, bool) = match reader.read_normals() {
+        Some(normals) => (normals.collect(), false),
+        None => {
+            log::info!("{}: primitive has no normals, computing {} ones", path.display(), if flat_normals { "flat" } else { "smooth" });
+            (mesh::compute_smooth_normals(&positions, &indices), flat_normals)
+        }
+    };
+
+    let material = primitive.material().pbr_metallic_roughness();
+    let base_color_texture = material.base_color_texture();
+    let tex_coord_set = base_color_texture.as_ref().map_or(0, |info| info.tex_coord());
+    let uvs: Vec<[f32; 2]> = match reader.read_tex_coords(tex_coord_set) {
+        Some(uvs) => uvs.into_f32().collect(),
+        None => vec![[0.0, 0.0]; positions.len()],
+    };
+
+    // TEXCOORD_1 is where exporters put lightmap coordinates
+    let uvs1: Vec<[f32; 2]> = match reader.read_tex_coords(1) {
+        Some(uvs) => uvs.into_f32().collect(),
+        None => uvs.clone(),
+    };
+
+    // vertex colors are multiplied by the material's base color factor, alpha is not used
+    let factor = material.base_color_factor();
+    let colors: Vec<[f32; 3]> = match reader.read_colors(0) {
+        Some(colors) => colors
+            .into_rgb_f32()
+            .map(|c| [c[0] * factor[0], c[1] * factor[1], c[2] * factor[2]])
+            .collect(),
+        None => vec![[factor[0], factor[1], factor[2]]; positions.len()],
+    };
+
+    let vertices = (0..positions.len())
+        .map(|i| Vertex { uv1: uvs1[i], ..Vertex::new(positions[i], colors[i], normals[i], uvs[i]) })
+        .collect();
+
+    let base_color_texture = base_color_texture.and_then(|info| {
+        let data = &images[info.texture().source().index()];
+        let image = rgba_image(data);
+        if image.is_none() {
+            log::warn!("{}: base color texture format {:?} is not supported", path.display(), data.format);
+        }
+        image
+    });
+
+    let mesh = Mesh {
+        vertices,
+        indices,
+    };
+
+    Ok(Model {
+        mesh: if generate_flat_normals { mesh::compute_flat_normals(&mesh) } else { mesh },
+        base_color_texture,
+    })
+}
+
+// the json vertex file format, hand written geometry without a modelling tool:
+// { "positions": [[x, y, z], ...], "colors": [[r, g, b], ...], "indices": [0, 1, 2, ...] }
+// colors may be left out for white, indices for a plain triangle list
+#[derive(serde::Deserialize)]
+struct VertexFile {
+    positions: Vec<[f32; 3]>,
+    colors: Option<Vec<[f32; 3]>>,
+    indices: Option<Vec<u32>>,
+}
+
+// load a mesh from a json vertex file, normals are computed from the triangles: smooth, or faceted
+// with flat_normals
+pub fn load_vertex_file(path: &Path, flat_normals: bool) -> anyhow::Result<Mesh> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let file: VertexFile = serde_json::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let colors = file.colors.unwrap_or_else(|| vec![[1.0, 1.0, 1.0]; file.positions.len()]);
+    if colors.len() != file.positions.len() {
+        return Err(anyhow!("{}: {} positions but {} colors, there must be one color per position",
+            path.display(), file.positions.len(), colors.len()));
+    }
+
+    let indices = file.indices.unwrap_or_else(|| (0..file.positions.len() as u32).collect());
+    if !indices.len().is_multiple_of(3) {
+        return Err(anyhow!("{}: {} indices is not a whole number of triangles", path.display(), indices.len()));
+    }
+    if let Some(&index) = indices.iter().find(|&&index| index as usize >= file.positions.len()) {
+        return Err(anyhow!("{}: index {} is out of range for {} positions", path.display(), index, file.positions.len()));
+    }
+
+    let mut vertices: Vec<Vertex> = file.positions
+        .iter()
+        .zip(&colors)
+        .map(|(&position, &color)| Vertex::new(position, color, [0.0, 0.0, 0.0], [0.0, 0.0]))
+        .collect();
+    mesh::compute_normals(&mut vertices, &indices);
+    for vertex in &mut vertices {
+        vertex.morph_normal = vertex.normal;
+    }
+
+    let mesh = Mesh {
+        vertices,
+        indices,
+    };
+
+    Ok(if flat_normals { mesh::compute_flat_normals(&mesh) } else { mesh })
+}
+
+// expand an 8 bit per channel gltf image to rgba
+fn rgba_image(data: &gltf::image::Data) -> Option<image::RgbaImage> {
+    use gltf::image::Format;
+
+    let pixels: Vec<u8> = match data.format {
+        Format::R8 => data.pixels.iter().flat_map(|&r| [r, r, r, 255]).collect(),
+        Format::R8G8 => data.pixels.chunks_exact(2).flat_map(|p| [p[0], p[1], 0, 255]).collect(),
+        Format::R8G8B8 => data.pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        Format::R8G8B8A8 => data.pixels.clone(),
+        _ => return None,
+    };
+
+    image::RgbaImage::from_raw(data.width, data.height, pixels)
+}
+
+// load a model by its extension: gltf/glb through load_gltf, obj through load_obj and json vertex
+// files through load_vertex_file. only gltf brings a texture along
+pub fn load(path: &Path, flat_normals: bool) -> anyhow::Result<Model> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    let mesh = match extension.as_deref() {
+        Some("gltf" | "glb") => return load_gltf(path, flat_normals),
+        Some("obj") => load_obj(path, flat_normals)?,
+        Some("json") => load_vertex_file(path, flat_normals)?,
+        _ => return Err(anyhow!("{}: unknown model format, expected .gltf, .glb, .obj or .json", path.display())),
+    };
+
+    Ok(Model {
+        mesh,
+        base_color_texture: None,
+    })
+}
+
+// load the faces of a wavefront obj file, polygons split into triangle fans. vertex colors given after
+// a position (the common "v x y z r g b" extension) are used, materials, groups and smoothing groups
+// are ignored. obj's texture v runs up, the opposite of wgpu's. a file without normals on every corner
+// gets computed ones: smooth, or faceted with flat_normals
+pub fn load_obj(path: &Path, flat_normals: bool) -> anyhow::Result<Mesh> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+    let mut positions: Vec<([f32; 3], [f32; 3])> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut vertices: Vec<Vertex> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    let mut lookup: HashMap<(usize, Option<usize>, Option<usize>), u32> = HashMap::new();
+    let mut missing_normals = false;
+
+    for (number, line) in text.lines().enumerate() {
+        let context = || format!("{}:{}", path.display(), number + 1);
+        let mut fields = line.split_whitespace();
+        let Some(keyword) = fields.next() else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.collect();
+        let floats = || -> anyhow::Result<Vec<f32>> {
+            fields.iter().map(|f| f.parse::<f32>().with_context(|| format!("{}: bad number {:?}", context(), f))).collect()
+        };
+
+        match keyword {
+            "v" => {
+                let v = floats()?;
+                if v.len() < 3 {
+                    return Err(anyhow!("{}: a position needs three coordinates", context()));
+                }
+                let color = if v.len() >= 6 { [v[3], v[4], v[5]] } else { [1.0, 1.0, 1.0] };
+                positions.push(([v[0], v[1], v[2]], color));
+            }
+            "vt" => {
+                let v = floats()?;
+                if v.is_empty() {
+                    return Err(anyhow!("{}: a texture coordinate needs at least u", context()));
+                }
+                uvs.push([v[0], 1.0 - v.get(1).copied().unwrap_or(0.0)]);
+            }
+            "vn" => {
+                let v = floats()?;
+                if v.len() < 3 {
+                    return Err(anyhow!("{}: a normal needs three coordinates", context()));
+                }
+                normals.push([v[0], v[1], v[2]]);
+            }
+            "f" => {
+                if fields.len() < 3 {
+                    return Err(anyhow!("{}: a face needs at least three corners", context()));
+                }
+                let mut corners = Vec::with_capacity(fields.len());
+                for field in &fields {
+                    let mut parts = field.split('/');
+                    let position = obj_index(parts.next(), positions.len(), &context)?
+                        .ok_or_else(|| anyhow!("{}: face corner {:?} has no position", context(), field))?;
+                    let uv = obj_index(parts.next(), uvs.len(), &context)?;
+                    let normal = obj_index(parts.next(), normals.len(), &context)?;
+                    missing_normals |= normal.is_none();
+
+                    let index = *lookup.entry((position, uv, normal)).or_insert_with(|| {
+                        let (position, color) = positions[position];
+                        let normal = normal.map_or([0.0, 0.0, 0.0], |n| normals[n]);
+                        let uv = uv.map_or([0.0, 0.0], |t| uvs[t]);
+                        vertices.push(Vertex::new(position, color, normal, uv));
+                        vertices.len() as u32 - 1
+                    });
+                    corners.push(index);
+                }
+                for i in 1..corners.len() - 1 {
+                    indices.extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if indices.is_empty() {
+        return Err(anyhow!("{} contains no faces", path.display()));
+    }
+
+    if missing_normals {
+        log::info!("{}: not every face has normals, computing {} ones", path.display(), if flat_normals { "flat" } else { "smooth" });
+        mesh::compute_normals(&mut vertices, &indices);
+        for vertex in &mut vertices {
+            vertex.morph_normal = vertex.normal;
+        }
+    }
+
+    let mesh = Mesh {
+        vertices,
+        indices,
+    };
+
+    Ok(if missing_normals && flat_normals { mesh::compute_flat_normals(&mesh) } else { mesh })
+}
+
+// one index of a face corner: 1 based, or counting back from the last element when negative. None
+// for a left out index, as in "1//3"
+fn obj_index(field: Option<&str>, count: usize, context: &impl Fn() -> String) -> anyhow::Result<Option<usize>> {
+    let Some(field) = field.filter(|f| !f.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = field.parse().with_context(|| format!("{}: bad index {:?}", context(), field))?;
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(anyhow!("{}: index {} is out of range for {} elements", context(), index, count));
+    }
+    Ok(Some(resolved as usize))
+}
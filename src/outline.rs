@@ -0,0 +1,228 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{mesh::GpuMesh, render_target::RenderTarget, resource_tracker::ResourceTracker, vertex_data::Vertex};
+
+// the stencil attachment the outline pass draws with. the scene's depth buffer stays stencil free:
+// Depth24PlusStencil8 can't have its depth copied out for read_pixel or keep reversed z's float
+// precision, and Depth32FloatStencil8 is an optional feature
+pub const STENCIL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Stencil8;
+
+// what the mask writes where the mesh covers the target, the outline draws everywhere else
+const STENCIL_REFERENCE: u32 = 1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Settings {
+    color: [f32; 4],
+    center: [f32; 3],
+    scale: f32,
+}
+
+unsafe impl Pod for Settings {}
+unsafe impl Zeroable for Settings {}
+
+// selection outline through the stencil buffer: the mesh is drawn once writing only stencil, then
+// again scaled up about its center in a solid color where the stencil wasn't set, leaving a rim
+// around its silhouette. both draws skip the depth test, so the outline shows through whatever is in
+// front of the object. the pass draws into the resolved color of the target after the scene pass,
+// single sampled since a multisampled target discards its samples once resolved
+pub struct Outline {
+    pub mask_pipeline: wgpu::RenderPipeline,
+    pub outline_pipeline: wgpu::RenderPipeline,
+    pub settings_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    // the one instance outlined, written with write_instance
+    pub instance_buffer: wgpu::Buffer,
+    pub stencil_texture: wgpu::Texture,
+    pub stencil_view: wgpu::TextureView,
+}
+
+impl Outline {
+    // scene_layout is the scene's uniform bind group layout, the outline uses its matrices and morph
+    // weight. instance_layout is the scene's instance buffer layout
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, width: u32, height: u32,
+        scene_layout: &wgpu::BindGroupLayout, instance_layout: wgpu::VertexBufferLayout) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Outline Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("outline.wgsl").into()),
+        });
+
+        let settings_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Outline Settings Buffer"),
+            size: std::mem::size_of::<Settings>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let instance_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Outline Instance Buffer"),
+            size: std::mem::size_of::<[[f32; 4]; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Outline Bind Group Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: settings_buffer.as_entire_binding(),
+            }],
+            label: Some("Outline Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Outline Pipeline Layout"),
+            bind_group_layouts: &[scene_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, entry_point, write_mask, stencil_face: wgpu::StencilFaceState| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point,
+                    buffers: &[Vertex::desc(), instance_layout.clone()],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: STENCIL_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState {
+                        front: stencil_face,
+                        back: stencil_face,
+                        read_mask: 0xff,
+                        write_mask: 0xff,
+                    },
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        let mask_pipeline = create_pipeline("Outline Mask Pipeline", "vs_mask", wgpu::ColorWrites::empty(), wgpu::StencilFaceState {
+            compare: wgpu::CompareFunction::Always,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op: wgpu::StencilOperation::Replace,
+        });
+        let outline_pipeline = create_pipeline("Outline Pipeline", "vs_outline", wgpu::ColorWrites::ALL, wgpu::StencilFaceState {
+            compare: wgpu::CompareFunction::NotEqual,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op: wgpu::StencilOperation::Keep,
+        });
+
+        let (stencil_texture, stencil_view) = create_stencil(device, tracker, width, height);
+
+        Outline {
+            mask_pipeline,
+            outline_pipeline,
+            settings_buffer,
+            bind_group,
+            instance_buffer,
+            stencil_texture,
+            stencil_view,
+        }
+    }
+
+    // the stencil attachment has to match the target's size, call whenever the target is recreated
+    pub fn resize(&mut self, device: &wgpu::Device, tracker: &ResourceTracker, width: u32, height: u32) {
+        (self.stencil_texture, self.stencil_view) = create_stencil(device, tracker, width, height);
+    }
+
+    // center is the point of the mesh it grows from, scale how much larger the outlined copy is
+    pub fn write_settings(&self, queue: &wgpu::Queue, color: [f32; 4], center: [f32; 3], scale: f32) {
+        queue.write_buffer(&self.settings_buffer, 0, bytemuck::cast_slice(&[Settings { color, center, scale }]));
+    }
+
+    // the instance matrix of the outlined object, as in the scene's instance buffer
+    pub fn write_instance(&self, queue: &wgpu::Queue, instance: [[f32; 4]; 4]) {
+        queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&[instance]));
+    }
+
+    // viewport limits the outline to part of the target, for the split screen
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, target: &RenderTarget, scene_bind_group: &wgpu::BindGroup,
+        mesh: &GpuMesh, viewport: Option<[f32; 4]>) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Outline Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.stencil_view,
+                depth_ops: None,
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+            }),
+            ..Default::default()
+        });
+
+        if let Some([x, y, width, height]) = viewport {
+            pass.set_viewport(x, y, width, height, 0.0, 1.0);
+        }
+        pass.set_stencil_reference(STENCIL_REFERENCE);
+        pass.set_bind_group(0, scene_bind_group, &[]);
+        pass.set_bind_group(1, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+
+        pass.set_pipeline(&self.mask_pipeline);
+        pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        pass.set_pipeline(&self.outline_pipeline);
+        pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+    }
+}
+
+fn create_stencil(device: &wgpu::Device, tracker: &ResourceTracker, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+        label: Some("Outline Stencil"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: STENCIL_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
@@ -0,0 +1,52 @@
+// the scene's Uniforms from shader.wgsl, shared through its bind group
+struct Uniforms {
+    viewProjectionMatrix : mat4x4<f32>,
+    modelMatrix : mat4x4<f32>,
+    morphWeight : f32,
+    edgeWidth : f32,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+struct Settings {
+    color : vec4<f32>,
+    // the mesh is scaled about this point, its bounding box center
+    center : vec3<f32>,
+    // 1 for the stencil mask, a little over 1 for the outline
+    scale : f32,
+};
+@binding(0) @group(1) var<uniform> settings : Settings;
+
+struct Vertex {
+    @location(0) position : vec4<f32>,
+    @location(2) morphPosition : vec4<f32>,
+};
+
+struct Instance {
+    @location(8) model0 : vec4<f32>,
+    @location(9) model1 : vec4<f32>,
+    @location(10) model2 : vec4<f32>,
+    @location(11) model3 : vec4<f32>,
+};
+
+// the mesh's position as shader.wgsl places it, scaled by scale about center
+fn position(vertex : Vertex, instance : Instance, scale : f32) -> vec4<f32> {
+    let instanceMatrix = mat4x4<f32>(instance.model0, instance.model1, instance.model2, instance.model3);
+    let morphed = mix(vertex.position, vertex.morphPosition, uniforms.morphWeight).xyz;
+    let scaled = settings.center + (morphed - settings.center) * scale;
+    return uniforms.viewProjectionMatrix * uniforms.modelMatrix * instanceMatrix * vec4<f32>(scaled, 1.0);
+}
+
+@vertex
+fn vs_mask(vertex : Vertex, instance : Instance) -> @builtin(position) vec4<f32> {
+    return position(vertex, instance, 1.0);
+}
+
+@vertex
+fn vs_outline(vertex : Vertex, instance : Instance) -> @builtin(position) vec4<f32> {
+    return position(vertex, instance, settings.scale);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return settings.color;
+}
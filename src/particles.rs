@@ -0,0 +1,346 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+use rand::Rng;
+
+use crate::{error::Error, render_target, resource_tracker::ResourceTracker, transforms::DepthMode, util::rng::{self, Rng as SeededRng}};
+
+// size of the compute workgroups in particles.wgsl
+const WORKGROUP_SIZE: u32 = 64;
+// half the width of each particle's quad, in world units
+const PARTICLE_SIZE: f32 = 0.04;
+// initial particles are scattered over a box this far around the emitter
+const SPAWN_EXTENT: f32 = 3.0;
+// LIFETIME in particles.wgsl
+const MAX_LIFE: f32 = 4.0;
+
+// matches Particle in particles.wgsl. it doubles as the instance vertex layout of the render pass
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Particle {
+    pub position: [f32; 3],
+    pub life: f32,
+    pub velocity: [f32; 3],
+    pub _padding: f32,
+}
+
+unsafe impl Pod for Particle {}
+unsafe impl Zeroable for Particle {}
+
+impl Particle {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Particle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct SimulationUniforms {
+    gravity: [f32; 3],
+    dt: f32,
+    time: f32,
+    seed: u32,
+    // pads the struct to the 16 byte multiple wgsl expects for uniform structs
+    _padding: [f32; 2],
+}
+
+unsafe impl Pod for SimulationUniforms {}
+unsafe impl Zeroable for SimulationUniforms {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct CameraUniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    right: [f32; 3],
+    size: f32,
+    up: [f32; 3],
+    _padding: f32,
+}
+
+unsafe impl Pod for CameraUniforms {}
+unsafe impl Zeroable for CameraUniforms {}
+
+// particles integrated on the gpu: a compute pass moves them in their storage buffer each frame, then
+// the same buffer feeds the render pass as instance data for one camera facing quad per particle,
+// so the positions never come back to the cpu
+pub struct Particles {
+    pub compute_pipeline: wgpu::ComputePipeline,
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub particle_buffer: wgpu::Buffer,
+    pub simulation_buffer: wgpu::Buffer,
+    pub camera_buffer: wgpu::Buffer,
+    pub compute_bind_group: wgpu::BindGroup,
+    pub render_bind_group: wgpu::BindGroup,
+    pub count: u32,
+    // mixed into the respawn randomness, see util::rng::seed_u32
+    pub seed: u32,
+}
+
+impl Particles {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode, count: u32, seed: u64) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("particles.wgsl").into()),
+        });
+
+        let particle_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Buffer"),
+            contents: bytemuck::cast_slice(&initial_particles(count, seed)),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let simulation_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Particle Simulation Buffer"),
+            size: std::mem::size_of::<SimulationUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let camera = CameraUniforms {
+            view_projection_matrix: Matrix4::identity().into(),
+            right: [1.0, 0.0, 0.0],
+            size: PARTICLE_SIZE,
+            up: [0.0, 1.0, 0.0],
+            _padding: 0.0,
+        };
+        let camera_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Camera Buffer"),
+            contents: bytemuck::cast_slice(&[camera]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let compute_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Particle Compute Bind Group Layout"),
+        });
+
+        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: simulation_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("Particle Compute Bind Group"),
+        });
+
+        let compute_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Compute Pipeline Layout"),
+            bind_group_layouts: &[&compute_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Particle Compute Pipeline"),
+            layout: Some(&compute_pipeline_layout),
+            module: &shader,
+            entry_point: "cs_main",
+        });
+
+        let render_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Particle Render Bind Group Layout"),
+        });
+
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &render_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+            label: Some("Particle Render Bind Group"),
+        });
+
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Render Pipeline Layout"),
+            bind_group_layouts: &[&render_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Particle::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    // additive, so overlapping particles glow and draw order doesn't matter
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Particles {
+            compute_pipeline,
+            render_pipeline,
+            particle_buffer,
+            simulation_buffer,
+            camera_buffer,
+            compute_bind_group,
+            render_bind_group,
+            count,
+            seed: rng::seed_u32(seed),
+        }
+    }
+
+    // advances every particle by dt seconds. recorded before the scene pass that draws them, wgpu
+    // orders the storage writes before the vertex reads
+    pub fn simulate(&self, encoder: &mut wgpu::CommandEncoder, queue: &wgpu::Queue, gravity: [f32; 3], dt: f32, time: f32) {
+        self.simulate_steps(encoder, queue, gravity, dt, time, 1);
+    }
+
+    // simulate, steps times over in one pass, for a fixed timestep catching up. the steps share one
+    // uniform write, so particles respawning in different steps are seeded from the same time
+    pub fn simulate_steps(&self, encoder: &mut wgpu::CommandEncoder, queue: &wgpu::Queue, gravity: [f32; 3], dt: f32, time: f32,
+        steps: u32) {
+        let simulation = SimulationUniforms {
+            gravity,
+            dt,
+            time,
+            seed: self.seed,
+            _padding: [0.0; 2],
+        };
+        queue.write_buffer(&self.simulation_buffer, 0, bytemuck::cast_slice(&[simulation]));
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Particle Compute Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.compute_pipeline);
+        pass.set_bind_group(0, &self.compute_bind_group, &[]);
+        for _ in 0..steps {
+            pass.dispatch_workgroups(self.count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+    }
+
+    // the quads face the camera along the view matrix's right and up axes
+    pub fn write_camera(&self, queue: &wgpu::Queue, view: Matrix4<f32>, projection: Matrix4<f32>) {
+        let camera = CameraUniforms {
+            view_projection_matrix: (projection * view).into(),
+            right: [view.x.x, view.y.x, view.z.x],
+            size: PARTICLE_SIZE,
+            up: [view.x.y, view.y.y, view.z.y],
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera]));
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>) {
+        pass.set_pipeline(&self.render_pipeline);
+        pass.set_bind_group(0, &self.render_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.particle_buffer.slice(..));
+        pass.draw(0..6, 0..self.count);
+    }
+
+    // copies the particles back to the cpu, waiting for the gpu. for inspecting the simulation, far
+    // too slow to call every frame
+    pub fn read_back(&self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> Result<Vec<Particle>, Error> {
+        let size = self.particle_buffer.size();
+        let readback = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Particle Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Particle Readback Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.particle_buffer, 0, &readback, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let bytes = render_target::read_buffer(device, &readback)?;
+        Ok(bytemuck::pod_collect_to_vec(&bytes))
+    }
+}
+
+// particles scattered around the emitter with random velocities and ages, so they don't all respawn
+// together on the first frames. the same seed scatters them the same way
+fn initial_particles(count: u32, seed: u64) -> Vec<Particle> {
+    let mut rng = SeededRng::new(seed);
+
+    (0..count)
+        .map(|_| Particle {
+            position: [
+                rng.gen_range(-SPAWN_EXTENT..SPAWN_EXTENT),
+                rng.gen_range(-1.0..SPAWN_EXTENT),
+                rng.gen_range(-SPAWN_EXTENT..SPAWN_EXTENT),
+            ],
+            life: rng.gen_range(0.0..MAX_LIFE),
+            velocity: [rng.gen_range(-1.0..1.0), rng.gen_range(0.0..2.0), rng.gen_range(-1.0..1.0)],
+            _padding: 0.0,
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+// matches Particle in particles.rs, 32 bytes
+struct Particle {
+    position : vec3<f32>,
+    // seconds left before respawning at the emitter
+    life : f32,
+    velocity : vec3<f32>,
+};
+
+struct Simulation {
+    gravity : vec3<f32>,
+    dt : f32,
+    // seeds the respawn randomness so particles don't repeat each cycle
+    time : f32,
+    // the --seed, so a different seed also respawns differently
+    seed : u32,
+};
+
+@binding(0) @group(0) var<storage, read_write> particles : array<Particle>;
+@binding(1) @group(0) var<uniform> simulation : Simulation;
+
+const EMITTER : vec3<f32> = vec3<f32>(0.0, 1.0, 0.0);
+const LIFETIME : f32 = 4.0;
+const FLOOR : f32 = -1.0;
+const BOUNCE : f32 = 0.4;
+
+// pcg hash, a cheap well mixed u32 -> u32
+fn hash(value : u32) -> u32 {
+    let state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(seed : ptr<function, u32>) -> f32 {
+    *seed = hash(*seed);
+    return f32(*seed) / 4294967295.0;
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id : vec3<u32>) {
+    let index = id.x;
+    if (index >= arrayLength(&particles)) {
+        return;
+    }
+
+    var particle = particles[index];
+    particle.life -= simulation.dt;
+
+    if (particle.life <= 0.0) {
+        // back to the emitter with a new upward velocity inside a cone
+        var seed = hash(index ^ simulation.seed) ^ bitcast<u32>(simulation.time);
+        let angle = random(&seed) * 6.2831853;
+        let spread = random(&seed) * 1.5;
+        particle.position = EMITTER;
+        particle.velocity = vec3<f32>(cos(angle) * spread, 4.0 + random(&seed) * 2.0, sin(angle) * spread);
+        particle.life = LIFETIME * (0.5 + 0.5 * random(&seed));
+    } else {
+        particle.velocity += simulation.gravity * simulation.dt;
+        particle.position += particle.velocity * simulation.dt;
+
+        // lose some energy on the floor the reference grid marks
+        if (particle.position.y < FLOOR && particle.velocity.y < 0.0) {
+            particle.position.y = FLOOR;
+            particle.velocity = vec3<f32>(particle.velocity.x, -particle.velocity.y, particle.velocity.z) * BOUNCE;
+        }
+    }
+
+    particles[index] = particle;
+}
+
+struct Camera {
+    viewProjectionMatrix : mat4x4<f32>,
+    // world space directions of the screen's x and y, to face the quads toward the camera
+    right : vec3<f32>,
+    size : f32,
+    up : vec3<f32>,
+};
+@binding(0) @group(0) var<uniform> camera : Camera;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) offset : vec2<f32>,
+    @location(1) life : f32,
+};
+
+// one quad per instance, the particle buffer is bound as the instance vertex buffer
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32, @location(0) position : vec3<f32>, @location(1) life : f32) -> Output {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[index];
+    let world = position + (camera.right * corner.x + camera.up * corner.y) * camera.size;
+
+    var output: Output;
+    output.Position = camera.viewProjectionMatrix * vec4<f32>(world, 1.0);
+    output.offset = corner;
+    output.life = life;
+    return output;
+}
+
+// a soft round dot fading from white to orange as the particle ages, blended additively
+@fragment
+fn fs_main(in : Output) -> @location(0) vec4<f32> {
+    let falloff = max(1.0 - dot(in.offset, in.offset), 0.0);
+    let age = clamp(in.life / LIFETIME, 0.0, 1.0);
+    let color = mix(vec3<f32>(1.0, 0.35, 0.05), vec3<f32>(1.0, 0.95, 0.8), age);
+    return vec4<f32>(color * falloff * falloff * age, 1.0);
+}
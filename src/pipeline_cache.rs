@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+// everything that can differ between the scene pipelines the runtime toggles switch between
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    pub topology: wgpu::PrimitiveTopology,
+    pub polygon_mode: wgpu::PolygonMode,
+    pub front_face: wgpu::FrontFace,
+    pub cull_mode: Option<wgpu::Face>,
+    pub blend: wgpu::BlendState,
+    // index of the shader variant
+    pub shader: usize,
+    // pull the depth toward the camera by the overlay depth bias, for geometry drawn over coplanar
+    // surfaces. DepthBiasState has floats and can't be hashed, so the key only records whether
+    pub depth_bias: bool,
+    // the shader's colors and uvs are interpolated linearly in screen space instead of perspective
+    // correctly, see State::linear_shaders
+    pub linear_interpolation: bool,
+    // msaa sample count of the target the pipeline draws into, so the variants of each anti-aliasing
+    // mode stay cached side by side
+    pub sample_count: u32,
+}
+
+impl PipelineKey {
+    pub fn primitive(&self) -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            topology: self.topology,
+            polygon_mode: self.polygon_mode,
+            front_face: self.front_face,
+            cull_mode: self.cull_mode,
+            ..Default::default()
+        }
+    }
+}
+
+// render pipelines by the state they were built for, so toggling back to an earlier combination reuses
+// the pipeline instead of compiling it again. anything not in the key (formats, depth mode, layout)
+// must stay fixed for the cache's lifetime, clear it when those change
+#[derive(Default)]
+pub struct PipelineCache {
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+}
+
+impl PipelineCache {
+    pub fn get_or_insert_with(&mut self, key: PipelineKey, create: impl FnOnce(&PipelineKey) -> wgpu::RenderPipeline) -> &wgpu::RenderPipeline {
+        self.pipelines.entry(key).or_insert_with(|| {
+            log::debug!("creating pipeline for {:?}", key);
+            create(&key)
+        })
+    }
+
+    pub fn get(&self, key: &PipelineKey) -> Option<&wgpu::RenderPipeline> {
+        self.pipelines.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pipelines.clear();
+    }
+}
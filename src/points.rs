@@ -0,0 +1,216 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{render_target, resource_tracker::ResourceTracker, transforms::DepthMode, vertex_data::Vertex};
+
+// diameter in pixels of points without a size of their own
+pub const DEFAULT_POINT_SIZE: f32 = 4.0;
+
+// matches Point in points.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Point {
+    pub position: [f32; 3],
+    // diameter in pixels, 0 for DEFAULT_POINT_SIZE
+    pub size: f32,
+    pub color: [f32; 3],
+    pub _padding: f32,
+}
+
+unsafe impl Pod for Point {}
+unsafe impl Zeroable for Point {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Settings {
+    viewport: [f32; 2],
+    default_size: f32,
+    _padding: f32,
+}
+
+unsafe impl Pod for Settings {}
+unsafe impl Zeroable for Settings {}
+
+// the point list mode: every vertex drawn as a round screen space quad rather than the single pixel a
+// PointList pipeline gives. the points are read from a storage buffer by vertex index, which leaves
+// the instance rate free for the scene's instance buffer
+pub struct PointCloud {
+    pub pipeline: wgpu::RenderPipeline,
+    pub settings_buffer: wgpu::Buffer,
+    pub point_buffer: wgpu::Buffer,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    pub count: u32,
+}
+
+impl PointCloud {
+    // scene_layout is the scene's uniform bind group layout, the points use its view projection and
+    // model matrices. instance_layout is the scene's instance buffer layout
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode, scene_layout: &wgpu::BindGroupLayout, instance_layout: wgpu::VertexBufferLayout) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Point Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("points.wgsl").into()),
+        });
+
+        let settings_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Point Settings Buffer"),
+            contents: bytemuck::cast_slice(&[Settings { viewport: [1.0, 1.0], default_size: DEFAULT_POINT_SIZE, _padding: 0.0 }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("Point Bind Group Layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Point Pipeline Layout"),
+            bind_group_layouts: &[scene_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Point Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[instance_layout],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        let (point_buffer, bind_group) = Self::upload(device, tracker, &bind_group_layout, &settings_buffer, &[]);
+
+        PointCloud {
+            pipeline,
+            settings_buffer,
+            point_buffer,
+            bind_group_layout,
+            bind_group,
+            count: 0,
+        }
+    }
+
+    // one point per vertex, at its unmorphed position. sizes gives each point its own diameter in
+    // pixels; without it, or for a size of 0, points are DEFAULT_POINT_SIZE
+    pub fn set_points(&mut self, device: &wgpu::Device, tracker: &ResourceTracker, vertices: &[Vertex], sizes: Option<&[f32]>) {
+        let points: Vec<Point> = vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Point {
+                position: [v.position[0], v.position[1], v.position[2]],
+                size: sizes.and_then(|sizes| sizes.get(i)).copied().unwrap_or(0.0),
+                color: [v.color[0], v.color[1], v.color[2]],
+                _padding: 0.0,
+            })
+            .collect();
+
+        (self.point_buffer, self.bind_group) = Self::upload(device, tracker, &self.bind_group_layout, &self.settings_buffer, &points);
+        self.count = points.len() as u32;
+    }
+
+    // viewport is the size in pixels point sizes are measured in, the window's rather than the target's
+    // so render scale and supersampling don't change them
+    pub fn write_viewport(&self, queue: &wgpu::Queue, viewport: [f32; 2]) {
+        let settings = Settings { viewport, default_size: DEFAULT_POINT_SIZE, _padding: 0.0 };
+        queue.write_buffer(&self.settings_buffer, 0, bytemuck::cast_slice(&[settings]));
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, scene_bind_group: &'pass wgpu::BindGroup,
+        instance_buffer: &'pass wgpu::Buffer, instance_count: u32) {
+        if self.count == 0 {
+            return;
+        }
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, scene_bind_group, &[]);
+        pass.set_bind_group(1, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        pass.draw(0..self.count * 6, 0..instance_count);
+    }
+
+    // storage buffers can't be empty, an empty cloud keeps one unused point
+    fn upload(device: &wgpu::Device, tracker: &ResourceTracker, layout: &wgpu::BindGroupLayout, settings_buffer: &wgpu::Buffer,
+        points: &[Point]) -> (wgpu::Buffer, wgpu::BindGroup) {
+        let placeholder = [Point::zeroed()];
+        let contents = if points.is_empty() { &placeholder[..] } else { points };
+        let point_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Point Buffer"),
+            contents: bytemuck::cast_slice(contents),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: settings_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: point_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("Point Bind Group"),
+        });
+
+        (point_buffer, bind_group)
+    }
+}
+
+// a size for every vertex growing linearly from min_size at the origin to max_size at radius, for
+// showing the per point sizes
+pub fn sizes_by_distance(vertices: &[Vertex], radius: f32, min_size: f32, max_size: f32) -> Vec<f32> {
+    vertices
+        .iter()
+        .map(|v| {
+            let distance = (v.position[0] * v.position[0] + v.position[1] * v.position[1] + v.position[2] * v.position[2]).sqrt();
+            let t = if radius > 0.0 { (distance / radius).min(1.0) } else { 0.0 };
+            min_size + (max_size - min_size) * t
+        })
+        .collect()
+}
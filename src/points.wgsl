@@ -0,0 +1,68 @@
+// the scene's Uniforms from shader.wgsl, shared through its bind group
+struct Uniforms {
+    viewProjectionMatrix : mat4x4<f32>,
+    modelMatrix : mat4x4<f32>,
+    morphWeight : f32,
+    edgeWidth : f32,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+struct Settings {
+    // size of the window in pixels, to turn pixel sizes into clip space offsets
+    viewport : vec2<f32>,
+    // the size of points whose own size is 0
+    defaultSize : f32,
+};
+@binding(0) @group(1) var<uniform> settings : Settings;
+
+struct Point {
+    position : vec3<f32>,
+    // diameter in pixels, 0 for the default size
+    size : f32,
+    color : vec3<f32>,
+};
+@binding(1) @group(1) var<storage, read> points : array<Point>;
+
+struct Instance {
+    @location(8) model0 : vec4<f32>,
+    @location(9) model1 : vec4<f32>,
+    @location(10) model2 : vec4<f32>,
+    @location(11) model3 : vec4<f32>,
+};
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vColor : vec4<f32>,
+    @location(1) vCorner : vec2<f32>,
+};
+
+// six vertices per point, the instances are the scene's instances. the quad is offset in clip space
+// scaled by w, so it keeps the same size in pixels at any distance
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32, instance : Instance) -> Output {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let point = points[index / 6u];
+    let corner = corners[index % 6u];
+    let size = select(settings.defaultSize, point.size, point.size > 0.0);
+
+    let instanceMatrix = mat4x4<f32>(instance.model0, instance.model1, instance.model2, instance.model3);
+    let clip = uniforms.viewProjectionMatrix * uniforms.modelMatrix * instanceMatrix * vec4<f32>(point.position, 1.0);
+
+    var output: Output;
+    output.Position = clip + vec4<f32>(corner * size / settings.viewport * clip.w, 0.0, 0.0);
+    output.vColor = vec4<f32>(point.color, 1.0);
+    output.vCorner = corner;
+    return output;
+}
+
+// round points: the quad's corners are cut away
+@fragment
+fn fs_main(in : Output) -> @location(0) vec4<f32> {
+    if (dot(in.vCorner, in.vCorner) > 1.0) {
+        discard;
+    }
+    return in.vColor;
+}
@@ -0,0 +1,132 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::resource_tracker::ResourceTracker;
+
+// inputs of procedural.wgsl, at group 0 binding 0 and visible to the fragment stage. byte offsets:
+// time at 0, resolution at 8 (wgsl aligns vec2 to 8, hence the padding), mouse at 16
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ProceduralUniforms {
+    pub time: f32,
+    pub _padding: f32,
+    pub resolution: [f32; 2],
+    pub mouse: [f32; 2],
+    // rounds the buffer up to a 16 byte multiple
+    pub _padding2: [f32; 2],
+}
+
+unsafe impl Pod for ProceduralUniforms {}
+unsafe impl Zeroable for ProceduralUniforms {}
+
+// a minimal shadertoy: procedural.wgsl's fragment stage run over the whole target with a
+// fullscreen triangle, like the blit but with time, resolution and mouse inputs instead of a texture
+pub struct Procedural {
+    pub pipeline: wgpu::RenderPipeline,
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Procedural {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Procedural Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("procedural.wgsl").into()),
+        });
+
+        let uniform_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Procedural Uniform Buffer"),
+            size: std::mem::size_of::<ProceduralUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Procedural Bind Group Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Procedural Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Procedural Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Procedural Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        Procedural {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    pub fn write(&self, queue: &wgpu::Queue, time: f32, resolution: [f32; 2], mouse: [f32; 2]) {
+        let uniforms = ProceduralUniforms {
+            time,
+            _padding: 0.0,
+            resolution,
+            mouse,
+            _padding2: [0.0; 2],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Procedural Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..Default::default()
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
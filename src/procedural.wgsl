@@ -0,0 +1,35 @@
+// shadertoy style inputs, see ProceduralUniforms in procedural.rs for the layout
+struct Inputs {
+    // seconds on the animation clock
+    time : f32,
+    // surface size and cursor position in pixels, origin at the top left
+    resolution : vec2<f32>,
+    mouse : vec2<f32>,
+};
+@binding(0) @group(0) var<uniform> inputs : Inputs;
+
+// a single triangle covering the whole screen, the same one the blit uses
+@vertex
+fn vs_main(@builtin(vertex_index) index : u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+}
+
+// an animated plasma: a few sine waves over the pixel position summed and mapped to a palette,
+// with one of them centered on the cursor
+@fragment
+fn fs_main(@builtin(position) fragCoord : vec4<f32>) -> @location(0) vec4<f32> {
+    let scale = 8.0 / inputs.resolution.y;
+    let p = fragCoord.xy * scale;
+    let m = inputs.mouse * scale;
+    let t = inputs.time;
+
+    var v = sin(p.x + t);
+    v += sin((p.y + t) * 0.5);
+    v += sin((p.x + p.y + t) * 0.5);
+    v += sin(length(p - m) - t * 2.0);
+    v *= 0.5;
+
+    let color = vec3<f32>(sin(v * 3.14159), sin(v * 3.14159 + 2.094), sin(v * 3.14159 + 4.189)) * 0.5 + 0.5;
+    return vec4<f32>(color, 1.0);
+}
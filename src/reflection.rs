@@ -0,0 +1,338 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, Point3};
+
+use crate::{
+    mesh::{self, GpuMesh},
+    render_target,
+    resource_tracker::ResourceTracker,
+    transforms::{self, DepthMode},
+    vertex_data::Vertex,
+};
+
+// width and height of each cube face in texels
+pub const PROBE_SIZE: u32 = 256;
+
+// how many frames a probe update lasts, stepped through with a key. 1 renders the six faces every frame
+pub const UPDATE_INTERVALS: [u32; 4] = [1, 2, 4, 8];
+
+const PROBE_NEAR: f32 = 0.05;
+
+const SPHERE_RINGS: u32 = 32;
+const SPHERE_SEGMENTS: u32 = 64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct SphereUniforms {
+    view_projection_matrix: [[f32; 4]; 4],
+    camera_position: [f32; 3],
+    radius: f32,
+    center: [f32; 3],
+    _padding: f32,
+}
+
+unsafe impl Pod for SphereUniforms {}
+unsafe impl Zeroable for SphereUniforms {}
+
+// one face of the cube: the layer it renders into and its copy of the scene uniforms
+pub struct ProbeFace {
+    pub view: wgpu::TextureView,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_bind_group: wgpu::BindGroup,
+}
+
+// a dynamic reflection: the scene rendered into a cube map from position, six passes with a 90 degree
+// camera each, then looked up by a mirror sphere at the same position with the reflected view
+// direction. the faces are drawn by the caller between begin_face_pass and the end of the pass, with
+// pipelines built for the probe's format, a single sample and render_target::DEPTH_FORMAT
+pub struct ReflectionProbe {
+    pub position: Point3<f32>,
+    pub radius: f32,
+    pub cube_texture: wgpu::Texture,
+    pub faces: Vec<ProbeFace>,
+    pub depth_texture: wgpu::Texture,
+    pub depth_view: wgpu::TextureView,
+    pub sphere_pipeline: wgpu::RenderPipeline,
+    pub sphere_mesh: GpuMesh,
+    pub sphere_buffer: wgpu::Buffer,
+    pub sphere_bind_group: wgpu::BindGroup,
+    // index into UPDATE_INTERVALS, and the frames left until the next update
+    pub interval_index: usize,
+    pub frames_until_update: u32,
+}
+
+impl ReflectionProbe {
+    // format, sample_count and depth_mode are the scene target's, for the sphere drawn into it. scene_layout
+    // is the scene's uniform bind group layout with shared_entries the bindings after the uniforms, as
+    // for uniform_ring::UniformRing
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode, scene_layout: &wgpu::BindGroupLayout, shared_entries: &[wgpu::BindGroupEntry],
+        uniform_size: wgpu::BufferAddress, position: Point3<f32>, radius: f32) -> Self {
+        let cube_texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some("Reflection Probe Cube"),
+            size: wgpu::Extent3d {
+                width: PROBE_SIZE,
+                height: PROBE_SIZE,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let cube_view = cube_texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Reflection Probe Cube View"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+
+        let faces = (0..6)
+            .map(|layer| {
+                let view = cube_texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("Reflection Probe Face View"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                });
+                let uniform_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+                    label: Some("Reflection Probe Uniform Buffer"),
+                    size: uniform_size,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+
+                let mut entries = vec![wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }];
+                entries.extend(shared_entries.iter().cloned());
+                let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: scene_layout,
+                    entries: &entries,
+                    label: Some("Reflection Probe Uniform Bind Group"),
+                });
+
+                ProbeFace { view, uniform_buffer, uniform_bind_group }
+            })
+            .collect();
+
+        let depth_texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some("Reflection Probe Depth"),
+            size: wgpu::Extent3d {
+                width: PROBE_SIZE,
+                height: PROBE_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: render_target::DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Reflection Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("reflection.wgsl").into()),
+        });
+
+        let sphere_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Reflection Sphere Uniform Buffer"),
+            size: std::mem::size_of::<SphereUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Reflection Probe Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("Reflection Sphere Bind Group Layout"),
+        });
+
+        let sphere_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: sphere_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&cube_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("Reflection Sphere Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Reflection Sphere Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sphere_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Reflection Sphere Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        let sphere_mesh = GpuMesh::new(device, tracker, &mesh::uv_sphere(1.0, SPHERE_RINGS, SPHERE_SEGMENTS, [1.0, 1.0, 1.0]));
+
+        ReflectionProbe {
+            position,
+            radius,
+            cube_texture,
+            faces,
+            depth_texture,
+            depth_view,
+            sphere_pipeline,
+            sphere_mesh,
+            sphere_buffer,
+            sphere_bind_group,
+            interval_index: 0,
+            frames_until_update: 0,
+        }
+    }
+
+    pub fn update_interval(&self) -> u32 {
+        UPDATE_INTERVALS[self.interval_index]
+    }
+
+    pub fn cycle_update_interval(&mut self) {
+        self.interval_index = (self.interval_index + 1) % UPDATE_INTERVALS.len();
+        self.frames_until_update = 0;
+    }
+
+    // call once per frame, true on the frames the faces should be rendered
+    pub fn due(&mut self) -> bool {
+        if self.frames_until_update == 0 {
+            self.frames_until_update = self.update_interval() - 1;
+            true
+        } else {
+            self.frames_until_update -= 1;
+            false
+        }
+    }
+
+    // the view projection of each face in cube layer order. cube maps are addressed with texel rows going
+    // down, the opposite of clip space y, so the projection flips y; that also flips the winding, so the
+    // face pipelines shouldn't cull
+    pub fn view_projections(&self, depth_mode: DepthMode) -> [Matrix4<f32>; 6] {
+        let flip_y = Matrix4::from_nonuniform_scale(1.0, -1.0, 1.0);
+        let projection = depth_mode.projection(flip_y * transforms::cubemap_projection(PROBE_NEAR, transforms::Z_FAR));
+        transforms::cubemap_view_matrices(self.position).map(|view| projection * view)
+    }
+
+    pub fn begin_face_pass<'pass>(&'pass self, encoder: &'pass mut wgpu::CommandEncoder, face: usize, clear_color: wgpu::Color,
+        depth_mode: DepthMode) -> wgpu::RenderPass<'pass> {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Reflection Probe Face Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.faces[face].view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(depth_mode.clear_value()),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        })
+    }
+
+    pub fn write_sphere(&self, queue: &wgpu::Queue, view_projection: Matrix4<f32>, camera_position: Point3<f32>) {
+        let uniforms = SphereUniforms {
+            view_projection_matrix: view_projection.into(),
+            camera_position: camera_position.into(),
+            radius: self.radius,
+            center: self.position.into(),
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.sphere_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    // the mirror sphere, into the scene pass
+    pub fn draw_sphere<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>) {
+        pass.set_pipeline(&self.sphere_pipeline);
+        pass.set_bind_group(0, &self.sphere_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.sphere_mesh.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.sphere_mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..self.sphere_mesh.index_count, 0, 0..1);
+    }
+}
@@ -0,0 +1,40 @@
+struct Uniforms {
+    viewProjectionMatrix : mat4x4<f32>,
+    cameraPosition : vec3<f32>,
+    radius : f32,
+    center : vec3<f32>,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+@binding(1) @group(0) var environment : texture_cube<f32>;
+@binding(2) @group(0) var environmentSampler : sampler;
+
+struct Vertex {
+    @location(0) position : vec4<f32>,
+    @location(3) normal : vec3<f32>,
+};
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vWorldPosition : vec3<f32>,
+    @location(1) vNormal : vec3<f32>,
+};
+
+// a unit sphere mesh placed at center and scaled to radius
+@vertex
+fn vs_main(vertex : Vertex) -> Output {
+    let world = uniforms.center + vertex.position.xyz * uniforms.radius;
+
+    var output: Output;
+    output.Position = uniforms.viewProjectionMatrix * vec4<f32>(world, 1.0);
+    output.vWorldPosition = world;
+    output.vNormal = vertex.normal;
+    return output;
+}
+
+// a perfect mirror: the view direction reflected about the normal looks up the probe's cube map
+@fragment
+fn fs_main(in : Output) -> @location(0) vec4<f32> {
+    let view = normalize(in.vWorldPosition - uniforms.cameraPosition);
+    let direction = reflect(view, normalize(in.vNormal));
+    return vec4<f32>(textureSample(environment, environmentSampler, direction).rgb, 1.0);
+}
@@ -0,0 +1,540 @@
+use crate::{error::Error, resource_tracker::ResourceTracker, vertex_data};
+
+// float depth so reversed z (transforms::DepthMode) actually gains precision
+pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+// offscreen color + depth attachments the scene is rendered into before being blitted to the surface.
+// with a sample count above 1 the scene is drawn into msaa_view and resolved into color_view.
+pub struct RenderTarget {
+    pub color_texture: wgpu::Texture,
+    pub color_view: wgpu::TextureView,
+    pub msaa_texture: Option<wgpu::Texture>,
+    pub msaa_view: Option<wgpu::TextureView>,
+    pub depth_texture: wgpu::Texture,
+    pub depth_view: wgpu::TextureView,
+    pub format: wgpu::TextureFormat,
+    pub width: u32,
+    pub height: u32,
+    pub sample_count: u32,
+}
+
+// what read_pixel found at a pixel of the target. color is the resolved color as stored, so srgb encoded
+// for srgb targets. depth is the raw depth buffer value, None when the depth buffer is multisampled since
+// those can't be copied
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelReadback {
+    pub color: [u8; 4],
+    pub depth: Option<f32>,
+}
+
+// the attachments a set of pipelines was built for. wgpu pipelines don't expose this, so it is kept
+// next to them and checked against the target they draw into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PipelineTargets {
+    pub color_format: wgpu::TextureFormat,
+    pub depth_format: wgpu::TextureFormat,
+    pub sample_count: u32,
+}
+
+// what a render target's attachments turned out to be, read back from its textures
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attachments {
+    // the count the target was created for
+    pub sample_count: u32,
+    pub color_format: wgpu::TextureFormat,
+    pub color_samples: u32,
+    pub depth_format: wgpu::TextureFormat,
+    pub depth_samples: u32,
+}
+
+impl RenderTarget {
+    pub fn attachments(&self) -> Attachments {
+        Attachments {
+            sample_count: self.sample_count,
+            color_format: self.format,
+            color_samples: self.msaa_texture.as_ref().map_or(1, |texture| texture.sample_count()),
+            depth_format: self.depth_texture.format(),
+            depth_samples: self.depth_texture.sample_count(),
+        }
+    }
+
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat,
+        width: u32, height: u32, sample_count: u32) -> Self {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let color_texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some("Render Target Color"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            // COPY_SRC for read_color
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let msaa_texture = (sample_count > 1).then(|| tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some("Render Target Multisampled Color"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        }));
+
+        let depth_texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some("Render Target Depth"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            // TEXTURE_BINDING for the depth view, COPY_SRC for read_pixel
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let color_view = color_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let msaa_view = msaa_texture.as_ref().map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        RenderTarget {
+            color_texture,
+            color_view,
+            msaa_texture,
+            msaa_view,
+            depth_texture,
+            depth_view,
+            format,
+            width,
+            height,
+            sample_count,
+        }
+    }
+
+    // copies the resolved color texture back to the cpu, blocking until the gpu has finished every
+    // submitted frame. only 8 bit rgba and bgra targets can be read; the image is srgb encoded whatever
+    // the target's format, see frame_image
+    pub fn read_color(&self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> Result<image::RgbaImage, Error> {
+        let texels = self.read_texels(device, queue, tracker)?;
+        frame_image(self.format, self.width, self.height, texels)
+    }
+
+    // read_color's texels as the texture stores them, tightly packed rows in the target's format
+    pub fn read_texels(&self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> Result<Vec<u8>, Error> {
+        // fails early for formats frame_image can't convert
+        self.swaps_red_blue()?;
+
+        let unpadded_bytes_per_row = self.width * 4;
+        let padded_bytes_per_row = padded_bytes_per_row(unpadded_bytes_per_row);
+        let buffer = create_readback_buffer(device, tracker, padded_bytes_per_row * self.height);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            self.color_texture.as_image_copy(),
+            readback_copy(&buffer, padded_bytes_per_row, self.height),
+            self.color_texture.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * self.height) as usize);
+        for row in read_buffer(device, &buffer)?.chunks_exact(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+
+        Ok(pixels)
+    }
+
+    // the color and depth at one pixel of the target, blocking like read_color. even a single pixel copy
+    // goes through a buffer row padded to COPY_BYTES_PER_ROW_ALIGNMENT
+    pub fn read_pixel(&self, device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker, x: u32, y: u32) -> Result<PixelReadback, Error> {
+        if x >= self.width || y >= self.height {
+            return Err(Error::Unsupported(format!("pixel {}, {} is outside the {}x{} target", x, y, self.width, self.height)));
+        }
+        let swap_red_blue = self.swaps_red_blue()?;
+
+        let row = padded_bytes_per_row(4);
+        let color_buffer = create_readback_buffer(device, tracker, row);
+        // multisampled textures can't be copied, and resolving depth is not something wgpu does
+        let depth_buffer = (self.sample_count == 1).then(|| create_readback_buffer(device, tracker, row));
+
+        let origin = wgpu::Origin3d { x, y, z: 0 };
+        let pixel = wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 };
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pixel Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture { origin, ..self.color_texture.as_image_copy() },
+            readback_copy(&color_buffer, row, 1),
+            pixel,
+        );
+        if let Some(depth_buffer) = &depth_buffer {
+            encoder.copy_texture_to_buffer(
+                wgpu::ImageCopyTexture { origin, aspect: wgpu::TextureAspect::DepthOnly, ..self.depth_texture.as_image_copy() },
+                readback_copy(depth_buffer, row, 1),
+                pixel,
+            );
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let bytes = read_buffer(device, &color_buffer)?;
+        let mut color = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if swap_red_blue {
+            color.swap(0, 2);
+        }
+
+        let depth = match &depth_buffer {
+            Some(depth_buffer) => {
+                let bytes = read_buffer(device, depth_buffer)?;
+                Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            None => None,
+        };
+
+        Ok(PixelReadback { color, depth })
+    }
+
+    fn swaps_red_blue(&self) -> Result<bool, Error> {
+        swaps_red_blue(self.format)
+    }
+
+    // color attachment for the scene pass, resolving the multisampled texture when there is one
+    pub fn color_attachment(&self, load: wgpu::LoadOp<wgpu::Color>) -> wgpu::RenderPassColorAttachment<'_> {
+        match &self.msaa_view {
+            Some(msaa_view) => wgpu::RenderPassColorAttachment {
+                view: msaa_view,
+                resolve_target: Some(&self.color_view),
+                ops: wgpu::Operations {
+                    load,
+                    // only the resolved result is read afterwards
+                    store: wgpu::StoreOp::Discard,
+                },
+            },
+            None => wgpu::RenderPassColorAttachment {
+                view: &self.color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            },
+        }
+    }
+}
+
+// whether readback bytes of the format come out bgra and need swapping, for the 8 bit formats only
+fn swaps_red_blue(format: wgpu::TextureFormat) -> Result<bool, Error> {
+    match format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => Ok(false),
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => Ok(true),
+        format => Err(Error::Unsupported(format!("reading back {:?} targets is not supported", format))),
+    }
+}
+
+// rgba pixels in srgb, as image files expect, from the tightly packed texels of a format texture.
+// an srgb format already stores srgb encoded bytes, they are kept as they are; converting them again
+// would apply the gamma twice and wash the image out. a linear format stores the shader's linear
+// output, which is encoded here so it doesn't come out too dark. alpha is linear either way
+pub fn frame_image(format: wgpu::TextureFormat, width: u32, height: u32, mut pixels: Vec<u8>) -> Result<image::RgbaImage, Error> {
+    let swap_red_blue = swaps_red_blue(format)?;
+    let linear = !format.is_srgb();
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        if swap_red_blue {
+            pixel.swap(0, 2);
+        }
+        if linear {
+            for channel in &mut pixel[..3] {
+                *channel = (vertex_data::linear_channel_to_srgb(*channel as f32 / 255.0) * 255.0).round() as u8;
+            }
+        }
+    }
+
+    image::RgbaImage::from_raw(width, height, pixels).ok_or_else(|| Error::Unsupported("readback size mismatch".to_string()))
+}
+
+// frame_image encoded as a png file's bytes
+pub fn encode_frame(format: wgpu::TextureFormat, width: u32, height: u32, pixels: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let image = frame_image(format, width, height, pixels)?;
+    let mut png = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+// buffer rows of a texture copy must be a multiple of COPY_BYTES_PER_ROW_ALIGNMENT
+fn padded_bytes_per_row(unpadded_bytes_per_row: u32) -> u32 {
+    unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+fn create_readback_buffer(device: &wgpu::Device, tracker: &ResourceTracker, size: u32) -> wgpu::Buffer {
+    tracker.create_buffer(device, &wgpu::BufferDescriptor {
+        label: Some("Render Target Readback Buffer"),
+        size: size as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    })
+}
+
+fn readback_copy(buffer: &wgpu::Buffer, bytes_per_row: u32, rows: u32) -> wgpu::ImageCopyBuffer<'_> {
+    wgpu::ImageCopyBuffer {
+        buffer,
+        layout: wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: Some(rows),
+        },
+    }
+}
+
+// maps a readback buffer once the copies into it are submitted and returns its contents
+pub fn read_buffer(device: &wgpu::Device, buffer: &wgpu::Buffer) -> Result<Vec<u8>, Error> {
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    // the callback has run once poll returns, a closed channel would mean it was dropped unmapped
+    receiver
+        .recv()
+        .map_err(|_| Error::BufferAsync(wgpu::BufferAsyncError))??;
+
+    let bytes = slice.get_mapped_range().to_vec();
+    buffer.unmap();
+
+    Ok(bytes)
+}
+
+// a viewport or scissor rectangle from the top left corner. wgpu's set_viewport and set_scissor_rect
+// take physical pixels while layouts are easier to think about in logical ones (physical divided by
+// the window's scale factor), so viewports are described logically and converted right before use
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+pub fn logical_to_physical_rect(rect: Rect, scale_factor: f64) -> Rect {
+    let scale = scale_factor as f32;
+
+    Rect {
+        x: rect.x * scale,
+        y: rect.y * scale,
+        width: rect.width * scale,
+        height: rect.height * scale,
+    }
+}
+
+pub fn physical_to_logical_rect(rect: Rect, scale_factor: f64) -> Rect {
+    logical_to_physical_rect(rect, 1.0 / scale_factor)
+}
+
+// sets the pass's viewport from a logical rectangle, with the full [0, 1] depth range
+pub fn set_logical_viewport(pass: &mut wgpu::RenderPass, rect: Rect, scale_factor: f64) {
+    let physical = logical_to_physical_rect(rect, scale_factor);
+    pass.set_viewport(physical.x, physical.y, physical.width, physical.height, 0.0, 1.0);
+}
+
+// size of the internal target for a surface of the given size, never smaller than 1x1
+pub fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    let scaled_width = ((width as f32 * scale).round() as u32).max(1);
+    let scaled_height = ((height as f32 * scale).round() as u32).max(1);
+
+    (scaled_width, scaled_height)
+}
+
+// highest msaa sample count the device can render to format with. counts beyond 4 are only valid with
+// TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES enabled on the device, which init_wgpu requests when available
+// and safe mode never does, so without it the adapter's flags are capped at the 4 every adapter allows
+pub fn max_supported_samples(adapter: &wgpu::Adapter, device: &wgpu::Device, format: wgpu::TextureFormat) -> u32 {
+    let flags = adapter.get_texture_format_features(format).flags;
+    let adapter_specific = device.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+
+    [16, 8, 4, 2]
+        .into_iter()
+        .filter(|&count| adapter_specific || count <= 4)
+        .find(|&count| flags.sample_count_supported(count))
+        .unwrap_or(1)
+}
+
+// checks that pipelines built for `pipelines` can draw into `target` and that the adapter can render
+// the target at all, describing the first mismatch instead of leaving it to a wgpu validation panic
+pub fn validate_render_config(adapter: &wgpu::Adapter, target: &RenderTarget, pipelines: &PipelineTargets) -> Result<(), String> {
+    check_attachments(pipelines, &target.attachments())?;
+
+    for format in [target.format, target.depth_texture.format()] {
+        let features = adapter.get_texture_format_features(format);
+        if !features.allowed_usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT) {
+            return Err(format!("{:?} is not renderable on this adapter", format));
+        }
+        if target.sample_count > 1 && !features.flags.sample_count_supported(target.sample_count) {
+            return Err(format!("{}x msaa is not supported for {:?} on this adapter", target.sample_count, format));
+        }
+    }
+
+    Ok(())
+}
+
+// the part of validate_render_config that needs no adapter: the sample counts and formats pipelines
+// were built for against the ones the attachments have
+pub fn check_attachments(pipelines: &PipelineTargets, attachments: &Attachments) -> Result<(), String> {
+    if pipelines.sample_count != attachments.sample_count {
+        return Err(format!("pipelines use {}x msaa but the render target has {}x", pipelines.sample_count, attachments.sample_count));
+    }
+
+    if attachments.color_samples != attachments.sample_count {
+        return Err(format!("color attachment has {}x msaa but the render target expects {}x",
+            attachments.color_samples, attachments.sample_count));
+    }
+
+    if attachments.depth_samples != attachments.sample_count {
+        return Err(format!("depth attachment has {}x msaa but the color attachment has {}x",
+            attachments.depth_samples, attachments.sample_count));
+    }
+
+    if pipelines.color_format != attachments.color_format {
+        return Err(format!("pipelines write {:?} but the render target is {:?}", pipelines.color_format, attachments.color_format));
+    }
+
+    if pipelines.depth_format != attachments.depth_format {
+        return Err(format!("pipelines test against {:?} but the depth attachment is {:?}",
+            pipelines.depth_format, attachments.depth_format));
+    }
+
+    Ok(())
+}
+
+// one line on the anti-aliasing the adapter allows for format, to help pick settings: the msaa sample counts
+// of the color and depth formats and the one in use, whether wireframes can be drawn (the device has
+// POLYGON_MODE_LINE, not just the adapter), whether the format is srgb, the largest supersampling the
+// texture size limit allows at size, and whether anisotropic filtering works
+pub fn aa_report(adapter: &wgpu::Adapter, device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32,
+    size: (u32, u32)) -> String {
+    let color_samples = max_supported_samples(adapter, device, format);
+    let depth_samples = max_supported_samples(adapter, device, DEPTH_FORMAT);
+    let wireframe = device.features().contains(wgpu::Features::POLYGON_MODE_LINE);
+    let max_dimension = device.limits().max_texture_dimension_2d;
+    let max_ssaa = max_dimension / size.0.max(size.1).max(1);
+    let anisotropy = crate::texture::anisotropy_supported(adapter);
+
+    format!(
+        "anti-aliasing: msaa up to {}x ({:?} {}x, {:?} {}x), using {}x; wireframe {}; {:?} is {}srgb; \
+         ssaa up to {}x at {}x{}; anisotropic filtering {}",
+        color_samples.min(depth_samples), format, color_samples, DEPTH_FORMAT, depth_samples, sample_count,
+        if wireframe { "available" } else { "unavailable (no POLYGON_MODE_LINE)" },
+        format, if format.is_srgb() { "" } else { "not " },
+        max_ssaa, size.0, size.1,
+        if anisotropy { "available" } else { "unavailable" },
+    )
+}
+
+// the requested sample count lowered to what both the color and depth formats support
+pub fn clamp_sample_count(adapter: &wgpu::Adapter, device: &wgpu::Device, format: wgpu::TextureFormat, requested: u32) -> u32 {
+    let supported = max_supported_samples(adapter, device, format).min(max_supported_samples(adapter, device, DEPTH_FORMAT));
+
+    if requested > supported {
+        log::warn!("{}x msaa is not supported for {:?}, falling back to {}x", requested, format, supported);
+        supported
+    } else {
+        requested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINES: PipelineTargets = PipelineTargets {
+        color_format: wgpu::TextureFormat::Bgra8UnormSrgb,
+        depth_format: DEPTH_FORMAT,
+        sample_count: 4,
+    };
+
+    const ATTACHMENTS: Attachments = Attachments {
+        sample_count: 4,
+        color_format: wgpu::TextureFormat::Bgra8UnormSrgb,
+        color_samples: 4,
+        depth_format: DEPTH_FORMAT,
+        depth_samples: 4,
+    };
+
+    // a linear clear color as the gpu stores it in a texel of format: srgb encoded for the srgb
+    // formats, blue first for the bgra ones
+    fn clear_texel(color: [f64; 4], format: wgpu::TextureFormat) -> [u8; 4] {
+        let encode = |c: f64| if format.is_srgb() { vertex_data::linear_channel_to_srgb(c as f32) } else { c as f32 };
+        let [r, g, b, a] = [encode(color[0]), encode(color[1]), encode(color[2]), color[3] as f32].map(|c| (c * 255.0).round() as u8);
+        if swaps_red_blue(format).unwrap() { [b, g, r, a] } else { [r, g, b, a] }
+    }
+
+    #[test]
+    fn clear_color_reads_back_as_srgb() {
+        let color = [0.5, 0.25, 0.0, 1.0];
+        // srgb of 0.5 is 0.735 and of 0.25 is 0.537
+        let expected = [188, 137, 0, 255];
+        let formats = [
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureFormat::Bgra8Unorm,
+            wgpu::TextureFormat::Bgra8UnormSrgb,
+        ];
+
+        for format in formats {
+            let texels = clear_texel(color, format).repeat(4);
+            let image = frame_image(format, 2, 2, texels.clone()).unwrap();
+            assert!(image.pixels().all(|pixel| pixel.0 == expected), "{:?} read back as {:?}", format, image.get_pixel(0, 0));
+
+            let png = encode_frame(format, 2, 2, texels).unwrap();
+            let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap().to_rgba8();
+            assert_eq!(decoded, image);
+        }
+    }
+
+    #[test]
+    fn matching_attachments_pass() {
+        assert_eq!(check_attachments(&PIPELINES, &ATTACHMENTS), Ok(()));
+    }
+
+    #[test]
+    fn pipeline_and_target_samples_differ() {
+        let pipelines = PipelineTargets { sample_count: 1, ..PIPELINES };
+        let error = check_attachments(&pipelines, &ATTACHMENTS).unwrap_err();
+        assert!(error.contains("pipelines use 1x msaa"), "{}", error);
+    }
+
+    #[test]
+    fn color_and_depth_samples_differ() {
+        let color = Attachments { color_samples: 1, ..ATTACHMENTS };
+        let error = check_attachments(&PIPELINES, &color).unwrap_err();
+        assert!(error.contains("color attachment has 1x msaa"), "{}", error);
+
+        let depth = Attachments { depth_samples: 1, ..ATTACHMENTS };
+        let error = check_attachments(&PIPELINES, &depth).unwrap_err();
+        assert!(error.contains("depth attachment has 1x msaa"), "{}", error);
+    }
+
+    #[test]
+    fn color_format_differs() {
+        let attachments = Attachments { color_format: wgpu::TextureFormat::Rgba8Unorm, ..ATTACHMENTS };
+        let error = check_attachments(&PIPELINES, &attachments).unwrap_err();
+        assert!(error.contains("but the render target is Rgba8Unorm"), "{}", error);
+    }
+
+    #[test]
+    fn depth_format_differs() {
+        let attachments = Attachments { depth_format: wgpu::TextureFormat::Depth24Plus, ..ATTACHMENTS };
+        let error = check_attachments(&PIPELINES, &attachments).unwrap_err();
+        assert!(error.contains("but the depth attachment is Depth24Plus"), "{}", error);
+    }
+}
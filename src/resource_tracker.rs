@@ -0,0 +1,87 @@
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use wgpu::util::DeviceExt;
+
+// counts the textures and buffers created through it, so a leak such as a texture allocated every
+// frame shows up as steadily growing totals in the log. the counting only exists in debug builds,
+// in release builds the helpers forward straight to the device.
+#[derive(Default)]
+pub struct ResourceTracker {
+    #[cfg(debug_assertions)]
+    textures: AtomicU64,
+    #[cfg(debug_assertions)]
+    texture_bytes: AtomicU64,
+    #[cfg(debug_assertions)]
+    buffers: AtomicU64,
+    #[cfg(debug_assertions)]
+    buffer_bytes: AtomicU64,
+    #[cfg(debug_assertions)]
+    changed: AtomicBool,
+}
+
+impl ResourceTracker {
+    pub fn create_texture(&self, device: &wgpu::Device, desc: &wgpu::TextureDescriptor) -> wgpu::Texture {
+        #[cfg(debug_assertions)]
+        {
+            // formats without a fixed block size (e.g. Depth24Plus) are counted as 4 bytes per texel
+            let (block_width, block_height) = desc.format.block_dimensions();
+            let block_size = desc.format.block_copy_size(None).unwrap_or(4) as u64;
+            let blocks = (desc.size.width.div_ceil(block_width) * desc.size.height.div_ceil(block_height)) as u64;
+            let bytes = blocks * block_size * desc.size.depth_or_array_layers as u64 * desc.sample_count as u64;
+
+            self.textures.fetch_add(1, Ordering::Relaxed);
+            self.texture_bytes.fetch_add(bytes, Ordering::Relaxed);
+            self.changed.store(true, Ordering::Relaxed);
+        }
+
+        device.create_texture(desc)
+    }
+
+    pub fn create_buffer(&self, device: &wgpu::Device, desc: &wgpu::BufferDescriptor) -> wgpu::Buffer {
+        #[cfg(debug_assertions)]
+        self.count_buffer(desc.size);
+
+        device.create_buffer(desc)
+    }
+
+    pub fn create_buffer_init(&self, device: &wgpu::Device, desc: &wgpu::util::BufferInitDescriptor) -> wgpu::Buffer {
+        #[cfg(debug_assertions)]
+        self.count_buffer(desc.contents.len() as u64);
+
+        device.create_buffer_init(desc)
+    }
+
+    #[cfg(debug_assertions)]
+    fn count_buffer(&self, size: u64) {
+        self.buffers.fetch_add(1, Ordering::Relaxed);
+        self.buffer_bytes.fetch_add(size, Ordering::Relaxed);
+        self.changed.store(true, Ordering::Relaxed);
+    }
+
+    // totals of everything created so far, None in release builds
+    pub fn summary(&self) -> Option<String> {
+        #[cfg(debug_assertions)]
+        {
+            Some(format!(
+                "{} textures ({:.1} MiB), {} buffers ({:.1} MiB) created",
+                self.textures.load(Ordering::Relaxed),
+                self.texture_bytes.load(Ordering::Relaxed) as f64 / (1024.0 * 1024.0),
+                self.buffers.load(Ordering::Relaxed),
+                self.buffer_bytes.load(Ordering::Relaxed) as f64 / (1024.0 * 1024.0),
+            ))
+        }
+
+        #[cfg(not(debug_assertions))]
+        None
+    }
+
+    // the summary, but only if something was created since the last call
+    pub fn take_changed_summary(&self) -> Option<String> {
+        #[cfg(debug_assertions)]
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return None;
+        }
+
+        self.summary()
+    }
+}
@@ -1,22 +1,126 @@
 struct Uniforms {
-    mvpMatrix : mat4x4<f32>,
+    viewProjectionMatrix : mat4x4<f32>,
+    modelMatrix : mat4x4<f32>,
+    morphWeight : f32,
+    // 0 turns the edge highlight off
+    edgeWidth : f32,
+    fogDensity : f32,
+    // 0 turns the fog off
+    fogEnabled : u32,
+    cameraPosition : vec4<f32>,
+    fogColor : vec4<f32>,
+    // seconds of animation time
+    time : f32,
 };
 @binding(0) @group(0) var<uniform> uniforms : Uniforms;
 
+const MAX_LIGHTS : u32 = 16u;
+const AMBIENT : f32 = 0.15;
+
+struct Light {
+    position : vec3<f32>,
+    range : f32,
+    color : vec3<f32>,
+    intensity : f32,
+};
+
+struct Lights {
+    lights : array<Light, MAX_LIGHTS>,
+    // 0 renders the plain vertex colors without lighting
+    count : u32,
+};
+@binding(1) @group(0) var<uniform> lights : Lights;
+
+struct Vertex {
+    @location(0) position : vec4<f32>,
+    @location(1) color : vec4<f32>,
+    @location(2) morphPosition : vec4<f32>,
+    @location(3) normal : vec3<f32>,
+    @location(4) morphNormal : vec3<f32>,
+    @location(5) uv : vec2<f32>,
+    @location(6) uv1 : vec2<f32>,
+    @location(7) barycentric : vec3<f32>,
+};
+
+@binding(0) @group(1) var baseColorTexture : texture_2d<f32>;
+@binding(1) @group(1) var baseColorSampler : sampler;
+
+// multiplies the lit and flat variants' color through uv1. white unless --lightmap is given
+@binding(0) @group(2) var lightmapTexture : texture_2d<f32>;
+@binding(1) @group(2) var lightmapSampler : sampler;
+
 struct Output {
     @builtin(position) Position : vec4<f32>,
     @location(0) vColor : vec4<f32>,
+    @location(1) vWorldPosition : vec3<f32>,
+    @location(2) vNormal : vec3<f32>,
+    @location(3) vUv : vec2<f32>,
+    @location(4) vUv1 : vec2<f32>,
+    @location(5) vBarycentric : vec3<f32>,
+};
+
+struct Instance {
+    @location(8) model0 : vec4<f32>,
+    @location(9) model1 : vec4<f32>,
+    @location(10) model2 : vec4<f32>,
+    @location(11) model3 : vec4<f32>,
 };
 
+// the inverse transpose of m's upper 3x3, up to a scale the fragment stage normalizes away: its cofactor
+// matrix, whose columns are cross products of m's. unlike m itself it keeps normals perpendicular to the
+// surface under non-uniform scale. wgsl has no inverse, this needs none. the cofactor matrix is the
+// inverse transpose times the determinant, so a mirroring m (see transforms::flip_handedness) would
+// turn normals inward; multiplying by the determinant's sign keeps them pointing out
+fn normalMatrix(m : mat4x4<f32>) -> mat3x3<f32> {
+    let x = m[0].xyz;
+    let y = m[1].xyz;
+    let z = m[2].xyz;
+    return mat3x3<f32>(cross(y, z), cross(z, x), cross(x, y)) * sign(dot(x, cross(y, z)));
+}
+
 @vertex
-fn vs_main(@location(0) pos: vec4<f32>, @location(1) color: vec4<f32>) -> Output {
+fn vs_main(vertex: Vertex, instance: Instance) -> Output {
+    let instanceMatrix = mat4x4<f32>(instance.model0, instance.model1, instance.model2, instance.model3);
+    let position = mix(vertex.position, vertex.morphPosition, uniforms.morphWeight);
+    let normal = mix(vertex.normal, vertex.morphNormal, uniforms.morphWeight);
+    let worldMatrix = uniforms.modelMatrix * instanceMatrix;
+
     var output: Output;
-    output.Position = uniforms.mvpMatrix * pos;
-    output.vColor = color;
+    output.Position = uniforms.viewProjectionMatrix * worldMatrix * position;
+    output.vColor = vertex.color;
+    output.vWorldPosition = (worldMatrix * position).xyz;
+    output.vNormal = normalMatrix(worldMatrix) * normal;
+    output.vUv = vertex.uv;
+    output.vUv1 = vertex.uv1;
+    output.vBarycentric = vertex.barycentric;
     return output;
 }
 
-@fragment
-fn fs_main(@location(0) vColor: vec4<f32>) -> @location(0) vec4<f32> {
-    return vColor;
-}
\ No newline at end of file
+const EDGE_COLOR : vec3<f32> = vec3<f32>(0.05, 0.05, 0.05);
+
+// darkens color near the triangle's edges, where one barycentric coordinate goes to 0. dividing by the
+// screen space derivatives keeps the line edgeWidth pixels wide at any distance and angle, and the
+// one pixel smoothstep ramp anti-aliases it. the branch is on a uniform, so the derivatives are valid
+fn edgeHighlight(color : vec4<f32>, barycentric : vec3<f32>) -> vec4<f32> {
+    if (uniforms.edgeWidth <= 0.0) {
+        return color;
+    }
+
+    let pixels = barycentric / max(abs(dpdx(barycentric)) + abs(dpdy(barycentric)), vec3<f32>(1e-6));
+    let distance = min(min(pixels.x, pixels.y), pixels.z);
+    let edge = 1.0 - smoothstep(uniforms.edgeWidth - 0.5, uniforms.edgeWidth + 0.5, distance);
+    return vec4<f32>(mix(color.rgb, EDGE_COLOR, edge), color.a);
+}
+
+// exponential squared distance fog: clear near the camera, thickening quickly past 1 / fogDensity
+fn applyFog(color : vec4<f32>, worldPosition : vec3<f32>) -> vec4<f32> {
+    if (uniforms.fogEnabled == 0u) {
+        return color;
+    }
+
+    let distance = length(worldPosition - uniforms.cameraPosition.xyz) * uniforms.fogDensity;
+    let fog = 1.0 - exp(-distance * distance);
+    return vec4<f32>(mix(color.rgb, uniforms.fogColor.rgb, fog), color.a);
+}
+
+// the fragment stage is one of the shader_*.wgsl variants, appended to this file when the module is built
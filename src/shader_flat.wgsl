@@ -0,0 +1,7 @@
+// vertex colors, base color texture and lightmap without any dynamic lighting
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    let color = in.vColor * textureSample(baseColorTexture, baseColorSampler, in.vUv)
+        * textureSample(lightmapTexture, lightmapSampler, in.vUv1);
+    return applyFog(edgeHighlight(color, in.vBarycentric), in.vWorldPosition);
+}
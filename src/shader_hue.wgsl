@@ -0,0 +1,20 @@
+// how fast the hue turns, in turns per second of animation time, and how much it shifts per world
+// unit along each axis so bands of color sweep over the model
+const HUE_SPEED : f32 = 0.2;
+const HUE_SPREAD : vec3<f32> = vec3<f32>(0.25, 0.35, 0.15);
+
+// the standard hsv to rgb conversion, h, s and v in [0, 1] with h wrapping around
+fn hsvToRgb(hsv : vec3<f32>) -> vec3<f32> {
+    let k = vec3<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0);
+    let p = abs(fract(hsv.xxx + k) * 6.0 - 3.0);
+    return hsv.z * mix(vec3<f32>(1.0), clamp(p - 1.0, vec3<f32>(0.0), vec3<f32>(1.0)), hsv.y);
+}
+
+// hue cycling over time and position, the vertex color only keeps its brightness as the value
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    let hue = fract(uniforms.time * HUE_SPEED + dot(in.vWorldPosition, HUE_SPREAD));
+    let value = 0.6 + 0.4 * max(max(in.vColor.r, in.vColor.g), in.vColor.b);
+    let color = vec4<f32>(hsvToRgb(vec3<f32>(hue, 0.85, value)), in.vColor.a);
+    return applyFog(edgeHighlight(color, in.vBarycentric), in.vWorldPosition);
+}
@@ -0,0 +1,25 @@
+// vertex colors and base color texture, lit by the point lights
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    // untextured meshes are bound to a 1x1 white texture, and so is the lightmap without --lightmap
+    let color = in.vColor * textureSample(baseColorTexture, baseColorSampler, in.vUv)
+        * textureSample(lightmapTexture, lightmapSampler, in.vUv1);
+
+    if (lights.count == 0u) {
+        return applyFog(edgeHighlight(color, in.vBarycentric), in.vWorldPosition);
+    }
+
+    let normal = normalize(in.vNormal);
+    var lighting = vec3<f32>(AMBIENT);
+
+    for (var i = 0u; i < min(lights.count, MAX_LIGHTS); i++) {
+        let light = lights.lights[i];
+        let toLight = light.position - in.vWorldPosition;
+        let distance = length(toLight);
+        let falloff = pow(saturate(1.0 - pow(distance / light.range, 2.0)), 2.0);
+        let diffuse = max(dot(normal, toLight / distance), 0.0);
+        lighting += light.color * light.intensity * diffuse * falloff;
+    }
+
+    return applyFog(edgeHighlight(vec4<f32>(color.rgb * lighting, color.a), in.vBarycentric), in.vWorldPosition);
+}
\ No newline at end of file
@@ -0,0 +1,5 @@
+// world space normals remapped from [-1, 1] to [0, 1]
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    return applyFog(edgeHighlight(vec4<f32>(normalize(in.vNormal) * 0.5 + 0.5, 1.0), in.vBarycentric), in.vWorldPosition);
+}
@@ -0,0 +1,5 @@
+// texture coordinates as red and green, wrapped so tiling uvs stay visible
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    return applyFog(edgeHighlight(vec4<f32>(fract(in.vUv), 0.0, 1.0), in.vBarycentric), in.vWorldPosition);
+}
@@ -0,0 +1,5 @@
+// a single color for the wireframe overlay, so its edges stand out against any of the other variants
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    return applyFog(vec4<f32>(0.05, 0.05, 0.05, 1.0), in.vWorldPosition);
+}
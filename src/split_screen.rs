@@ -0,0 +1,53 @@
+use cgmath::{Matrix4, Point3, Vector3};
+
+use crate::transforms;
+
+// the orthographic views of the 2x2 split screen, looking along the axes like a cad layout
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AxisView {
+    Front,
+    Side,
+    Top,
+}
+
+// the first three quadrants, left to right and top to bottom; the last one is the main camera's own view
+pub const AXIS_VIEWS: [AxisView; 3] = [AxisView::Front, AxisView::Side, AxisView::Top];
+pub const MAIN_QUADRANT: usize = 3;
+
+impl AxisView {
+    pub fn name(self) -> &'static str {
+        match self {
+            AxisView::Front => "front",
+            AxisView::Side => "side",
+            AxisView::Top => "top",
+        }
+    }
+
+    // from +z toward -z, from +x toward -x, and from +y down with -z at the top of the view
+    pub fn direction_and_up(self) -> (Vector3<f32>, Vector3<f32>) {
+        match self {
+            AxisView::Front => (Vector3::unit_z(), Vector3::unit_y()),
+            AxisView::Side => (Vector3::unit_x(), Vector3::unit_y()),
+            AxisView::Top => (Vector3::unit_y(), -Vector3::unit_z()),
+        }
+    }
+
+    // a view of the sphere at center with radius, the camera outside it on this view's axis
+    pub fn view_matrix(self, center: Point3<f32>, radius: f32) -> Matrix4<f32> {
+        let (direction, up) = self.direction_and_up();
+        transforms::create_view(center + direction * radius * 2.0, center, up)
+    }
+
+    // an orthographic projection just fitting the sphere view_matrix was built for, in the wgpu depth
+    // range. aspect is the quadrant's width over its height
+    pub fn projection(self, radius: f32, aspect: f32) -> Matrix4<f32> {
+        transforms::create_projection_ortho_fit(radius, aspect, radius, radius * 3.0)
+    }
+}
+
+// the viewport of quadrant index (0 to 3, see AXIS_VIEWS) of a width by height target, as x, y, width, height
+pub fn quadrant_viewport(index: usize, width: u32, height: u32) -> [f32; 4] {
+    let (half_width, half_height) = (width as f32 / 2.0, height as f32 / 2.0);
+    let (column, row) = ((index % 2) as f32, (index / 2) as f32);
+    [column * half_width, row * half_height, half_width, half_height]
+}
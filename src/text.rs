@@ -0,0 +1,458 @@
+use std::cell::Cell;
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+
+use crate::{resource_tracker::ResourceTracker, transforms};
+
+// glyphs are 5x7 pixels, each stored in a 6x8 cell of the atlas so neighbours never bleed in
+pub const GLYPH_WIDTH: u32 = 5;
+pub const GLYPH_HEIGHT: u32 = 7;
+const CELL_WIDTH: u32 = GLYPH_WIDTH + 1;
+const CELL_HEIGHT: u32 = GLYPH_HEIGHT + 1;
+const ATLAS_COLUMNS: u32 = 16;
+const ATLAS_ROWS: u32 = 6;
+const FIRST_GLYPH: u8 = b' ';
+const LAST_GLYPH: u8 = b'~';
+// the atlas cell after the last glyph is fully covered, draw_rects samples it for solid quads
+const SOLID_CELL: u32 = (LAST_GLYPH - FIRST_GLYPH) as u32 + 1;
+// glyphs past this in a frame are dropped, the vertex buffer never grows mid pass
+const MAX_GLYPHS: usize = 4096;
+const TAB_WIDTH: u32 = 4;
+
+// overlay vertex in window pixels
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct TextVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+unsafe impl Pod for TextVertex {}
+unsafe impl Zeroable for TextVertex {}
+
+impl TextVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TextVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// bitmap font text drawn as alpha blended quads over whatever is already in the pass. every
+// draw_text call appends its quads after the previous call's in one vertex buffer, so several
+// labels can be drawn into the same pass; begin_frame rewinds the buffer once per frame.
+pub struct TextRenderer {
+    pub pipeline: wgpu::RenderPipeline,
+    pub uniform_buffer: wgpu::Buffer,
+    pub vertex_buffer: wgpu::Buffer,
+    pub atlas: wgpu::Texture,
+    pub bind_group: wgpu::BindGroup,
+    glyph_count: Cell<usize>,
+}
+
+impl TextRenderer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Text Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("text.wgsl").into()),
+        });
+
+        let projection: [[f32; 4]; 4] = Matrix4::identity().into();
+        let uniform_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Text Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[projection]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let vertex_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Text Vertex Buffer"),
+            size: (6 * MAX_GLYPHS * std::mem::size_of::<TextVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let atlas = create_atlas(device, queue, tracker);
+        let atlas_view = atlas.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Text Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("Text Bind Group Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: Some("Text Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Text Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Text Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[TextVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        TextRenderer {
+            pipeline,
+            uniform_buffer,
+            vertex_buffer,
+            atlas,
+            bind_group,
+            glyph_count: Cell::new(0),
+        }
+    }
+
+    // rewinds the vertex buffer and sets up a pixel projection for a target of the given size
+    pub fn begin_frame(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+        let projection: [[f32; 4]; 4] =
+            transforms::create_projection_ortho(0.0, width as f32, height as f32, 0.0, -1.0, 1.0).into();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[projection]));
+        self.glyph_count.set(0);
+    }
+
+    // draws ascii text with its top left corner at (x, y) window pixels, each font pixel scale pixels
+    // wide. newlines return to x, tabs advance to the next multiple of four columns and anything
+    // outside printable ascii is drawn as '?'
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, queue: &wgpu::Queue,
+        text: &str, x: f32, y: f32, scale: f32, color: [f32; 4]) {
+        self.draw_quads(pass, queue, &layout_text(text, x, y, scale, color));
+    }
+
+    // solid [x, y, width, height] rectangles in window pixels, for overlay shapes like the crosshair.
+    // they share the glyph budget and buffer with draw_text, one glyph each
+    pub fn draw_rects<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, queue: &wgpu::Queue,
+        rects: &[[f32; 4]], color: [f32; 4]) {
+        self.draw_quads(pass, queue, &layout_rects(rects, color));
+    }
+
+    fn draw_quads<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, queue: &wgpu::Queue, vertices: &[TextVertex]) {
+        let first = self.glyph_count.get();
+        let count = (vertices.len() / 6).min(MAX_GLYPHS - first);
+        if count < vertices.len() / 6 {
+            log::warn!("text overlay is full, dropping {} glyphs", vertices.len() / 6 - count);
+        }
+        if count == 0 {
+            return;
+        }
+
+        let offset = (6 * first * std::mem::size_of::<TextVertex>()) as wgpu::BufferAddress;
+        queue.write_buffer(&self.vertex_buffer, offset, bytemuck::cast_slice(&vertices[..6 * count]));
+        self.glyph_count.set(first + count);
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.draw(6 * first as u32..6 * (first + count) as u32, 0..1);
+    }
+}
+
+// two triangles per visible glyph
+pub fn layout_text(text: &str, x: f32, y: f32, scale: f32, color: [f32; 4]) -> Vec<TextVertex> {
+    let mut vertices = Vec::with_capacity(6 * text.len());
+    let advance = CELL_WIDTH as f32 * scale;
+    let line_height = (CELL_HEIGHT + 2) as f32 * scale;
+    let (mut column, mut line) = (0, 0);
+
+    for c in text.chars() {
+        match c {
+            '\n' => {
+                column = 0;
+                line += 1;
+                continue;
+            }
+            '\t' => {
+                column = (column / TAB_WIDTH + 1) * TAB_WIDTH;
+                continue;
+            }
+            ' ' => {
+                column += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let glyph = if c.is_ascii() && (FIRST_GLYPH..=LAST_GLYPH).contains(&(c as u8)) { c as u8 } else { b'?' };
+        let index = (glyph - FIRST_GLYPH) as u32;
+        let (u0, v0) = ((index % ATLAS_COLUMNS * CELL_WIDTH) as f32, (index / ATLAS_COLUMNS * CELL_HEIGHT) as f32);
+        let (u0, v0) = (u0 / (ATLAS_COLUMNS * CELL_WIDTH) as f32, v0 / (ATLAS_ROWS * CELL_HEIGHT) as f32);
+        let u1 = u0 + GLYPH_WIDTH as f32 / (ATLAS_COLUMNS * CELL_WIDTH) as f32;
+        let v1 = v0 + GLYPH_HEIGHT as f32 / (ATLAS_ROWS * CELL_HEIGHT) as f32;
+
+        let x0 = x + column as f32 * advance;
+        let y0 = y + line as f32 * line_height;
+        let x1 = x0 + GLYPH_WIDTH as f32 * scale;
+        let y1 = y0 + GLYPH_HEIGHT as f32 * scale;
+
+        let corner = |x, y, u, v| TextVertex { position: [x, y], uv: [u, v], color };
+        vertices.extend_from_slice(&[
+            corner(x0, y0, u0, v0),
+            corner(x0, y1, u0, v1),
+            corner(x1, y1, u1, v1),
+            corner(x0, y0, u0, v0),
+            corner(x1, y1, u1, v1),
+            corner(x1, y0, u1, v0),
+        ]);
+        column += 1;
+    }
+
+    vertices
+}
+
+// two triangles per rectangle, all sampling the middle of the solid cell
+pub fn layout_rects(rects: &[[f32; 4]], color: [f32; 4]) -> Vec<TextVertex> {
+    let u = (SOLID_CELL % ATLAS_COLUMNS * CELL_WIDTH) as f32 + GLYPH_WIDTH as f32 / 2.0;
+    let v = (SOLID_CELL / ATLAS_COLUMNS * CELL_HEIGHT) as f32 + GLYPH_HEIGHT as f32 / 2.0;
+    let uv = [u / (ATLAS_COLUMNS * CELL_WIDTH) as f32, v / (ATLAS_ROWS * CELL_HEIGHT) as f32];
+
+    rects
+        .iter()
+        .flat_map(|&[x, y, width, height]| {
+            let corner = |x, y| TextVertex { position: [x, y], uv, color };
+            [
+                corner(x, y),
+                corner(x, y + height),
+                corner(x + width, y + height),
+                corner(x, y),
+                corner(x + width, y + height),
+                corner(x + width, y),
+            ]
+        })
+        .collect()
+}
+
+// single channel coverage atlas of every printable ascii glyph, 16 per row, and the solid cell
+fn create_atlas(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> wgpu::Texture {
+    let width = ATLAS_COLUMNS * CELL_WIDTH;
+    let height = ATLAS_ROWS * CELL_HEIGHT;
+    let mut pixels = vec![0u8; (width * height) as usize];
+
+    for (index, rows) in FONT.iter().enumerate() {
+        let cell_x = index as u32 % ATLAS_COLUMNS * CELL_WIDTH;
+        let cell_y = index as u32 / ATLAS_COLUMNS * CELL_HEIGHT;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                    pixels[((cell_y + row as u32) * width + cell_x + column) as usize] = 255;
+                }
+            }
+        }
+    }
+
+    let (cell_x, cell_y) = (SOLID_CELL % ATLAS_COLUMNS * CELL_WIDTH, SOLID_CELL / ATLAS_COLUMNS * CELL_HEIGHT);
+    for row in cell_y..cell_y + GLYPH_HEIGHT {
+        for column in cell_x..cell_x + GLYPH_WIDTH {
+            pixels[(row * width + column) as usize] = 255;
+        }
+    }
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let atlas = tracker.create_texture(device, &wgpu::TextureDescriptor {
+        label: Some("Font Atlas"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &atlas,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &pixels,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+
+    atlas
+}
+
+// 5x7 glyphs for ' ' through '~', one byte per row top to bottom, bit 4 is the leftmost pixel
+#[rustfmt::skip]
+const FONT: [[u8; GLYPH_HEIGHT as usize]; (LAST_GLYPH - FIRST_GLYPH + 1) as usize] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // '!'
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a], // '#'
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04], // '$'
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // '%'
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d], // '&'
+    [0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // '\''
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // '('
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // ')'
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00], // '*'
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08], // ','
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c], // '.'
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // '/'
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // '0'
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // '1'
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // '2'
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // '3'
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // '4'
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // '5'
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // '6'
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // '7'
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // '8'
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // '9'
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // ':'
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08], // ';'
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // '<'
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00], // '='
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // '>'
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e], // '@'
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // 'A'
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // 'B'
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // 'C'
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c], // 'D'
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // 'E'
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // 'F'
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // 'G'
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // 'H'
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // 'I'
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // 'J'
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // 'K'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // 'L'
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // 'M'
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // 'N'
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // 'O'
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // 'P'
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // 'Q'
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // 'R'
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // 'S'
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // 'T'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // 'U'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // 'V'
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // 'W'
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // 'X'
+    [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04], // 'Y'
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // 'Z'
+    [0x0e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0e], // '['
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // '\\'
+    [0x0e, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0e], // ']'
+    [0x04, 0x0a, 0x11, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f], // '_'
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x0e, 0x01, 0x0f, 0x11, 0x0f], // 'a'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1e], // 'b'
+    [0x00, 0x00, 0x0e, 0x10, 0x10, 0x11, 0x0e], // 'c'
+    [0x01, 0x01, 0x0d, 0x13, 0x11, 0x11, 0x0f], // 'd'
+    [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e], // 'e'
+    [0x06, 0x09, 0x08, 0x1c, 0x08, 0x08, 0x08], // 'f'
+    [0x00, 0x0f, 0x11, 0x11, 0x0f, 0x01, 0x0e], // 'g'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // 'h'
+    [0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x0e], // 'i'
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0c], // 'j'
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // 'k'
+    [0x0c, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // 'l'
+    [0x00, 0x00, 0x1a, 0x15, 0x15, 0x11, 0x11], // 'm'
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // 'n'
+    [0x00, 0x00, 0x0e, 0x11, 0x11, 0x11, 0x0e], // 'o'
+    [0x00, 0x00, 0x1e, 0x11, 0x1e, 0x10, 0x10], // 'p'
+    [0x00, 0x00, 0x0d, 0x13, 0x0f, 0x01, 0x01], // 'q'
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // 'r'
+    [0x00, 0x00, 0x0e, 0x10, 0x0e, 0x01, 0x1e], // 's'
+    [0x08, 0x08, 0x1c, 0x08, 0x08, 0x09, 0x06], // 't'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0d], // 'u'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0a, 0x04], // 'v'
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0a], // 'w'
+    [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11], // 'x'
+    [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e], // 'y'
+    [0x00, 0x00, 0x1f, 0x02, 0x04, 0x08, 0x1f], // 'z'
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // '{'
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // '|'
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // '}'
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // '~'
+];
@@ -0,0 +1,29 @@
+struct Uniforms {
+    projectionMatrix : mat4x4<f32>,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+@binding(1) @group(0) var fontAtlas : texture_2d<f32>;
+@binding(2) @group(0) var fontSampler : sampler;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vUv : vec2<f32>,
+    @location(1) vColor : vec4<f32>,
+};
+
+// glyph quads are given in window pixels, origin at the top left
+@vertex
+fn vs_main(@location(0) pos: vec2<f32>, @location(1) uv: vec2<f32>, @location(2) color: vec4<f32>) -> Output {
+    var output: Output;
+    output.Position = uniforms.projectionMatrix * vec4<f32>(pos, 0.0, 1.0);
+    output.vUv = uv;
+    output.vColor = color;
+    return output;
+}
+
+// the atlas only stores coverage in its red channel
+@fragment
+fn fs_main(@location(0) vUv: vec2<f32>, @location(1) vColor: vec4<f32>) -> @location(0) vec4<f32> {
+    let coverage = textureSample(fontAtlas, fontSampler, vUv).r;
+    return vec4<f32>(vColor.rgb, vColor.a * coverage);
+}
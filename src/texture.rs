@@ -0,0 +1,263 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::{blit::Blit, resource_tracker::ResourceTracker, vertex_data};
+
+// anisotropic filtering levels the anisotropy key cycles through, 1 means plain trilinear
+pub const ANISOTROPY_LEVELS: [u16; 3] = [1, 4, 16];
+
+// a sampled 2d texture together with its view and sampler. textures loaded from images carry a full
+// mip chain, which the trilinear and anisotropic samplers pick from at a distance
+pub struct Texture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+}
+
+impl Texture {
+    pub fn from_image(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker,
+        image: &image::RgbaImage, label: &str) -> Self {
+        let size = wgpu::Extent3d {
+            width: image.width(),
+            height: image.height(),
+            depth_or_array_layers: 1,
+        };
+
+        let texture = tracker.create_texture(device, &wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: mip_level_count(image.width(), image.height()),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // color textures are authored in srgb
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            // the lower mip levels are rendered from the ones above them
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width()),
+                rows_per_image: Some(image.height()),
+            },
+            size,
+        );
+        generate_mipmaps(device, queue, &texture);
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = create_sampler(device, label, 1);
+
+        Texture {
+            texture,
+            view,
+            sampler,
+        }
+    }
+
+    // 1x1 white texture, so untextured meshes can go through the textured pipeline unchanged
+    pub fn white(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker) -> Self {
+        let image = image::RgbaImage::from_pixel(1, 1, image::Rgba([255, 255, 255, 255]));
+        Self::from_image(device, queue, tracker, &image, "White Texture")
+    }
+
+    // a stand in lightmap when none is given: full light in the middle fading toward the edges, like
+    // baked ambient occlusion darkening each face's corners
+    pub fn gradient_lightmap() -> image::RgbaImage {
+        const SIZE: u32 = 64;
+
+        image::RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+            let dx = (x as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+            let dy = (y as f32 + 0.5) / SIZE as f32 * 2.0 - 1.0;
+            let light = 1.0 - 0.65 * ((dx * dx + dy * dy) / 2.0).sqrt();
+            let value = (light * 255.0) as u8;
+            image::Rgba([value, value, value, 255])
+        })
+    }
+
+    // an 8x8 black and white checkerboard, the textured demo scene's texture
+    pub fn checker_image() -> image::RgbaImage {
+        const SIZE: u32 = 256;
+        const SQUARES: u32 = 8;
+
+        image::RgbaImage::from_fn(SIZE, SIZE, |x, y| {
+            let value = if (x * SQUARES / SIZE + y * SQUARES / SIZE).is_multiple_of(2) { 235 } else { 40 };
+            image::Rgba([value, value, value, 255])
+        })
+    }
+
+    pub fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("Texture Bind Group Layout"),
+        })
+    }
+
+    pub fn bind_group(&self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+        self.bind_group_with_sampler(device, layout, &self.sampler)
+    }
+
+    pub fn bind_group_with_sampler(&self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&self.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+            label: Some("Texture Bind Group"),
+        })
+    }
+}
+
+// levels in a full mip chain for a texture of this size, halving down to 1x1
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+// fills every mip level below the first by box filtering the level above it through the blit's
+// downsample pass. the views are srgb, so the texels are averaged as linear values
+fn generate_mipmaps(device: &wgpu::Device, queue: &wgpu::Queue, texture: &wgpu::Texture) {
+    if texture.mip_level_count() < 2 {
+        return;
+    }
+
+    let blit = Blit::new(device, texture.format());
+    let views: Vec<wgpu::TextureView> = (0..texture.mip_level_count())
+        .map(|level| texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Mip Level View"),
+            base_mip_level: level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        }))
+        .collect();
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Mipmap Encoder"),
+    });
+    for levels in views.windows(2) {
+        let bind_groups = blit.bind_groups(device, &levels[0]);
+        blit.draw(&mut encoder, &levels[1], &bind_groups.linear, true);
+    }
+    queue.submit(std::iter::once(encoder.finish()));
+}
+
+// which alpha a texture should hold for the blend it's drawn with:
+// - straight alpha (load_texture) with BlendState::ALPHA_BLENDING, for images as most files store
+//   them when they're sampled unfiltered, like pixel art sprites. filtering mixes the color of
+//   transparent texels into the edges, a dark halo around cutouts whose transparent parts are black
+// - premultiplied alpha (load_texture_premultiplied) with BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+//   for anything filtered, mipmapped or scaled: transparent texels are zero and add nothing
+// - premultiplied texels with straight blending multiply by alpha twice and come out too dark and
+//   too transparent at the edges; straight texels with premultiplied blending add their full color
+//   where they should be faint, a bright fringe
+pub fn load_texture(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker, path: &Path) -> anyhow::Result<Texture> {
+    let image = load_image(path)?;
+    Ok(Texture::from_image(device, queue, tracker, &image, &path.display().to_string()))
+}
+
+// load_texture with rgb multiplied by alpha on upload, for premultiplied blending. see load_texture
+pub fn load_texture_premultiplied(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker,
+    path: &Path) -> anyhow::Result<Texture> {
+    let image = premultiply(&load_image(path)?);
+    Ok(Texture::from_image(device, queue, tracker, &image, &path.display().to_string()))
+}
+
+fn load_image(path: &Path) -> anyhow::Result<image::RgbaImage> {
+    Ok(image::open(path).with_context(|| format!("failed to load {}", path.display()))?.to_rgba8())
+}
+
+// scales each texel's color by its alpha. the texture is sampled as srgb, so the multiply is done
+// on linear values to match what the gpu blends with
+pub fn premultiply(image: &image::RgbaImage) -> image::RgbaImage {
+    let mut premultiplied = image.clone();
+
+    for pixel in premultiplied.pixels_mut() {
+        let alpha = pixel[3] as f32 / 255.0;
+        for channel in 0..3 {
+            let linear = vertex_data::srgb_channel_to_linear(pixel[channel] as f32 / 255.0) * alpha;
+            pixel[channel] = (vertex_data::linear_channel_to_srgb(linear) * 255.0).round() as u8;
+        }
+    }
+
+    premultiplied
+}
+
+// repeating linear sampler. anisotropy above 1 requires every filter to be linear, which this always is
+pub fn create_sampler(device: &wgpu::Device, label: &str, anisotropy: u16) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some(label),
+        address_mode_u: wgpu::AddressMode::Repeat,
+        address_mode_v: wgpu::AddressMode::Repeat,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        anisotropy_clamp: anisotropy,
+        ..Default::default()
+    })
+}
+
+// wgpu has no max anisotropy limit, adapters either support up to 16x or ignore the clamp entirely
+pub fn anisotropy_supported(adapter: &wgpu::Adapter) -> bool {
+    adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(wgpu::DownlevelFlags::ANISOTROPIC_FILTERING)
+}
+
+// one bind group per anisotropy level the adapter allows, built up front so switching is just picking one
+pub struct AnisotropyBindGroups {
+    pub levels: Vec<(u16, wgpu::BindGroup)>,
+}
+
+impl AnisotropyBindGroups {
+    pub fn new(device: &wgpu::Device, adapter: &wgpu::Adapter, texture: &Texture, layout: &wgpu::BindGroupLayout) -> Self {
+        let supported = anisotropy_supported(adapter);
+        if !supported {
+            log::warn!("adapter does not support anisotropic filtering, only 1x is available");
+        }
+
+        let levels = ANISOTROPY_LEVELS
+            .iter()
+            .filter(|&&level| level == 1 || supported)
+            .map(|&level| {
+                let sampler = create_sampler(device, &format!("Texture Sampler {}x", level), level);
+                (level, texture.bind_group_with_sampler(device, layout, &sampler))
+            })
+            .collect();
+
+        AnisotropyBindGroups { levels }
+    }
+}
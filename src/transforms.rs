@@ -1,9 +1,14 @@
-use std::f32::consts::PI;
-use cgmath::{ortho, perspective, Matrix4, Point3, Rad, Vector3};
+use std::{f32::consts::PI, fmt, sync::Arc};
+use cgmath::{ortho, perspective, EuclideanSpace, InnerSpace, Matrix, Matrix3, Matrix4, Point3, Quaternion, Rad, Rotation3, SquareMatrix, Vector3, Vector4};
 use winit::window::Window;
 
+use crate::{error::Error, resource_tracker::ResourceTracker};
+
+// cgmath builds projections for opengl's [-1, 1] clip space depth, wgpu expects [0, 1]. this squashes
+// z accordingly and must be applied exactly once per projection: only create_perspective_projection and
+// create_projection_ortho apply it, every other projection in the crate is built through those two
+// (create_projection_with_correction can skip it on purpose). see clip_space_note for the full convention
 #[rustfmt::skip]
-#[allow(unused)]
 pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
     1.0, 0.0, 0.0, 0.0,
     0.0, 1.0, 0.0, 0.0,
@@ -11,24 +16,175 @@ pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
     0.0, 0.0, 0.5, 1.0,
 );
 
+// maps wgpu clip space depth z to w - z, so after the divide near lands on 1.0 and far on 0.0.
+// applied after OPENGL_TO_WGPU_MATRIX; cgmath's perspective() requires near < far, so the planes
+// are swapped here instead of in the perspective construction
+#[rustfmt::skip]
+pub const REVERSED_Z_MATRIX: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0,  0.0, 0.0,
+    0.0, 1.0,  0.0, 0.0,
+    0.0, 0.0, -1.0, 0.0,
+    0.0, 0.0,  1.0, 1.0,
+);
+
+// which end of the depth range is near. the perspective divide crowds most of the depth values
+// close to the near plane; with standard z those land where a float depth buffer is coarsest (near 1.0),
+// with reversed z they land near 0.0 where floats are densest, which roughly cancels out and keeps
+// distant surfaces from z-fighting. the benefit needs a float depth format, see DEPTH_FORMAT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepthMode {
+    Standard,
+    Reversed,
+}
+
+impl DepthMode {
+    pub fn clear_value(self) -> f32 {
+        match self {
+            DepthMode::Standard => 1.0,
+            DepthMode::Reversed => 0.0,
+        }
+    }
+
+    pub fn compare(self) -> wgpu::CompareFunction {
+        match self {
+            DepthMode::Standard => wgpu::CompareFunction::LessEqual,
+            DepthMode::Reversed => wgpu::CompareFunction::GreaterEqual,
+        }
+    }
+
+    // bias is given as magnitudes, this points it toward the camera: toward 0 for standard depth and
+    // toward 1 for reversed
+    pub fn toward_camera(self, bias: wgpu::DepthBiasState) -> wgpu::DepthBiasState {
+        match self {
+            DepthMode::Standard => wgpu::DepthBiasState {
+                constant: -bias.constant,
+                slope_scale: -bias.slope_scale,
+                clamp: -bias.clamp,
+            },
+            DepthMode::Reversed => bias,
+        }
+    }
+
+    // the projection matrix for this mode from a standard wgpu projection
+    pub fn projection(self, projection: Matrix4<f32>) -> Matrix4<f32> {
+        match self {
+            DepthMode::Standard => projection,
+            DepthMode::Reversed => REVERSED_Z_MATRIX * projection,
+        }
+    }
+
+    // view distance of a depth buffer value written with a perspective projection from near to far,
+    // the same mapping depth_view.wgsl draws
+    pub fn linearize(self, depth: f32, near: f32, far: f32) -> f32 {
+        let depth = match self {
+            DepthMode::Standard => depth,
+            DepthMode::Reversed => 1.0 - depth,
+        };
+
+        near * far / (far - depth * (far - near))
+    }
+}
+
+// vertical field of view of the perspective projection
+pub const FOVY: Rad<f32> = Rad(2.0 * PI / 5.0);
+// near and far planes of the default perspective projection
+pub const Z_NEAR: f32 = 0.1;
+pub const Z_FAR: f32 = 100.0;
+
+// the coordinate conventions the transforms in this module follow, printed with the matrix dump
+pub fn clip_space_note() -> &'static str {
+    "world and view space are right handed, y up, and the camera looks down -z (look_at_rh). \
+     projections map into wgpu clip space: x and y in [-w, w] with +y up, z in [0, w], so after the \
+     perspective divide the near plane lands on depth 0 and the far plane on depth 1 (swapped with \
+     reversed z). cgmath's perspective and ortho target opengl's z in [-w, w], so OPENGL_TO_WGPU_MATRIX \
+     is applied once, inside create_perspective_projection and create_projection_ortho. framebuffer \
+     coordinates have +y down, which wgpu handles in the viewport transform."
+}
+
 pub struct InitWgpu<'window> {
     pub instance: wgpu::Instance,
-    pub surface: wgpu::Surface<'window>,
+    pub adapter: wgpu::Adapter,
+    // None while the application is suspended, see drop_surface
+    pub surface: Option<wgpu::Surface<'window>>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub config: wgpu::SurfaceConfiguration,
+    // None for a headless init, which never has a surface
+    pub config: Option<wgpu::SurfaceConfiguration>,
     pub size: winit::dpi::PhysicalSize<u32>,
+    pub tracker: ResourceTracker,
+}
+
+// how many frames the cpu may queue ahead of the gpu, the surface's desired_maximum_frame_latency and
+// the size of uniform_ring::UniformRing. one frame gives the lowest input latency, but the cpu waits on
+// the gpu every frame and both sit idle part of the time; two lets the cpu record the next frame while
+// the gpu draws the current one, for better throughput at one more frame between input and screen
+pub const DEFAULT_FRAMES_IN_FLIGHT: u32 = 2;
+
+// backends init_wgpu picks its adapter from
+pub const BACKENDS: wgpu::Backends = wgpu::Backends::VULKAN;
+
+// one line per adapter wgpu can see on any backend, for working out why init_wgpu finds none: an
+// adapter listed under a backend other than BACKENDS is invisible to it
+pub fn adapter_report() -> String {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let adapters = instance.enumerate_adapters(wgpu::Backends::all());
+    if adapters.is_empty() {
+        return "no adapters found on any backend".to_string();
+    }
+
+    adapters
+        .iter()
+        .map(|adapter| {
+            let info = adapter.get_info();
+            let usable = if BACKENDS.contains(wgpu::Backends::from(info.backend)) { "" } else { " (not used, backend disabled)" };
+            format!("{} - {:?} {:?}, driver {} {}{}", info.name, info.backend, info.device_type, info.driver, info.driver_info, usable)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// the surface's preferred format, its first one. capability lists can come back empty on virtual
+// displays and remote sessions, which is an error instead of a panic
+pub fn pick_surface_format(capabilities: &wgpu::SurfaceCapabilities) -> Result<wgpu::TextureFormat, Error> {
+    capabilities.formats.first().copied().ok_or(Error::NoSurfaceFormat)
+}
+
+// premultiplied when transparent asks for it and the surface has it, the first supported mode otherwise
+pub fn pick_alpha_mode(capabilities: &wgpu::SurfaceCapabilities, transparent: bool) -> Result<wgpu::CompositeAlphaMode, Error> {
+    if transparent {
+        if capabilities.alpha_modes.contains(&wgpu::CompositeAlphaMode::PreMultiplied) {
+            return Ok(wgpu::CompositeAlphaMode::PreMultiplied);
+        }
+        log::warn!("surface does not support premultiplied alpha (supports {:?}), the window stays opaque",
+            capabilities.alpha_modes);
+    }
+    capabilities.alpha_modes.first().copied().ok_or(Error::NoAlphaMode)
 }
 
 impl<'window> InitWgpu<'window> {
-    pub async fn init_wgpu(window: &'window Window) -> Self {
+    // transparent asks the compositor to blend the surface with the desktop through premultiplied alpha, the
+    // window has to be built transparent as well. falls back to the first supported alpha mode when unavailable.
+    // safe_mode requests no optional device features, for drivers that fail on them. frames_in_flight is
+    // the surface's frame latency, see DEFAULT_FRAMES_IN_FLIGHT. backends are the ones adapters are
+    // looked for on, normally BACKENDS
+    pub async fn init_wgpu(window: &'window Window, transparent: bool, safe_mode: bool, frames_in_flight: u32,
+        backends: wgpu::Backends) -> Result<Self, Error> {
+        // some window managers report (0, 0) or a stale size until the window is actually mapped, and
+        // only send the real size with the first Resized event. a zero sized surface can't be configured
+        // and would make the aspect ratio divide by zero, so start at 1x1 at least; resize rebuilds the
+        // projection and render targets once the real size arrives
         let size = window.inner_size();
+        let size = winit::dpi::PhysicalSize::new(size.width.max(1), size.height.max(1));
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::VULKAN,
+            backends,
             ..Default::default()
         });
 
-        let surface = unsafe { instance.create_surface(window) }.unwrap();
+        let surface = instance.create_surface(window)?;
 
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptionsBase { 
@@ -37,24 +193,34 @@ impl<'window> InitWgpu<'window> {
                 compatible_surface: Some(&surface) 
             })
             .await
-            .unwrap();
+            .ok_or(Error::NoAdapter)?;
+
+        // wireframe rendering needs POLYGON_MODE_LINE, msaa above 4x needs the adapter specific format
+        // features and the gpu frame time TIMESTAMP_QUERY, only request them where the adapter has them
+        let required_features = if safe_mode {
+            wgpu::Features::empty()
+        } else {
+            adapter.features() & (wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+                | wgpu::Features::TIMESTAMP_QUERY)
+        };
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: None,
-                required_features: wgpu::Features::empty(),
+                required_features,
                 required_limits: wgpu::Limits::default()
             }, None)
-            .await
-            .unwrap();
+            .await?;
 
         let surface_capabilities = surface.get_capabilities(&adapter);
+        let format = pick_surface_format(&surface_capabilities)?;
+        let alpha_mode = pick_alpha_mode(&surface_capabilities, transparent)?;
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface_capabilities.formats[0],
-            alpha_mode: surface_capabilities.alpha_modes[0],
-            desired_maximum_frame_latency: 2,
+            format,
+            alpha_mode,
+            desired_maximum_frame_latency: frames_in_flight,
             present_mode: wgpu::PresentMode::Fifo,
             view_formats: vec![],
             height: size.height,
@@ -63,31 +229,286 @@ impl<'window> InitWgpu<'window> {
 
         surface.configure(&device, &config);
 
-        InitWgpu  {
-            config,
+        Ok(InitWgpu  {
+            adapter,
+            config: Some(config),
             device,
             instance,
             queue,
             size,
-            surface
+            surface: Some(surface),
+            tracker: ResourceTracker::default(),
+        })
+    }
+
+    // an adapter and device without any window or surface, for compute work and offscreen rendering.
+    // no optional features are requested
+    pub async fn init_headless(backends: wgpu::Backends) -> Result<InitWgpu<'static>, Error> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            ..Default::default()
+        });
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptionsBase {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await
+            .ok_or(Error::NoAdapter)?;
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default()
+            }, None)
+            .await?;
+
+        Ok(InitWgpu {
+            adapter,
+            config: None,
+            device,
+            instance,
+            queue,
+            size: winit::dpi::PhysicalSize::new(0, 0),
+            surface: None,
+            tracker: ResourceTracker::default(),
+        })
+    }
+
+    // some platforms (android, and wayland when the window is hidden) invalidate the native surface when
+    // the application is suspended; rendering into the old one fails or crashes, so it is dropped on
+    // Event::Suspended and recreated with recreate_surface on Event::Resumed
+    pub fn drop_surface(&mut self) {
+        self.surface = None;
+    }
+
+    // a new surface for window configured with the current config, the adapter and device are kept
+    pub fn recreate_surface(&mut self, window: &'window Window) {
+        let Some(config) = &mut self.config else {
+            log::error!("a headless init has no surface to recreate");
+            return;
+        };
+
+        let surface = match self.instance.create_surface(window) {
+            Ok(surface) => surface,
+            Err(e) => {
+                log::error!("could not recreate the surface: {}", e);
+                return;
+            }
+        };
+
+        let size = window.inner_size();
+        if size.width > 0 && size.height > 0 {
+            self.size = size;
+            config.width = size.width;
+            config.height = size.height;
+        }
+
+        surface.configure(&self.device, config);
+        self.surface = Some(surface);
+    }
+
+    // a surface for another window, presenting with this device and queue. it uses the main surface's
+    // format so pipelines built for the main window can draw to it; None when this window can't present
+    // that format
+    pub fn add_surface(&self, window: Arc<Window>) -> Option<WindowSurface> {
+        let Some(main_config) = &self.config else {
+            log::error!("a headless init has no surface format to match");
+            return None;
+        };
+
+        let surface = match self.instance.create_surface(window.clone()) {
+            Ok(surface) => surface,
+            Err(e) => {
+                log::error!("could not create a surface for the window: {}", e);
+                return None;
+            }
+        };
+
+        let surface_capabilities = surface.get_capabilities(&self.adapter);
+        if !surface_capabilities.formats.contains(&main_config.format) {
+            log::error!("window surface does not support {:?} (supports {:?})", main_config.format, surface_capabilities.formats);
+            return None;
+        }
+
+        let size = window.inner_size();
+        let alpha_mode = if surface_capabilities.alpha_modes.contains(&main_config.alpha_mode) {
+            main_config.alpha_mode
+        } else {
+            match surface_capabilities.alpha_modes.first() {
+                Some(alpha_mode) => *alpha_mode,
+                None => {
+                    log::error!("{}", Error::NoAlphaMode);
+                    return None;
+                }
+            }
+        };
+        let config = wgpu::SurfaceConfiguration {
+            alpha_mode,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            ..main_config.clone()
+        };
+
+        surface.configure(&self.device, &config);
+
+        Some(WindowSurface {
+            window,
+            surface,
+            config,
+        })
+    }
+}
+
+// a window added with InitWgpu::add_surface. the surface holds an Arc of its window rather than a borrow,
+// so it is 'static and the window can be opened and closed while the event loop runs
+pub struct WindowSurface {
+    pub window: Arc<Window>,
+    pub surface: wgpu::Surface<'static>,
+    pub config: wgpu::SurfaceConfiguration,
+}
+
+impl WindowSurface {
+    pub fn resize(&mut self, device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) {
+        if size.width > 0 && size.height > 0 {
+            self.config.width = size.width;
+            self.config.height = size.height;
+            self.surface.configure(device, &self.config);
         }
     }
+
+    pub fn aspect(&self) -> f32 {
+        self.config.width as f32 / self.config.height as f32
+    }
 }
 
 pub fn create_view(camera_position: Point3<f32>, look_direction: Point3<f32>, up_direction: Vector3<f32>) -> Matrix4<f32> {
     Matrix4::look_at_rh(camera_position, look_direction, up_direction)
 }
 
+// the look direction and up vector of each cube map face, in layer order +x, -x, +y, -y, +z, -z. the
+// ups follow the cube map convention wgpu shares with vulkan and d3d: the side faces are upside down
+// (up is -y) and the top and bottom faces have +z and -z up
+pub const CUBEMAP_FACES: [(Vector3<f32>, Vector3<f32>); 6] = [
+    (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+    (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+    (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+];
+
+// view matrices for rendering the six faces of a cube map seen from position, one per layer in
+// CUBEMAP_FACES order. pair with cubemap_projection
+pub fn cubemap_view_matrices(position: Point3<f32>) -> [Matrix4<f32>; 6] {
+    CUBEMAP_FACES.map(|(direction, up)| create_view(position, position + direction, up))
+}
+
+// the square 90 degree projection whose six views cover a cube map without gaps or overlap
+pub fn cubemap_projection(near: f32, far: f32) -> Matrix4<f32> {
+    create_perspective_projection(Rad(PI / 2.0), 1.0, near, far)
+}
+
+// the camera position orbited around target: yaw radians about up, then pitch radians up or down. the
+// pitch is dropped rather than taking the camera over the pole, where look_at_rh breaks down
+pub fn orbit(camera_position: Point3<f32>, target: Point3<f32>, up_direction: Vector3<f32>, yaw: f32, pitch: f32) -> Point3<f32> {
+    let up = up_direction.normalize();
+    let offset = Quaternion::from_axis_angle(up, Rad(yaw)) * (camera_position - target);
+
+    let right = offset.cross(up);
+    if right.magnitude2() == 0.0 {
+        return target + offset;
+    }
+    let pitched = Quaternion::from_axis_angle(right.normalize(), Rad(pitch)) * offset;
+
+    // a camera already inside the limit, like one snapped straight above, may still pitch back out
+    let pitched_cosine = pitched.normalize().dot(up).abs();
+    if pitched_cosine > ORBIT_POLE_LIMIT && pitched_cosine > offset.normalize().dot(up).abs() {
+        target + offset
+    } else {
+        target + pitched
+    }
+}
+
+// cosine of the closest orbit may get to straight above or below the target
+const ORBIT_POLE_LIMIT: f32 = 0.995;
+
 pub fn create_projection(aspect: f32, is_perspective: bool) -> Matrix4<f32> {
-    let projection_math: Matrix4<f32>;
+    create_projection_with_correction(aspect, is_perspective, true)
+}
+
+// create_projection, optionally leaving out OPENGL_TO_WGPU_MATRIX to show what the correction is for.
+// without it depth comes out in opengl's [-1, 1] range: wgpu clips everything below 0, so the nearer part
+// of the view volume disappears, and what remains only uses half of the depth range
+pub fn create_projection_with_correction(aspect: f32, is_perspective: bool, apply_clip_correction: bool) -> Matrix4<f32> {
+    create_projection_with_fovy(FOVY, aspect, is_perspective, apply_clip_correction)
+}
+
+// create_projection_with_correction with a field of view other than FOVY. ortho ignores fovy
+pub fn create_projection_with_fovy(fovy: Rad<f32>, aspect: f32, is_perspective: bool, apply_clip_correction: bool) -> Matrix4<f32> {
+    if !apply_clip_correction {
+        return if is_perspective {
+            perspective(fovy, aspect, Z_NEAR, Z_FAR)
+        } else {
+            ortho(-4.0, 4.0, -3.0, 3.0, -1.0, 6.0)
+        };
+    }
 
     if is_perspective {
-        projection_math = OPENGL_TO_WGPU_MATRIX * perspective(Rad(2.0 * PI / 5.0), aspect, 0.1, 100.0);
+        create_perspective_projection(fovy, aspect, Z_NEAR, Z_FAR)
     } else {
-        projection_math = OPENGL_TO_WGPU_MATRIX * ortho(-4.0, 4.0, -3.0,  3.0, -1.0, 6.0);
+        create_projection_ortho(-4.0, 4.0, -3.0,  3.0, -1.0, 6.0)
     }
+}
+
+// the dolly zoom: the field of view that keeps a subject seen with fovy from reference_distance the
+// same size from distance. the visible height at the subject, 2 * distance * tan(fovy / 2), is held
+// constant, so moving in widens the view and the background appears to stretch away
+pub fn dolly_zoom_fovy(fovy: Rad<f32>, reference_distance: f32, distance: f32) -> Rad<f32> {
+    Rad(2.0 * ((fovy.0 / 2.0).tan() * reference_distance / distance).atan())
+}
+
+// inverse of dolly_zoom_fovy: the distance at which fovy frames the subject the way
+// reference_fovy does from reference_distance
+pub fn dolly_zoom_distance(fovy: Rad<f32>, reference_fovy: Rad<f32>, reference_distance: f32) -> f32 {
+    reference_distance * (reference_fovy.0 / 2.0).tan() / (fovy.0 / 2.0).tan()
+}
+
+// this renderer is y up (right handed, -z forward), as are gltf files. blender, most cad packages and
+// many obj exports are z up with -y forward, which shows up here lying on its side. applied as the
+// rightmost factor of a model matrix this stands such a model up: a -90 degree turn about x, taking +z
+// to +y and +y to -z. it is a rotation, so its inverse is its transpose
+pub fn z_up_to_y_up() -> Matrix4<f32> {
+    Matrix4::from_angle_x(Rad(-PI / 2.0))
+}
+
+// this renderer is right handed: +x right, +y up and +z toward the viewer, the look_at_rh convention.
+// left handed engines (unity, unreal, directx samples) point +z away, so their models come out mirrored
+// front to back here. applied as the rightmost factor of a model matrix this negates the model's own z,
+// converting between the two. flipping twice is the identity. a mirror reverses every triangle's
+// winding, so a counter clockwise front face becomes clockwise: pipelines that cull have to swap
+// their front face along with it, and normals need the inverse transpose (normal_matrix) or a sign
+// correction to keep pointing outward
+pub fn flip_handedness(m: Matrix4<f32>) -> Matrix4<f32> {
+    m * Matrix4::from_nonuniform_scale(1.0, 1.0, -1.0)
+}
+
+// center and radius of the sphere through the corners of an axis aligned box
+pub fn bounding_sphere(min: [f32; 3], max: [f32; 3]) -> (Point3<f32>, f32) {
+    let (min, max) = (Point3::from(min), Point3::from(max));
+    (min.midpoint(max), (max - min).magnitude() / 2.0)
+}
 
-    projection_math
+// how far from a sphere's center the camera must be for the sphere to just fit the view. the
+// narrower of the vertical and horizontal field of view decides; at radius / sin(half angle) the
+// sphere touches the view's edges, a tangent rather than the radius across the frame
+pub fn framing_distance(radius: f32, fovy: Rad<f32>, aspect: f32) -> f32 {
+    let half_fovy = fovy.0 / 2.0;
+    let half_fovx = (half_fovy.tan() * aspect).atan();
+    radius / half_fovy.min(half_fovx).sin()
 }
 
 pub fn create_view_projection(camera_position: Point3<f32>, look_direction: Point3<f32>, up_direction: Vector3<f32>,
@@ -97,12 +518,7 @@ pub fn create_view_projection(camera_position: Point3<f32>, look_direction: Poin
     let view_mat = Matrix4::look_at_rh(camera_position, look_direction, up_direction);     
 
     // construct projection matrix
-    let project_mat:Matrix4<f32>;
-    if is_perspective {
-        project_mat = OPENGL_TO_WGPU_MATRIX * perspective(Rad(2.0*PI/5.0), aspect, 0.1, 100.0);
-    } else {
-        project_mat = OPENGL_TO_WGPU_MATRIX * ortho(-4.0, 4.0, -3.0, 3.0, -1.0, 6.0);
-    }
+    let project_mat = create_projection(aspect, is_perspective);
     
     // contruct view-projection matrix
     let view_project_mat = project_mat * view_mat;
@@ -119,6 +535,26 @@ pub fn create_projection_ortho(left: f32, right: f32, bottom: f32, top: f32, nea
     OPENGL_TO_WGPU_MATRIX * ortho(left, right, bottom, top, near, far)    
 }
 
+// an orthographic projection just fitting a circle of radius around the view's center, whatever the
+// aspect (width over height): the shorter side spans the circle and the longer one grows to keep
+// world units square, so nothing is stretched
+pub fn create_projection_ortho_fit(radius: f32, aspect: f32, near: f32, far: f32) -> Matrix4<f32> {
+    create_projection_ortho_fit_with_correction(radius, aspect, near, far, true)
+}
+
+// create_projection_ortho_fit, optionally leaving out OPENGL_TO_WGPU_MATRIX like
+// create_projection_with_correction. the volume is the same either way, only its depth range differs
+pub fn create_projection_ortho_fit_with_correction(radius: f32, aspect: f32, near: f32, far: f32,
+    apply_clip_correction: bool) -> Matrix4<f32> {
+    let (half_width, half_height) = if aspect >= 1.0 { (radius * aspect, radius) } else { (radius, radius / aspect) };
+    if apply_clip_correction {
+        create_projection_ortho(-half_width, half_width, -half_height, half_height, near, far)
+    } else {
+        ortho(-half_width, half_width, -half_height, half_height, near, far)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_view_projection_ortho(
     left: f32, 
     right: f32, 
@@ -131,10 +567,18 @@ pub fn create_view_projection_ortho(
     up_direction: Vector3<f32>
 ) -> (Matrix4<f32>, Matrix4<f32>, Matrix4<f32>) {
     let view_matrix = Matrix4::look_at_rh(camera_position, look_direction, up_direction);
-    let projection_matrix = OPENGL_TO_WGPU_MATRIX * ortho(left, right, bottom, top, near, far);
+    let projection_matrix = create_projection_ortho(left, right, bottom, top, near, far);
     let view_projection_matrix = projection_matrix * view_matrix;
 
-    return (view_matrix, projection_matrix, view_projection_matrix);
+    (view_matrix, projection_matrix, view_projection_matrix)
+}
+
+// the matrix normals go through for model: the inverse transpose of its upper 3x3, which keeps them
+// perpendicular to the surface under non-uniform scale where model itself would shear them. the result
+// isn't unit length, normalize after. a degenerate model gives the identity
+pub fn normal_matrix(model: Matrix4<f32>) -> Matrix3<f32> {
+    let linear = Matrix3::from_cols(model.x.truncate(), model.y.truncate(), model.z.truncate());
+    linear.invert().map_or(Matrix3::identity(), |inverse| inverse.transpose())
 }
 
 pub fn create_transforms(translation:[f32; 3], rotation:[f32; 3], scaling:[f32; 3]) -> Matrix4<f32> {
@@ -151,4 +595,325 @@ pub fn create_transforms(translation:[f32; 3], rotation:[f32; 3], scaling:[f32;
 
     // return final model matrix
     model_mat
-}
\ No newline at end of file
+}
+
+// extract the six clip planes (left, right, bottom, top, near, far) from a view-projection matrix.
+// each plane is (a, b, c, d) with a normalized normal pointing into the frustum, so a point p is
+// inside when dot(n, p) + d >= 0. the near plane uses wgpu's [0, 1] depth range. with a reversed z
+// projection the near and far planes trade places in the result, but the set of planes is the same.
+pub fn frustum_planes(view_projection: Matrix4<f32>) -> [Vector4<f32>; 6] {
+    let row0 = view_projection.row(0);
+    let row1 = view_projection.row(1);
+    let row2 = view_projection.row(2);
+    let row3 = view_projection.row(3);
+
+    let planes = [
+        row3 + row0,
+        row3 - row0,
+        row3 + row1,
+        row3 - row1,
+        row2,
+        row3 - row2,
+    ];
+
+    planes.map(|plane| plane / plane.truncate().magnitude())
+}
+
+// the world space corners of the volume a view-projection matrix sees, unprojected from the corners
+// of wgpu's clip volume: the first four at depth 0 and the last four at depth 1, each four going
+// counter-clockwise from bottom left as seen through the camera. depth 0 is the near plane, or the
+// far one with a reversed z projection. a singular matrix gives all corners at the origin
+pub fn frustum_corners(view_projection: Matrix4<f32>) -> [[f32; 3]; 8] {
+    let Some(inverse) = view_projection.invert() else {
+        return [[0.0; 3]; 8];
+    };
+
+    let ndc = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+    std::array::from_fn(|i| {
+        let [x, y] = ndc[i % 4];
+        let depth = if i < 4 { 0.0 } else { 1.0 };
+        let corner = inverse * Vector4::new(x, y, depth, 1.0);
+        (corner.truncate() / corner.w).into()
+    })
+}
+
+pub fn frustum_contains_sphere(planes: &[Vector4<f32>; 6], center: Point3<f32>, radius: f32) -> bool {
+    planes.iter().all(|plane| {
+        plane.x * center.x + plane.y * center.y + plane.z * center.z + plane.w >= -radius
+    })
+}
+
+// a world space ray, direction normalized
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Point3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    pub fn at(&self, distance: f32) -> Point3<f32> {
+        self.origin + self.direction * distance
+    }
+}
+
+// the ray through a window pixel (origin at the top left), from the near plane towards the far plane.
+// unprojecting both depth ends makes this work for reversed z too. None if the matrix can't be inverted
+pub fn screen_ray(view_projection: Matrix4<f32>, x: f32, y: f32, width: f32, height: f32) -> Option<Ray> {
+    let inverse = view_projection.invert()?;
+    let ndc_x = 2.0 * x / width - 1.0;
+    let ndc_y = 1.0 - 2.0 * y / height;
+
+    let unproject = |depth: f32| {
+        let point = inverse * Vector4::new(ndc_x, ndc_y, depth, 1.0);
+        Point3::from_vec(point.truncate() / point.w)
+    };
+    let (start, end) = (unproject(0.0), unproject(1.0));
+
+    Some(Ray {
+        origin: start,
+        direction: (end - start).normalize(),
+    })
+}
+
+pub fn ray_hits_sphere(ray: &Ray, center: Point3<f32>, radius: f32) -> bool {
+    let to_center = center - ray.origin;
+    let along = to_center.dot(ray.direction);
+    let closest = to_center.magnitude2() - along * along;
+    closest <= radius * radius && (along >= 0.0 || to_center.magnitude2() <= radius * radius)
+}
+
+// distance along the ray to triangle abc, either winding (moller-trumbore)
+pub fn ray_triangle(ray: &Ray, a: Point3<f32>, b: Point3<f32>, c: Point3<f32>) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let inverse_determinant = 1.0 / determinant;
+    let to_origin = ray.origin - a;
+    let u = to_origin.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = to_origin.cross(edge1);
+    let v = ray.direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let distance = edge2.dot(q) * inverse_determinant;
+    (distance > 0.0).then_some(distance)
+}
+
+// prints a matrix as a grid in the usual math layout, one row per line. cgmath stores matrices
+// column-major, so each printed column is one of the matrix's x, y, z, w vectors.
+pub struct MatrixDisplay<'a>(pub &'a Matrix4<f32>);
+
+impl fmt::Display for MatrixDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..4 {
+            let row = self.0.row(row);
+            writeln!(f, "[ {:9.4} {:9.4} {:9.4} {:9.4} ]", row.x, row.y, row.z, row.w)?;
+        }
+
+        Ok(())
+    }
+}
+
+// axis-aligned bounds of the box (min, max) after transforming it by matrix
+pub fn transform_aabb(matrix: Matrix4<f32>, min: [f32; 3], max: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+    let corners: Vec<[f32; 3]> = (0..8)
+        .map(|i| {
+            let corner = Vector4::new(
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+                1.0,
+            );
+            (matrix * corner).truncate().into()
+        })
+        .collect();
+
+    crate::vertex_data::bounding_box(&corners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    // clip space depth after the perspective divide of a view space point
+    fn depth(projection: Matrix4<f32>, view_z: f32) -> f32 {
+        let clip = projection * Vector4::new(0.0, 0.0, view_z, 1.0);
+        clip.z / clip.w
+    }
+
+    #[test]
+    fn perspective_maps_near_to_zero_and_far_to_one() {
+        let (near, far) = (0.5, 40.0);
+        let projection = create_perspective_projection(FOVY, 1.5, near, far);
+
+        // the camera looks down -z
+        assert!(depth(projection, -near).abs() < EPSILON);
+        assert!((depth(projection, -far) - 1.0).abs() < EPSILON);
+        // cgmath's own projection puts near at opengl's -1, what OPENGL_TO_WGPU_MATRIX corrects
+        assert!((depth(perspective(FOVY, 1.5, near, far), -near) + 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn ortho_fit_correction_only_changes_depth() {
+        let (near, far) = (0.5, 40.0);
+        let corrected = create_projection_ortho_fit_with_correction(3.0, 1.5, near, far, true);
+        let uncorrected = create_projection_ortho_fit_with_correction(3.0, 1.5, near, far, false);
+
+        assert_eq!(OPENGL_TO_WGPU_MATRIX * uncorrected, corrected);
+        // the same x and y extents, only z differs: near at 0 with the correction, at -1 without
+        assert_eq!((corrected.x, corrected.y), (uncorrected.x, uncorrected.y));
+        assert!(depth(corrected, -near).abs() < EPSILON);
+        assert!((depth(uncorrected, -near) + 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn framing_distance_uses_the_narrower_half_fov() {
+        // a 90 degree vertical fov at a square aspect: the sphere touches the view at radius / sin(45)
+        let square = framing_distance(2.0, Rad(PI / 2.0), 1.0);
+        assert!((square - 2.0 * 2.0_f32.sqrt()).abs() < EPSILON);
+
+        // landscape: the horizontal fov is wider, so the vertical one still decides
+        let landscape = framing_distance(2.0, Rad(PI / 2.0), 2.0);
+        assert!((landscape - square).abs() < EPSILON);
+
+        // portrait: the horizontal half fov is atan(tan(45) * 0.5), narrower than the vertical one
+        let portrait = framing_distance(2.0, Rad(PI / 2.0), 0.5);
+        assert!((portrait - 2.0 / 0.5_f32.atan().sin()).abs() < EPSILON);
+        assert!(portrait > square);
+    }
+
+    #[test]
+    fn flipping_handedness_twice_is_the_identity() {
+        let model = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0)) * Matrix4::from_angle_y(Rad(0.7));
+        assert_eq!(flip_handedness(flip_handedness(model)), model);
+        assert_eq!(flip_handedness(flip_handedness(Matrix4::identity())), Matrix4::identity());
+
+        // once mirrors z, which flips the sign of the determinant
+        assert!(flip_handedness(model).determinant() < 0.0);
+    }
+
+    #[test]
+    fn cubemap_views_look_down_each_face() {
+        let position = Point3::new(1.0, -2.0, 3.0);
+        let directions = [Vector3::unit_x(), -Vector3::unit_x(), Vector3::unit_y(), -Vector3::unit_y(), Vector3::unit_z(), -Vector3::unit_z()];
+
+        for (view, direction) in cubemap_view_matrices(position).iter().zip(directions) {
+            // the point one unit along the face's direction lands straight ahead, on view space -z
+            let ahead = view * (position + direction).to_homogeneous();
+            assert!((ahead - Vector4::new(0.0, 0.0, -1.0, 1.0)).magnitude() < EPSILON, "{:?} went to {:?}", direction, ahead);
+        }
+    }
+
+    fn capabilities(formats: Vec<wgpu::TextureFormat>, alpha_modes: Vec<wgpu::CompositeAlphaMode>) -> wgpu::SurfaceCapabilities {
+        wgpu::SurfaceCapabilities { formats, alpha_modes, ..Default::default() }
+    }
+
+    #[test]
+    fn empty_capabilities_are_errors() {
+        let empty = capabilities(Vec::new(), Vec::new());
+        assert!(matches!(pick_surface_format(&empty), Err(Error::NoSurfaceFormat)));
+        assert!(matches!(pick_alpha_mode(&empty, false), Err(Error::NoAlphaMode)));
+        assert!(matches!(pick_alpha_mode(&empty, true), Err(Error::NoAlphaMode)));
+    }
+
+    #[test]
+    fn transparent_prefers_premultiplied_alpha() {
+        let both = capabilities(vec![wgpu::TextureFormat::Bgra8UnormSrgb],
+            vec![wgpu::CompositeAlphaMode::Opaque, wgpu::CompositeAlphaMode::PreMultiplied]);
+        assert_eq!(pick_surface_format(&both).unwrap(), wgpu::TextureFormat::Bgra8UnormSrgb);
+        assert_eq!(pick_alpha_mode(&both, true).unwrap(), wgpu::CompositeAlphaMode::PreMultiplied);
+        assert_eq!(pick_alpha_mode(&both, false).unwrap(), wgpu::CompositeAlphaMode::Opaque);
+
+        // without premultiplied alpha the window falls back to the first mode and stays opaque
+        let opaque = capabilities(Vec::new(), vec![wgpu::CompositeAlphaMode::Opaque]);
+        assert_eq!(pick_alpha_mode(&opaque, true).unwrap(), wgpu::CompositeAlphaMode::Opaque);
+    }
+
+    // doubles a storage buffer in a compute pass and reads it back. machines without any adapter skip it
+    #[test]
+    fn headless_compute_round_trip() {
+        use wgpu::util::DeviceExt;
+
+        let init = match pollster::block_on(InitWgpu::init_headless(wgpu::Backends::all())) {
+            Ok(init) => init,
+            Err(Error::NoAdapter) => {
+                eprintln!("no adapter, skipping the headless compute test");
+                return;
+            }
+            Err(e) => panic!("{}", e),
+        };
+        let device = &init.device;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Test Compute Shader"),
+            source: wgpu::ShaderSource::Wgsl("
+                @binding(0) @group(0) var<storage, read_write> values : array<u32>;
+
+                @compute @workgroup_size(64)
+                fn main(@builtin(global_invocation_id) id : vec3<u32>) {
+                    if (id.x < arrayLength(&values)) {
+                        values[id.x] = values[id.x] * 2u;
+                    }
+                }
+            ".into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Test Compute Pipeline"),
+            layout: None,
+            module: &shader,
+            entry_point: "main",
+        });
+
+        let input: Vec<u32> = (0..100).collect();
+        let size = (input.len() * std::mem::size_of::<u32>()) as wgpu::BufferAddress;
+        let storage = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Test Storage Buffer"),
+            contents: bytemuck::cast_slice(&input),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Test Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Test Bind Group"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: storage.as_entire_binding(),
+            }],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups((input.len() as u32).div_ceil(64), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&storage, 0, &readback, 0, size);
+        init.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.expect("failed to map the readback buffer"));
+        device.poll(wgpu::Maintain::Wait);
+        let output: Vec<u32> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+
+        let expected: Vec<u32> = input.iter().map(|value| value * 2).collect();
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,558 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Deg, Matrix4, MetricSpace, Point3, SquareMatrix};
+
+use crate::{mesh::{self, Mesh}, render_target, resource_tracker::ResourceTracker, texture::{self, Texture}, transforms::DepthMode, vertex_data};
+
+const QUAD_TEXTURE_SIZE: u32 = 32;
+
+// the overlapping cubes of TranslucentCubes, center and straight alpha color. listed out of depth
+// order from any side on purpose, so drawing them in creation order visibly blends wrong
+pub const TRANSLUCENT_CUBES: [([f32; 3], [f32; 4]); 4] = [
+    ([-2.0, 0.7, 0.6], [0.2, 0.9, 0.3, 0.5]),
+    ([-3.0, 0.3, -0.6], [1.0, 0.9, 0.2, 0.5]),
+    ([-1.5, 0.9, 1.2], [0.2, 0.4, 1.0, 0.5]),
+    ([-2.5, 0.5, 0.0], [1.0, 0.2, 0.2, 0.5]),
+];
+const TRANSLUCENT_CUBE_SIZE: f32 = 0.4;
+
+// the self overlapping torus of TranslucentMesh: center, tilt toward the camera's default position
+// in degrees about x, and straight alpha color
+const TRANSLUCENT_TORUS_POSITION: [f32; 3] = [0.0, 2.2, -2.5];
+const TRANSLUCENT_TORUS_TILT: f32 = 60.0;
+const TRANSLUCENT_TORUS_COLOR: [f32; 4] = [0.6, 0.8, 1.0, 0.45];
+
+// how the translucent quad's texture stores color and how it is blended. straight alpha keeps color
+// and coverage separate, which is what most image files hold and is easy to edit, but filtering mixes
+// in the color of fully transparent texels, so magnified edges pick up a dark fringe from them.
+// premultiplied alpha stores color already scaled by coverage, so transparent texels contribute
+// nothing when filtered and the blend is a single add; prefer it for anything filtered, mipmapped or
+// composited more than once, and keep straight alpha for unfiltered sprites or authoring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphaMode {
+    Straight,
+    Premultiplied,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct QuadVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+unsafe impl Pod for QuadVertex {}
+unsafe impl Zeroable for QuadVertex {}
+
+impl QuadVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// a unit quad with a cutout texture, drawn after the opaque scene with depth testing but
+// without depth writes. each alpha mode has its own pipeline and its own copy of the texture.
+pub struct TranslucentQuad {
+    pub straight_pipeline: wgpu::RenderPipeline,
+    pub premultiplied_pipeline: wgpu::RenderPipeline,
+    pub vertex_buffer: wgpu::Buffer,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_bind_group: wgpu::BindGroup,
+    pub straight_bind_group: wgpu::BindGroup,
+    pub premultiplied_bind_group: wgpu::BindGroup,
+}
+
+impl TranslucentQuad {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, tracker: &ResourceTracker, format: wgpu::TextureFormat,
+        sample_count: u32, depth_mode: DepthMode) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Translucent Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("translucent.wgsl").into()),
+        });
+
+        let vertices = [
+            QuadVertex { position: [-1.0, -1.0, 0.0], uv: [0.0, 1.0] },
+            QuadVertex { position: [ 1.0, -1.0, 0.0], uv: [1.0, 1.0] },
+            QuadVertex { position: [ 1.0,  1.0, 0.0], uv: [1.0, 0.0] },
+            QuadVertex { position: [-1.0, -1.0, 0.0], uv: [0.0, 1.0] },
+            QuadVertex { position: [ 1.0,  1.0, 0.0], uv: [1.0, 0.0] },
+            QuadVertex { position: [-1.0,  1.0, 0.0], uv: [0.0, 0.0] },
+        ];
+        let vertex_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mvp: [[f32; 4]; 4] = Matrix4::identity().into();
+        let uniform_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[mvp]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Translucent Uniform Bind Group Layout"),
+        });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Translucent Uniform Bind Group"),
+        });
+
+        let texture_bind_group_layout = Texture::bind_group_layout(device);
+        let straight_image = cutout_image();
+        let premultiplied_image = texture::premultiply(&straight_image);
+        let straight_texture = Texture::from_image(device, queue, tracker, &straight_image, "Straight Alpha Texture");
+        let premultiplied_texture = Texture::from_image(device, queue, tracker, &premultiplied_image, "Premultiplied Alpha Texture");
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Translucent Pipeline Layout"),
+            bind_group_layouts: &[&uniform_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, blend| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[QuadVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(blend),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        TranslucentQuad {
+            straight_pipeline: create_pipeline("Straight Alpha Pipeline", wgpu::BlendState::ALPHA_BLENDING),
+            premultiplied_pipeline: create_pipeline("Premultiplied Alpha Pipeline", wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            vertex_buffer,
+            uniform_buffer,
+            uniform_bind_group,
+            straight_bind_group: straight_texture.bind_group(device, &texture_bind_group_layout),
+            premultiplied_bind_group: premultiplied_texture.bind_group(device, &texture_bind_group_layout),
+        }
+    }
+
+    // draws a loaded image instead of cutout_image, as loaded with texture::load_texture and
+    // texture::load_texture_premultiplied
+    pub fn set_textures(&mut self, device: &wgpu::Device, straight: &Texture, premultiplied: &Texture) {
+        let layout = Texture::bind_group_layout(device);
+        self.straight_bind_group = straight.bind_group(device, &layout);
+        self.premultiplied_bind_group = premultiplied.bind_group(device, &layout);
+    }
+
+    pub fn write_mvp(&self, queue: &wgpu::Queue, mvp: Matrix4<f32>) {
+        let mvp: [[f32; 4]; 4] = mvp.into();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[mvp]));
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, mode: AlphaMode) {
+        let (pipeline, bind_group) = match mode {
+            AlphaMode::Straight => (&self.straight_pipeline, &self.straight_bind_group),
+            AlphaMode::Premultiplied => (&self.premultiplied_pipeline, &self.premultiplied_bind_group),
+        };
+
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_bind_group(1, bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.draw(0..6, 0..1);
+    }
+}
+
+// the order translucent objects are drawn in. blending over what is already drawn is only correct
+// back to front, creation order is there to show what goes wrong without sorting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawOrder {
+    BackToFront,
+    Creation,
+}
+
+// indices of positions ordered from the farthest from camera_pos to the nearest. this sorts whole
+// objects by their centers, not pixels: objects that intersect, or a large one and a small one
+// overlapping in depth, can still blend in the wrong order where they overlap, that needs an order
+// independent technique like depth peeling or weighted blending
+pub fn sort_by_depth(positions: &[[f32; 3]], camera_pos: Point3<f32>) -> Vec<usize> {
+    let distances: Vec<f32> = positions.iter().map(|p| camera_pos.distance2(Point3::from(*p))).collect();
+    let mut order: Vec<usize> = (0..positions.len()).collect();
+    order.sort_by(|a, b| distances[*b].total_cmp(&distances[*a]));
+    order
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CubeVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+}
+
+unsafe impl Pod for CubeVertex {}
+unsafe impl Zeroable for CubeVertex {}
+
+impl CubeVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<CubeVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CubeInstance {
+    pub model: [[f32; 4]; 4],
+    pub color: [f32; 4],
+}
+
+unsafe impl Pod for CubeInstance {}
+unsafe impl Zeroable for CubeInstance {}
+
+impl CubeInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4, 6 => Float32x4
+    ];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<CubeInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// the TRANSLUCENT_CUBES drawn blended as instances of one cube, in the order write puts them in the
+// instance buffer: instances of a draw are blended in order, so sorting the buffer sorts the cubes.
+// depth tested without depth writes like the quad, back faces culled so each cube only shows its
+// front
+pub struct TranslucentCubes {
+    pub pipeline: wgpu::RenderPipeline,
+    pub vertex_buffer: wgpu::Buffer,
+    pub instance_buffer: wgpu::Buffer,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_bind_group: wgpu::BindGroup,
+    pub positions: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+}
+
+impl TranslucentCubes {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode) -> Self {
+        let vertices: Vec<CubeVertex> = vertex_data::cube_positions()
+            .iter()
+            .zip(vertex_data::cube_normals())
+            .map(|(p, n)| CubeVertex {
+                position: [p[0] as f32, p[1] as f32, p[2] as f32],
+                normal: [n[0] as f32, n[1] as f32, n[2] as f32],
+            })
+            .collect();
+        let vertex_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Cubes Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let instance_buffer = tracker.create_buffer(device, &wgpu::BufferDescriptor {
+            label: Some("Translucent Cubes Instance Buffer"),
+            size: (TRANSLUCENT_CUBES.len() * std::mem::size_of::<CubeInstance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let view_projection: [[f32; 4]; 4] = Matrix4::identity().into();
+        let uniform_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Cubes Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[view_projection]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (pipeline, uniform_bind_group_layout) = cube_shader_pipeline(device, format, sample_count, depth_mode,
+            Some(wgpu::Face::Back), "Translucent Cubes Pipeline");
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Translucent Cubes Uniform Bind Group"),
+        });
+
+        TranslucentCubes {
+            pipeline,
+            vertex_buffer,
+            instance_buffer,
+            uniform_buffer,
+            uniform_bind_group,
+            positions: TRANSLUCENT_CUBES.iter().map(|(position, _)| *position).collect(),
+            colors: TRANSLUCENT_CUBES.iter().map(|(_, color)| *color).collect(),
+        }
+    }
+
+    // the camera uniform and the instances in the given order, back to front is sorted for camera_pos.
+    // has to be rewritten whenever the camera moves
+    pub fn write(&self, queue: &wgpu::Queue, view_projection: Matrix4<f32>, camera_pos: Point3<f32>, order: DrawOrder) {
+        let view_projection: [[f32; 4]; 4] = view_projection.into();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[view_projection]));
+
+        let indices = match order {
+            DrawOrder::BackToFront => sort_by_depth(&self.positions, camera_pos),
+            DrawOrder::Creation => (0..self.positions.len()).collect(),
+        };
+        let instances: Vec<CubeInstance> = indices
+            .into_iter()
+            .map(|i| CubeInstance {
+                model: (Matrix4::from_translation(self.positions[i].into()) * Matrix4::from_scale(TRANSLUCENT_CUBE_SIZE)).into(),
+                color: self.colors[i],
+            })
+            .collect();
+        queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        pass.draw(0..36, 0..self.positions.len() as u32);
+    }
+}
+
+// TRANSLUCENT_TORUS_* drawn blended with both faces, so its far side shows through its near side and
+// the mesh overlaps itself from almost any view. instances only sort whole objects, so in back to
+// front order write re-sorts the triangles in the index buffer instead, see mesh::sort_triangles.
+// shares the cubes' shader, the tilt is baked into the vertices since the shader has no normal matrix
+pub struct TranslucentMesh {
+    pub pipeline: wgpu::RenderPipeline,
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub instance_buffer: wgpu::Buffer,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_bind_group: wgpu::BindGroup,
+    // world space, for sorting
+    pub positions: Vec<[f32; 3]>,
+    // in creation order
+    pub indices: Vec<u32>,
+}
+
+impl TranslucentMesh {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode) -> Self {
+        let model = Matrix4::from_translation(TRANSLUCENT_TORUS_POSITION.into()) * Matrix4::from_angle_x(Deg(TRANSLUCENT_TORUS_TILT));
+        let torus = mesh::torus(0.7, 0.28, 32, 16, [1.0; 3]);
+        let Mesh { vertices, indices } = mesh::merge(&[(model, &torus)]);
+
+        let positions: Vec<[f32; 3]> = vertices.iter().map(|v| [v.position[0], v.position[1], v.position[2]]).collect();
+        let cube_vertices: Vec<CubeVertex> = vertices
+            .iter()
+            .zip(&positions)
+            .map(|(v, position)| CubeVertex { position: *position, normal: v.normal })
+            .collect();
+        let vertex_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Mesh Vertex Buffer"),
+            contents: bytemuck::cast_slice(&cube_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Mesh Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+        });
+        let instance = CubeInstance {
+            model: Matrix4::identity().into(),
+            color: TRANSLUCENT_TORUS_COLOR,
+        };
+        let instance_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Mesh Instance Buffer"),
+            contents: bytemuck::cast_slice(&[instance]),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let view_projection: [[f32; 4]; 4] = Matrix4::identity().into();
+        let uniform_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Translucent Mesh Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[view_projection]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (pipeline, uniform_bind_group_layout) = cube_shader_pipeline(device, format, sample_count, depth_mode, None,
+            "Translucent Mesh Pipeline");
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+            label: Some("Translucent Mesh Uniform Bind Group"),
+        });
+
+        TranslucentMesh {
+            pipeline,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            uniform_buffer,
+            uniform_bind_group,
+            positions,
+            indices,
+        }
+    }
+
+    // the camera uniform and the triangles in the given order, like TranslucentCubes::write. has to be
+    // rewritten whenever the camera moves
+    pub fn write(&self, queue: &wgpu::Queue, view_projection: Matrix4<f32>, camera_pos: Point3<f32>, order: DrawOrder) {
+        let view_projection: [[f32; 4]; 4] = view_projection.into();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[view_projection]));
+
+        match order {
+            DrawOrder::BackToFront => {
+                let sorted = mesh::sort_triangles(&self.positions, &self.indices, camera_pos);
+                queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&sorted));
+            }
+            DrawOrder::Creation => queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&self.indices)),
+        }
+    }
+
+    pub fn draw<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..self.indices.len() as u32, 0, 0..1);
+    }
+}
+
+// the alpha blended, depth tested but not depth writing pipeline of translucent_cubes.wgsl, and the
+// layout of its view projection uniform
+fn cube_shader_pipeline(device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32, depth_mode: DepthMode,
+    cull_mode: Option<wgpu::Face>, label: &str) -> (wgpu::RenderPipeline, wgpu::BindGroupLayout) {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Translucent Cubes Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("translucent_cubes.wgsl").into()),
+    });
+
+    let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+        label: Some("Translucent Cubes Uniform Bind Group Layout"),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Translucent Cubes Pipeline Layout"),
+        bind_group_layouts: &[&uniform_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[CubeVertex::desc(), CubeInstance::desc()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            cull_mode,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: render_target::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: depth_mode.compare(),
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    });
+
+    (pipeline, uniform_bind_group_layout)
+}
+
+// a two tone disc fading out over its outer rim with a hard edged star cut out of it. fully
+// transparent texels are black, like many exported images, which is where straight alpha's filtering
+// fringe comes from: the magnified cutout's edges show it as a dark halo, the fade as a dim rim
+pub fn cutout_image() -> image::RgbaImage {
+    let size = QUAD_TEXTURE_SIZE as f32;
+
+    image::RgbaImage::from_fn(QUAD_TEXTURE_SIZE, QUAD_TEXTURE_SIZE, |x, y| {
+        let dx = (x as f32 + 0.5) / size * 2.0 - 1.0;
+        let dy = (y as f32 + 0.5) / size * 2.0 - 1.0;
+        let radius = (dx * dx + dy * dy).sqrt();
+        let alpha = ((0.9 - radius) / 0.3).clamp(0.0, 1.0);
+        let star = 0.3 + 0.12 * (5.0 * dy.atan2(dx)).cos();
+
+        if alpha == 0.0 || radius < star {
+            image::Rgba([0, 0, 0, 0])
+        } else if radius < 0.55 {
+            image::Rgba([255, 230, 120, (alpha * 255.0) as u8])
+        } else {
+            image::Rgba([255, 120, 40, (alpha * 255.0) as u8])
+        }
+    })
+}
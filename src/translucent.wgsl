@@ -0,0 +1,26 @@
+struct Uniforms {
+    mvpMatrix : mat4x4<f32>,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+@binding(0) @group(1) var quadTexture : texture_2d<f32>;
+@binding(1) @group(1) var quadSampler : sampler;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vUv : vec2<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) pos: vec3<f32>, @location(1) uv: vec2<f32>) -> Output {
+    var output: Output;
+    output.Position = uniforms.mvpMatrix * vec4<f32>(pos, 1.0);
+    output.vUv = uv;
+    return output;
+}
+
+// passes the texel through unchanged, whether it is straight or premultiplied is up to the
+// texture and the blend state it is paired with
+@fragment
+fn fs_main(@location(0) vUv: vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(quadTexture, quadSampler, vUv);
+}
@@ -0,0 +1,28 @@
+struct Uniforms {
+    viewProjection : mat4x4<f32>,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vColor : vec4<f32>,
+};
+
+@vertex
+fn vs_main(@location(0) pos: vec3<f32>, @location(1) normal: vec3<f32>,
+    @location(2) model0: vec4<f32>, @location(3) model1: vec4<f32>, @location(4) model2: vec4<f32>,
+    @location(5) model3: vec4<f32>, @location(6) color: vec4<f32>) -> Output {
+    let model = mat4x4<f32>(model0, model1, model2, model3);
+    var output: Output;
+    output.Position = uniforms.viewProjection * model * vec4<f32>(pos, 1.0);
+    // a fixed key light so the faces of a cube can be told apart through each other, the cubes are
+    // only translated and uniformly scaled so the normal needs no normal matrix
+    let shade = 0.6 + 0.4 * max(dot(normal, normalize(vec3<f32>(0.4, 0.8, 0.5))), 0.0);
+    output.vColor = vec4<f32>(color.rgb * shade, color.a);
+    return output;
+}
+
+@fragment
+fn fs_main(@location(0) vColor: vec4<f32>) -> @location(0) vec4<f32> {
+    return vColor;
+}
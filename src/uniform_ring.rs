@@ -0,0 +1,57 @@
+use crate::resource_tracker::ResourceTracker;
+
+// one uniform buffer and bind group per frame in flight. each frame advances to the next slot and
+// writes all of its uniforms there, so a frame the gpu is still working through keeps the values it
+// was recorded with while the cpu fills in the next one. binding 0 of the group is the slot's buffer,
+// the other entries are shared by every slot
+pub struct UniformRing {
+    pub slots: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+    pub current: usize,
+}
+
+impl UniformRing {
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, layout: &wgpu::BindGroupLayout, contents: &[u8],
+        shared_entries: &[wgpu::BindGroupEntry], frames_in_flight: u32) -> Self {
+        let slots = (0..frames_in_flight.max(1))
+            .map(|_| {
+                let buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+                    label: Some("Uniform Ring Buffer"),
+                    contents,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+
+                let mut entries = vec![wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }];
+                entries.extend(shared_entries.iter().cloned());
+
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout,
+                    entries: &entries,
+                    label: Some("Uniform Ring Bind Group"),
+                });
+
+                (buffer, bind_group)
+            })
+            .collect();
+
+        UniformRing {
+            slots,
+            current: 0,
+        }
+    }
+
+    // moves on to the slot written for the next frame, call once per frame before writing it
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.slots.len();
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.slots[self.current].0
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.slots[self.current].1
+    }
+}
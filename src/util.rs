@@ -0,0 +1,2 @@
+// small helpers shared by several modules that don't belong to any one of them
+pub mod rng;
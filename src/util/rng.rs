@@ -0,0 +1,53 @@
+// the seed procedural content uses without --seed
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+// xorshift64*: a fast generator whose output depends only on the seed, unlike rand::thread_rng, so
+// procedural scenes come out the same on every run and platform for a given --seed. implements
+// rand's RngCore, so rand::Rng's gen_range and friends work on it. not for anything cryptographic
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    // the seed goes through splitmix64 first: xorshift never leaves an all zero state, and nearby
+    // seeds would otherwise start out correlated
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        Rng { state: if z == 0 { 1 } else { z } }
+    }
+}
+
+impl rand::RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// folds a seed to the 32 bits the shaders hash with
+pub fn seed_u32(seed: u64) -> u32 {
+    (seed ^ (seed >> 32)) as u32
+}
@@ -1,3 +1,71 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{lines::LineVertex, mesh::{self, Mesh}};
+
+// elevation colors of heightmap from the lowest point to the highest: water, grass, rock and snow
+const ELEVATION_RAMP: [[f32; 3]; 4] = [[0.15, 0.3, 0.55], [0.25, 0.55, 0.2], [0.45, 0.38, 0.3], [0.95, 0.95, 0.97]];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Vertex {
+    pub position: [f32; 4],
+    pub color: [f32; 4],
+    // position and normal of this vertex in the morph target, blended in by the morph weight uniform
+    pub morph_position: [f32; 4],
+    pub normal: [f32; 3],
+    pub morph_normal: [f32; 3],
+    pub uv: [f32; 2],
+    // second texture coordinate set, for the lightmap. a copy of uv for meshes that only have one
+    pub uv1: [f32; 2],
+    // this corner's barycentric coordinate within its triangle, for the edge highlight. only meaningful
+    // once the mesh is unwelded (see mesh::unweld), shared vertices keep the no edge default of all ones
+    pub barycentric: [f32; 3],
+}
+
+unsafe impl Pod for Vertex {}
+unsafe impl Zeroable for Vertex {}
+
+impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 8] = wgpu::vertex_attr_array![0=>Float32x4, 1=>Float32x4, 2=>Float32x4, 3=>Float32x3, 4=>Float32x3, 5=>Float32x2, 6=>Float32x2, 7=>Float32x3];
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+
+    // a vertex that does not morph, i.e. whose morph target is itself
+    pub fn new(position: [f32; 3], color: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> Self {
+        Vertex {
+            position: [position[0], position[1], position[2], 1.0],
+            color: [color[0], color[1], color[2], 1.0],
+            morph_position: [position[0], position[1], position[2], 1.0],
+            normal,
+            morph_normal: normal,
+            uv,
+            uv1: uv,
+            barycentric: [1.0; 3],
+        }
+    }
+
+    // this vertex as the shader draws it at the given morph weight, with the blend baked into
+    // position and normal
+    pub fn morphed(&self, weight: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * weight;
+        let position = [0, 1, 2, 3].map(|i| mix(self.position[i], self.morph_position[i]));
+        let normal = [0, 1, 2].map(|i| mix(self.normal[i], self.morph_normal[i]));
+
+        Vertex {
+            position,
+            morph_position: position,
+            normal,
+            morph_normal: normal,
+            ..*self
+        }
+    }
+}
+
 pub fn cube_positions() -> Vec<[i8; 3]> {
     [
         // front (0, 0, 1)
@@ -20,6 +88,72 @@ pub fn cube_positions() -> Vec<[i8; 3]> {
     ].to_vec()
 }
 
+// cube_positions() with the top face collapsed into a single apex, vertex for vertex, so it can be
+// used as a morph target of the cube: a square pyramid sharing the cube's base
+pub fn pyramid_positions() -> Vec<[i8; 3]> {
+    cube_positions()
+        .into_iter()
+        .map(|p| if p[1] > 0 { [0, 1, 0] } else { p })
+        .collect()
+}
+
+pub fn cube_normals() -> Vec<[i8; 3]> {
+    [
+        // front
+        [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1],
+
+        // right
+        [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0],
+
+        // back
+        [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1],
+
+        // left
+        [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0],
+
+        // top
+        [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0],
+
+        // bottom
+        [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0],
+    ].to_vec()
+}
+
+// normals of pyramid_positions(), vertex for vertex. the side faces lean inwards by atan(1/2),
+// the collapsed top face keeps pointing up so it blends smoothly from the cube's top
+pub fn pyramid_normals() -> Vec<[f32; 3]> {
+    let s = 1.0 / 5.0_f32.sqrt();
+    let faces = [
+        [0.0, s, 2.0 * s],
+        [2.0 * s, s, 0.0],
+        [0.0, s, -2.0 * s],
+        [-2.0 * s, s, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0],
+    ];
+
+    faces.iter().flat_map(|normal| [*normal; 6]).collect()
+}
+
+// names of the cube faces in the order cube_positions lists them, six vertices each
+pub const CUBE_FACES: [&str; 6] = ["front", "right", "back", "left", "top", "bottom"];
+
+// indices into the 36 cube vertices drawing every face except the given ones (indices into
+// CUBE_FACES). u32 to match the mesh index buffers
+pub fn cube_indices_excluding(faces: &[usize]) -> Vec<u32> {
+    (0..CUBE_FACES.len())
+        .filter(|face| !faces.contains(face))
+        .flat_map(|face| face as u32 * 6..face as u32 * 6 + 6)
+        .collect()
+}
+
+// every face maps the whole texture, upright when looking at the face from outside
+pub fn cube_uvs() -> Vec<[f32; 2]> {
+    let face = [[0.0, 1.0], [1.0, 1.0], [0.0, 0.0], [0.0, 0.0], [1.0, 1.0], [1.0, 0.0]];
+
+    face.iter().cycle().take(36).copied().collect()
+}
+
 pub fn cube_colors() -> Vec<[i8; 3]> {
     [
         // front - blue
@@ -40,4 +174,296 @@ pub fn cube_colors() -> Vec<[i8; 3]> {
         // bottom - fuchsia
         [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1],
     ].to_vec()
-}
\ No newline at end of file
+}
+
+// centers of a count x count grid of cubes on the xz plane, centered at the origin
+pub fn instance_grid(count: u32, spacing: f32) -> Vec<[f32; 3]> {
+    let offset = (count as f32 - 1.0) * spacing / 2.0;
+    let mut data:Vec<[f32; 3]> = Vec::with_capacity((count * count) as usize);
+
+    for x in 0..count {
+        for z in 0..count {
+            data.push([x as f32 * spacing - offset, 0.0, z as f32 * spacing - offset]);
+        }
+    }
+
+    data
+}
+
+// closed catmull-rom spline through points, segments samples per span. the curve passes through every
+// point with a continuous tangent; the returned polyline repeats its first sample at the end to close it
+pub fn catmull_rom_loop(points: &[[f32; 3]], segments: u32) -> Vec<[f32; 3]> {
+    let n = points.len();
+    let mut data:Vec<[f32; 3]> = Vec::with_capacity(n * segments as usize + 1);
+
+    for i in 0..n {
+        let [p0, p1, p2, p3] = [(i + n - 1) % n, i, (i + 1) % n, (i + 2) % n].map(|j| points[j]);
+        for s in 0..segments {
+            let t = s as f32 / segments as f32;
+            let (t2, t3) = (t * t, t * t * t);
+            data.push([0, 1, 2].map(|axis| 0.5 * (2.0 * p1[axis]
+                + (p2[axis] - p0[axis]) * t
+                + (2.0 * p0[axis] - 5.0 * p1[axis] + 4.0 * p2[axis] - p3[axis]) * t2
+                + (3.0 * p1[axis] - p0[axis] - 3.0 * p2[axis] + p3[axis]) * t3)));
+        }
+    }
+
+    if let Some(&first) = data.first() {
+        data.push(first);
+    }
+
+    data
+}
+
+// color of the reference grids
+pub const GRID_COLOR: [f32; 3] = [0.35, 0.35, 0.4];
+// rough length of the segments approximating the polar grid's circles, so larger rings get more of them
+const POLAR_SEGMENT_LENGTH: f32 = 0.25;
+
+// square reference grid on the xz plane, lines every spacing out to half_extent on each side
+pub fn rectangular_grid(half_extent: f32, spacing: f32) -> Vec<LineVertex> {
+    let steps = (half_extent / spacing).floor() as i32;
+    let mut data:Vec<LineVertex> = Vec::with_capacity(((2 * steps + 1) * 4) as usize);
+
+    for i in -steps..=steps {
+        let offset = i as f32 * spacing;
+        // one line parallel to z and one parallel to x through each step
+        data.push(LineVertex { position: [offset, 0.0, -half_extent], color: GRID_COLOR });
+        data.push(LineVertex { position: [offset, 0.0, half_extent], color: GRID_COLOR });
+        data.push(LineVertex { position: [-half_extent, 0.0, offset], color: GRID_COLOR });
+        data.push(LineVertex { position: [half_extent, 0.0, offset], color: GRID_COLOR });
+    }
+
+    data
+}
+
+// radial reference grid on the xz plane: rings evenly spaced out to radius and spokes from the center
+pub fn polar_grid(rings: u32, spokes: u32, radius: f32) -> Vec<LineVertex> {
+    let point = |r: f32, angle: f32| LineVertex { position: [r * angle.cos(), 0.0, r * angle.sin()], color: GRID_COLOR };
+    let mut data:Vec<LineVertex> = Vec::new();
+
+    for ring in 1..=rings {
+        let r = radius * ring as f32 / rings as f32;
+        let segments = ((std::f32::consts::TAU * r / POLAR_SEGMENT_LENGTH).ceil() as u32).max(8);
+        for segment in 0..segments {
+            let angle = |s: u32| std::f32::consts::TAU * s as f32 / segments as f32;
+            data.push(point(r, angle(segment)));
+            data.push(point(r, angle(segment + 1)));
+        }
+    }
+
+    for spoke in 0..spokes {
+        let angle = std::f32::consts::TAU * spoke as f32 / spokes as f32;
+        data.push(point(0.0, angle));
+        data.push(point(radius, angle));
+    }
+
+    data
+}
+
+// a grid of width by depth unit cells centered on the origin, each vertex raised to height_fn(x, z)
+// of its x and z. normals are the smooth angle weighted ones of mesh::compute_smooth_normals and the
+// colors follow ELEVATION_RAMP from the lowest vertex to the highest, so a flat grid is all water
+pub fn heightmap(width: u32, depth: u32, height_fn: impl Fn(f32, f32) -> f32) -> Mesh {
+    let (width, depth) = (width.max(1), depth.max(1));
+    let mut positions = Vec::with_capacity(((width + 1) * (depth + 1)) as usize);
+    let mut uvs = Vec::with_capacity(positions.capacity());
+    for row in 0..=depth {
+        for column in 0..=width {
+            let x = column as f32 - width as f32 / 2.0;
+            let z = row as f32 - depth as f32 / 2.0;
+            positions.push([x, height_fn(x, z), z]);
+            uvs.push([column as f32 / width as f32, row as f32 / depth as f32]);
+        }
+    }
+
+    // counter clockwise seen from above
+    let stride = width + 1;
+    let mut indices = Vec::with_capacity((width * depth * 6) as usize);
+    for row in 0..depth {
+        for column in 0..width {
+            let a = row * stride + column;
+            let b = a + stride;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    let normals = mesh::compute_smooth_normals(&positions, &indices);
+    let (low, high) = positions.iter().fold((f32::MAX, f32::MIN), |(low, high), p| (low.min(p[1]), high.max(p[1])));
+    let range = (high - low).max(f32::EPSILON);
+    let vertices = positions
+        .iter()
+        .zip(normals)
+        .zip(uvs)
+        .map(|((position, normal), uv)| Vertex::new(*position, elevation_color((position[1] - low) / range), normal, uv))
+        .collect();
+
+    Mesh { vertices, indices }
+}
+
+// t from 0 to 1 along ELEVATION_RAMP
+fn elevation_color(t: f32) -> [f32; 3] {
+    let scaled = t.clamp(0.0, 1.0) * (ELEVATION_RAMP.len() - 1) as f32;
+    let index = (scaled as usize).min(ELEVATION_RAMP.len() - 2);
+    let f = scaled - index as f32;
+    let (a, b) = (ELEVATION_RAMP[index], ELEVATION_RAMP[index + 1]);
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * f)
+}
+
+// smooth 2d value noise in [0, 1]: random values on the integer lattice, blended with a smoothstep
+// between the four around (x, z). the same coordinates and seed always give the same value
+pub fn value_noise(x: f32, z: f32, seed: u32) -> f32 {
+    let lattice = |x: i32, z: i32| {
+        let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (z as u32).wrapping_mul(0x1656_67b1) ^ seed ^ 0x9e37_79b9;
+        h = (h ^ (h >> 15)).wrapping_mul(0x2c1b_3c6d);
+        h ^= h >> 12;
+        (h & 0xffff) as f32 / 65535.0
+    };
+    let (x0, z0) = (x.floor(), z.floor());
+    let (fx, fz) = (x - x0, z - z0);
+    let (sx, sz) = (fx * fx * (3.0 - 2.0 * fx), fz * fz * (3.0 - 2.0 * fz));
+    let (x0, z0) = (x0 as i32, z0 as i32);
+
+    let top = lattice(x0, z0) + (lattice(x0 + 1, z0) - lattice(x0, z0)) * sx;
+    let bottom = lattice(x0, z0 + 1) + (lattice(x0 + 1, z0 + 1) - lattice(x0, z0 + 1)) * sx;
+    top + (bottom - top) * sz
+}
+
+// axis-aligned bounds (min, max) of a set of positions
+pub fn bounding_box(positions: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+
+    for position in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(position[axis]);
+            max[axis] = max[axis].max(position[axis]);
+        }
+    }
+
+    (min, max)
+}
+
+// three short segments crossing at point along the world axes, colored x red, y green, z blue
+pub fn marker_lines(point: [f32; 3], size: f32) -> Vec<LineVertex> {
+    let mut lines = Vec::with_capacity(6);
+
+    for axis in 0..3 {
+        let mut color = [0.0; 3];
+        color[axis] = 1.0;
+
+        let (mut start, mut end) = (point, point);
+        start[axis] -= size;
+        end[axis] += size;
+        lines.push(LineVertex { position: start, color });
+        lines.push(LineVertex { position: end, color });
+    }
+
+    lines
+}
+
+// ends of the gradient_colors ramp, from the lowest to the highest position along the axis
+pub const GRADIENT_LOW: [f32; 3] = [0.0, 0.2, 1.0];
+pub const GRADIENT_HIGH: [f32; 3] = [1.0, 0.1, 0.0];
+
+// one color per position, blending from GRADIENT_LOW to GRADIENT_HIGH by where the position lies along
+// axis (0 = x, 1 = y, 2 = z) between the bounds of all the positions, so any mesh gets the full ramp
+pub fn gradient_colors(positions: &[[f32; 3]], axis: usize) -> Vec<[f32; 3]> {
+    let (min, max) = bounding_box(positions);
+    let extent = max[axis] - min[axis];
+
+    positions
+        .iter()
+        .map(|position| {
+            let t = if extent > 0.0 { (position[axis] - min[axis]) / extent } else { 0.5 };
+            [0, 1, 2].map(|i| GRADIENT_LOW[i] + (GRADIENT_HIGH[i] - GRADIENT_LOW[i]) * t)
+        })
+        .collect()
+}
+
+// the edges of a frustum from transforms::frustum_corners: the depth 0 and depth 1 rectangles in
+// their own colors and the four edges joining them in side_color
+pub fn frustum_lines(corners: &[[f32; 3]; 8], near_color: [f32; 3], far_color: [f32; 3], side_color: [f32; 3]) -> Vec<LineVertex> {
+    let mut data:Vec<LineVertex> = Vec::with_capacity(24);
+
+    for i in 0..4 {
+        let next = (i + 1) % 4;
+        data.push(LineVertex { position: corners[i], color: near_color });
+        data.push(LineVertex { position: corners[next], color: near_color });
+        data.push(LineVertex { position: corners[i + 4], color: far_color });
+        data.push(LineVertex { position: corners[next + 4], color: far_color });
+        data.push(LineVertex { position: corners[i], color: side_color });
+        data.push(LineVertex { position: corners[i + 4], color: side_color });
+    }
+
+    data
+}
+
+// the 12 edges of an axis-aligned box as line segments
+pub fn aabb_lines(min: [f32; 3], max: [f32; 3], color: [f32; 3]) -> Vec<LineVertex> {
+    // corner i takes x, y, z from max where bits 0, 1, 2 of i are set
+    let corner = |i: usize| LineVertex {
+        position: [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ],
+        color,
+    };
+
+    let mut data:Vec<LineVertex> = Vec::with_capacity(24);
+
+    for i in 0..8 {
+        for bit in [1, 2, 4] {
+            // each edge joins two corners differing in one bit, emit it once from the lower corner
+            if i & bit == 0 {
+                data.push(corner(i));
+                data.push(corner(i | bit));
+            }
+        }
+    }
+
+    data
+}
+
+// colors picked in an image editor or color picker are srgb encoded, but the shaders work in linear
+// space and an srgb surface encodes the result again on write. converting such colors to linear first
+// makes the framebuffer show the color that was picked; without it mid tones come out too bright.
+// pure 0 and 1 channels are the same in both encodings
+pub fn srgb_to_linear(c: [f32; 3]) -> [f32; 3] {
+    c.map(srgb_channel_to_linear)
+}
+
+pub fn linear_to_srgb(c: [f32; 3]) -> [f32; 3] {
+    c.map(linear_channel_to_srgb)
+}
+
+pub fn srgb_channel_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+pub fn linear_channel_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_round_trips() {
+        // the ends, either side of the linear segment's threshold, and mid tones
+        let values = [0.0, 1.0, 0.04045, 0.04, 0.05, 0.2, 0.5, 0.735];
+        for value in values {
+            let [linear, _, _] = srgb_to_linear([value; 3]);
+            let [srgb, _, _] = linear_to_srgb([linear; 3]);
+            assert!((srgb - value).abs() < 1e-5, "{} came back as {}", value, srgb);
+        }
+
+        assert_eq!(srgb_to_linear([0.0, 1.0, 0.0]), [0.0, 1.0, 0.0]);
+        // the threshold is where the two segments meet
+        assert!((srgb_channel_to_linear(0.04045) - 0.003_130_8).abs() < 1e-6);
+        // srgb mid gray is about a fifth of the light
+        assert!((srgb_channel_to_linear(0.5) - 0.214).abs() < 1e-3);
+    }
+}
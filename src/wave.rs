@@ -0,0 +1,164 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{render_target, resource_tracker::ResourceTracker, transforms::DepthMode, vertex_data::Vertex};
+
+// the waves' settings at startup and how far each key press moves them, as a factor
+pub const AMPLITUDE: f32 = 0.12;
+pub const FREQUENCY: f32 = 2.0;
+pub const SPEED: f32 = 0.8;
+pub const STEEPNESS: f32 = 0.6;
+pub const STEP: f32 = 1.25;
+pub const AMPLITUDE_RANGE: (f32, f32) = (0.01, 1.0);
+pub const FREQUENCY_RANGE: (f32, f32) = (0.25, 16.0);
+pub const SPEED_RANGE: (f32, f32) = (0.05, 8.0);
+
+// the plane the waves move, its size and how many cells a side: the displacement only moves
+// vertices, so the cells have to be a lot smaller than the shortest wave
+pub const PLANE_SIZE: f32 = 4.0;
+pub const PLANE_SUBDIVISIONS: u32 = 160;
+
+// matches WaveSettings in wave.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct WaveSettings {
+    amplitude: f32,
+    frequency: f32,
+    speed: f32,
+    steepness: f32,
+}
+
+unsafe impl Pod for WaveSettings {}
+unsafe impl Zeroable for WaveSettings {}
+
+// which of the settings a key steps
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WaveParameter {
+    Amplitude,
+    Frequency,
+    Speed,
+}
+
+// a flat mesh deformed on the gpu: wave.wgsl moves every vertex by a sum of gerstner waves driven by
+// the scene's time uniform and lights it with the normal of the moved surface, so the mesh itself
+// is never rewritten. the mesh is the scene's own, drawn with this pipeline instead of the cached ones
+pub struct Wave {
+    pub pipeline: wgpu::RenderPipeline,
+    pub settings_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub speed: f32,
+    pub steepness: f32,
+}
+
+impl Wave {
+    // scene_layout is the scene's uniform bind group layout, the waves use its matrices, time, fog and
+    // lights. instance_layout is the scene's instance buffer layout
+    pub fn new(device: &wgpu::Device, tracker: &ResourceTracker, format: wgpu::TextureFormat, sample_count: u32,
+        depth_mode: DepthMode, scene_layout: &wgpu::BindGroupLayout, instance_layout: wgpu::VertexBufferLayout) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Wave Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("wave.wgsl").into()),
+        });
+
+        let settings_buffer = tracker.create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Wave Settings Buffer"),
+            contents: bytemuck::cast_slice(&[WaveSettings { amplitude: AMPLITUDE, frequency: FREQUENCY, speed: SPEED, steepness: STEEPNESS }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("Wave Bind Group Layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: settings_buffer.as_entire_binding(),
+            }],
+            label: Some("Wave Bind Group"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Wave Pipeline Layout"),
+            bind_group_layouts: &[scene_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Wave Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc(), instance_layout],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // the plane can be turned to show its underside, so both sides are drawn
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: render_target::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: depth_mode.compare(),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Wave {
+            pipeline,
+            settings_buffer,
+            bind_group,
+            amplitude: AMPLITUDE,
+            frequency: FREQUENCY,
+            speed: SPEED,
+            steepness: STEEPNESS,
+        }
+    }
+
+    // multiplies the setting by STEP, or divides it going down, within its range
+    pub fn step(&mut self, parameter: WaveParameter, up: bool) {
+        let (value, (low, high)) = match parameter {
+            WaveParameter::Amplitude => (&mut self.amplitude, AMPLITUDE_RANGE),
+            WaveParameter::Frequency => (&mut self.frequency, FREQUENCY_RANGE),
+            WaveParameter::Speed => (&mut self.speed, SPEED_RANGE),
+        };
+        *value = (if up { *value * STEP } else { *value / STEP }).clamp(low, high);
+    }
+
+    pub fn write(&self, queue: &wgpu::Queue) {
+        let settings = WaveSettings { amplitude: self.amplitude, frequency: self.frequency, speed: self.speed, steepness: self.steepness };
+        queue.write_buffer(&self.settings_buffer, 0, bytemuck::cast_slice(&[settings]));
+    }
+
+    // sets the pipeline and both bind groups, the caller binds the mesh and instances and draws
+    pub fn bind<'pass>(&'pass self, pass: &mut wgpu::RenderPass<'pass>, scene_bind_group: &'pass wgpu::BindGroup) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, scene_bind_group, &[]);
+        pass.set_bind_group(1, &self.bind_group, &[]);
+    }
+}
@@ -0,0 +1,146 @@
+// the scene's Uniforms and Lights from shader.wgsl, shared through its bind group
+struct Uniforms {
+    viewProjectionMatrix : mat4x4<f32>,
+    modelMatrix : mat4x4<f32>,
+    morphWeight : f32,
+    edgeWidth : f32,
+    fogDensity : f32,
+    fogEnabled : u32,
+    cameraPosition : vec4<f32>,
+    fogColor : vec4<f32>,
+    time : f32,
+};
+@binding(0) @group(0) var<uniform> uniforms : Uniforms;
+
+const MAX_LIGHTS : u32 = 16u;
+const AMBIENT : f32 = 0.15;
+
+struct Light {
+    position : vec3<f32>,
+    range : f32,
+    color : vec3<f32>,
+    intensity : f32,
+};
+
+struct Lights {
+    lights : array<Light, MAX_LIGHTS>,
+    count : u32,
+};
+@binding(1) @group(0) var<uniform> lights : Lights;
+
+struct WaveSettings {
+    // height of the first wave, the others are fractions of it
+    amplitude : f32,
+    // radians per unit of the first wave along its direction, the others are multiples of it
+    frequency : f32,
+    // units per second the crests move at
+    speed : f32,
+    // 0 for round sine crests, toward 1 for sharper gerstner crests
+    steepness : f32,
+};
+@binding(0) @group(1) var<uniform> wave : WaveSettings;
+
+struct Vertex {
+    @location(0) position : vec4<f32>,
+    @location(1) color : vec4<f32>,
+};
+
+struct Instance {
+    @location(8) model0 : vec4<f32>,
+    @location(9) model1 : vec4<f32>,
+    @location(10) model2 : vec4<f32>,
+    @location(11) model3 : vec4<f32>,
+};
+
+struct Output {
+    @builtin(position) Position : vec4<f32>,
+    @location(0) vColor : vec4<f32>,
+    @location(1) vWorldPosition : vec3<f32>,
+    @location(2) vNormal : vec3<f32>,
+};
+
+// the summed waves: a direction in the xz plane, a frequency and an amplitude relative to the
+// settings'. directions and frequencies that don't line up keep the sum from visibly repeating
+const WAVE_COUNT : u32 = 4u;
+const WAVE_DIRECTIONS = array<vec2<f32>, 4>(
+    vec2<f32>(1.0, 0.0),
+    vec2<f32>(0.6, 0.8),
+    vec2<f32>(-0.8, 0.6),
+    vec2<f32>(-0.28, -0.96),
+);
+const WAVE_FREQUENCIES = array<f32, 4>(1.0, 1.7, 2.9, 4.3);
+const WAVE_AMPLITUDES = array<f32, 4>(1.0, 0.5, 0.25, 0.12);
+
+fn normalMatrix(m : mat4x4<f32>) -> mat3x3<f32> {
+    let x = m[0].xyz;
+    let y = m[1].xyz;
+    let z = m[2].xyz;
+    return mat3x3<f32>(cross(y, z), cross(z, x), cross(x, y)) * sign(dot(x, cross(y, z)));
+}
+
+// gerstner waves over the plane y = 0: each wave lifts the point by a sine and pulls it toward its
+// crest along its direction, and the normal is the analytic one of the displaced surface. the pull of
+// each wave is limited so the sum never folds the surface over itself
+@vertex
+fn vs_main(vertex : Vertex, instance : Instance) -> Output {
+    let instanceMatrix = mat4x4<f32>(instance.model0, instance.model1, instance.model2, instance.model3);
+    let worldMatrix = uniforms.modelMatrix * instanceMatrix;
+    let rest = vertex.position.xz;
+    var directions = WAVE_DIRECTIONS;
+    var frequencies = WAVE_FREQUENCIES;
+    var amplitudes = WAVE_AMPLITUDES;
+
+    var position = vec3<f32>(rest.x, 0.0, rest.y);
+    var normal = vec3<f32>(0.0, 1.0, 0.0);
+    for (var i = 0u; i < WAVE_COUNT; i++) {
+        let direction = directions[i];
+        let k = wave.frequency * frequencies[i];
+        let a = wave.amplitude * amplitudes[i];
+        let phase = k * (dot(direction, rest) - wave.speed * uniforms.time);
+        let ka = k * a;
+        let q = select(0.0, min(wave.steepness, 1.0 / (ka * f32(WAVE_COUNT))), ka > 0.0);
+
+        position += vec3<f32>(direction.x * q * a * cos(phase), a * sin(phase), direction.y * q * a * cos(phase));
+        normal -= vec3<f32>(direction.x * ka * cos(phase), q * ka * sin(phase), direction.y * ka * cos(phase));
+    }
+
+    let world = worldMatrix * vec4<f32>(position, 1.0);
+    var output: Output;
+    output.Position = uniforms.viewProjectionMatrix * world;
+    output.vColor = vertex.color;
+    output.vWorldPosition = world.xyz;
+    output.vNormal = normalMatrix(worldMatrix) * normal;
+    return output;
+}
+
+fn applyFog(color : vec4<f32>, worldPosition : vec3<f32>) -> vec4<f32> {
+    if (uniforms.fogEnabled == 0u) {
+        return color;
+    }
+
+    let distance = length(worldPosition - uniforms.cameraPosition.xyz) * uniforms.fogDensity;
+    let fog = 1.0 - exp(-distance * distance);
+    return vec4<f32>(mix(color.rgb, uniforms.fogColor.rgb, fog), color.a);
+}
+
+// the point light loop of shader_lit.wgsl, without the textures
+@fragment
+fn fs_main(in : Output) -> @location(0) vec4<f32> {
+    if (lights.count == 0u) {
+        return applyFog(in.vColor, in.vWorldPosition);
+    }
+
+    let normal = normalize(in.vNormal);
+    var lighting = vec3<f32>(AMBIENT);
+
+    for (var i = 0u; i < min(lights.count, MAX_LIGHTS); i++) {
+        let light = lights.lights[i];
+        let toLight = light.position - in.vWorldPosition;
+        let distance = length(toLight);
+        let falloff = pow(saturate(1.0 - pow(distance / light.range, 2.0)), 2.0);
+        let diffuse = max(dot(normal, toLight / distance), 0.0);
+        lighting += light.color * light.intensity * diffuse * falloff;
+    }
+
+    return applyFog(vec4<f32>(in.vColor.rgb * lighting, in.vColor.a), in.vWorldPosition);
+}